@@ -0,0 +1,507 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::contract::SolCallBuilder;
+use alloy::network::{Ethereum, TxSigner};
+use alloy::primitives::{Address, U256};
+use alloy::providers::{Provider, RootProvider};
+use alloy::sol_types::SolCall;
+use anyhow::{anyhow, bail, Context};
+use kailua_contracts::*;
+use kailua_sync::args::parse_address;
+use kailua_sync::deployment::SyncDeployment;
+use kailua_sync::provider::optimism::fetch_rollup_config;
+use kailua_sync::provider::{failover_client, failover_provider};
+use kailua_sync::stall::Stall;
+use kailua_sync::telemetry::TelemetryArgs;
+use kailua_sync::transact::safe::{exec_safe_txn, SafeCosignerArgs};
+use kailua_sync::transact::signer::{GuardianSignerArgs, OwnerSignerArgs};
+use kailua_sync::transact::{Transact, TransactArgs};
+use kailua_sync::{await_tel, await_tel_res, KAILUA_GAME_TYPE};
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{FutureExt, Status, TraceContextExt, Tracer};
+use tracing::info;
+
+/// Change the parameters of a live Kailua deployment
+#[derive(clap::Args, Debug, Clone)]
+pub struct AdminArgs {
+    #[clap(subcommand)]
+    pub command: AdminCommand,
+
+    /// Address of the OP-NODE endpoint to use
+    #[clap(long, env)]
+    pub op_node_url: String,
+    /// Address of the OP-GETH endpoint to use (eth and debug namespace required).
+    #[clap(long, env)]
+    pub op_geth_url: String,
+    /// Comma-separated addresses of the ethereum rpc endpoints to use (eth namespace required)
+    #[clap(long, env)]
+    pub eth_rpc_url: String,
+    /// Whether to bypass loading rollup chain configurations from the kona registry
+    #[clap(long, env, default_value_t = false)]
+    pub bypass_chain_registry: bool,
+    /// Address of the KailuaGame implementation to administer (Default: latest in factory)
+    #[clap(long, env, value_parser = parse_address)]
+    pub kailua_game_implementation: Option<Address>,
+
+    /// Whether to only simulate the change against the live state without publishing it
+    #[clap(long, env, default_value_t = false)]
+    pub dry_run: bool,
+
+    /// Transaction publication configuration
+    #[clap(flatten)]
+    pub txn_args: TransactArgs,
+    /// Secret key of L1 wallet that (indirectly) owns `DisputeGameFactory`
+    #[clap(flatten)]
+    pub owner_signer: Option<OwnerSignerArgs>,
+    /// Signatures of the other owners of the `DisputeGameFactory` owner Safe
+    #[clap(flatten)]
+    pub safe_cosigners: SafeCosignerArgs,
+    /// Secret key of L1 guardian wallet
+    #[clap(flatten)]
+    pub guardian_signer: Option<GuardianSignerArgs>,
+
+    #[clap(flatten)]
+    pub telemetry: TelemetryArgs,
+}
+
+/// A privileged change to a Kailua deployment
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum AdminCommand {
+    /// Set the collateral (wei) that must be locked up by a sequencer to propose
+    SetParticipationBond {
+        #[clap(long)]
+        amount: U256,
+    },
+    /// Assign the vanguard proposer and the duration of its advantage
+    AssignVanguard {
+        #[clap(long, value_parser = parse_address)]
+        vanguard_address: Address,
+        #[clap(long)]
+        vanguard_advantage: u64,
+    },
+    /// Install a KailuaGame implementation in the DisputeGameFactory
+    SetImplementation {
+        #[clap(long, value_parser = parse_address)]
+        implementation: Address,
+    },
+    /// Set the bond required to create Kailua games through the DisputeGameFactory
+    SetInitBond {
+        #[clap(long)]
+        amount: U256,
+    },
+    /// Set the game type respected by the OptimismPortal for withdrawals
+    SetRespectedGameType {
+        #[clap(long, default_value_t = KAILUA_GAME_TYPE)]
+        game_type: u32,
+    },
+}
+
+/// The governance parameters of a Kailua deployment
+#[derive(Clone, Debug)]
+pub struct GovernanceState {
+    /// Address of the DisputeGameFactory contract
+    pub factory: Address,
+    /// Address of the OptimismPortal contract
+    pub portal: Address,
+    /// Parameters of the KailuaGame implementation
+    pub deployment: SyncDeployment,
+    /// Collateral required to propose
+    pub participation_bond: U256,
+    /// Address of the vanguard proposer
+    pub vanguard: Address,
+    /// Duration of the vanguard's advantage
+    pub vanguard_advantage: u64,
+    /// Bond required to create a Kailua game through the factory
+    pub init_bond: U256,
+    /// Game type respected by the portal
+    pub respected_game_type: u32,
+}
+
+impl GovernanceState {
+    pub async fn load<P: Provider>(
+        provider: &P,
+        factory: Address,
+        portal: Address,
+        game_impl_address: Option<Address>,
+    ) -> anyhow::Result<Self> {
+        let tracer = tracer("kailua");
+        let context =
+            opentelemetry::Context::current_with_span(tracer.start("GovernanceState::load"));
+
+        let dispute_game_factory = IDisputeGameFactory::new(factory, provider);
+        let game_impl_address = match game_impl_address {
+            Some(address) => address,
+            None => {
+                dispute_game_factory
+                    .gameImpls(KAILUA_GAME_TYPE)
+                    .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
+                    .await
+            }
+        };
+        if game_impl_address.is_zero() {
+            bail!("Kailua is not installed in DisputeGameFactory {factory}.");
+        }
+        let deployment = await_tel!(
+            context,
+            SyncDeployment::load_from_l1(provider, game_impl_address)
+        )
+        .context("SyncDeployment::load_from_l1")?;
+
+        let kailua_treasury = KailuaTreasury::new(deployment.treasury, provider);
+        let participation_bond = kailua_treasury
+            .participationBond()
+            .stall_with_context(context.clone(), "KailuaTreasury::participationBond")
+            .await;
+        let vanguard = kailua_treasury
+            .vanguard()
+            .stall_with_context(context.clone(), "KailuaTreasury::vanguard")
+            .await;
+        let vanguard_advantage = kailua_treasury
+            .vanguardAdvantage()
+            .stall_with_context(context.clone(), "KailuaTreasury::vanguardAdvantage")
+            .await;
+        let init_bond = dispute_game_factory
+            .initBonds(KAILUA_GAME_TYPE)
+            .stall_with_context(context.clone(), "DisputeGameFactory::initBonds")
+            .await;
+        let respected_game_type = OptimismPortal2::new(portal, provider)
+            .respectedGameType()
+            .stall_with_context(context.clone(), "OptimismPortal2::respectedGameType")
+            .await;
+
+        Ok(Self {
+            factory,
+            portal,
+            deployment,
+            participation_bond,
+            vanguard,
+            vanguard_advantage,
+            init_bond,
+            respected_game_type,
+        })
+    }
+
+    /// Lists the name and value of each parameter
+    pub fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("GAME_IMPLEMENTATION", self.deployment.game.to_string()),
+            ("TREASURY", self.deployment.treasury.to_string()),
+            ("VERIFIER", self.deployment.verifier.to_string()),
+            ("FPVM_IMAGE_ID", self.deployment.image_id.to_string()),
+            ("ROLLUP_CONFIG_HASH", self.deployment.cfg_hash.to_string()),
+            (
+                "PROPOSAL_OUTPUT_COUNT",
+                self.deployment.proposal_output_count.to_string(),
+            ),
+            (
+                "OUTPUT_BLOCK_SPAN",
+                self.deployment.output_block_span.to_string(),
+            ),
+            ("PROPOSAL_BLOBS", self.deployment.proposal_blobs.to_string()),
+            ("GAME_TYPE", self.deployment.game_type.to_string()),
+            ("MAX_CLOCK_DURATION", self.deployment.timeout.to_string()),
+            (
+                "GENESIS_TIME_STAMP",
+                self.deployment.genesis_time.to_string(),
+            ),
+            ("L2_BLOCK_TIME", self.deployment.block_time.to_string()),
+            ("PARTICIPATION_BOND", self.participation_bond.to_string()),
+            ("VANGUARD", self.vanguard.to_string()),
+            ("VANGUARD_ADVANTAGE", self.vanguard_advantage.to_string()),
+            ("INIT_BOND", self.init_bond.to_string()),
+            ("RESPECTED_GAME_TYPE", self.respected_game_type.to_string()),
+        ]
+    }
+}
+
+/// Prints every parameter, marking those that differ between both states
+pub fn print_diff(before: &GovernanceState, after: &GovernanceState) {
+    for ((name, old), (_, new)) in before.parameters().into_iter().zip(after.parameters()) {
+        if old == new {
+            println!("  {name}: {old}");
+        } else {
+            println!("* {name}: {old} -> {new}");
+        }
+    }
+}
+
+pub async fn admin(args: AdminArgs) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("admin"));
+
+    // fetch rollup config
+    let config = await_tel!(
+        context,
+        fetch_rollup_config(
+            &args.op_node_url,
+            &args.op_geth_url,
+            None,
+            args.bypass_chain_registry
+        )
+    )
+    .context("fetch_rollup_config")?;
+    let eth_rpc_provider = failover_provider(&args.eth_rpc_url)?;
+
+    // load the current governance state
+    let system_config = SystemConfig::new(config.l1_system_config_address, &eth_rpc_provider);
+    let portal_address = system_config
+        .optimismPortal()
+        .stall_with_context(context.clone(), "SystemConfig::optimismPortal")
+        .await;
+    let dgf_address = system_config
+        .disputeGameFactory()
+        .stall_with_context(context.clone(), "SystemConfig::disputeGameFactory")
+        .await;
+    let before = await_tel_res!(
+        context,
+        GovernanceState::load(
+            &eth_rpc_provider,
+            dgf_address,
+            portal_address,
+            args.kailua_game_implementation
+        ),
+        "GovernanceState::load"
+    )?;
+    let factory_owner_address = OwnableUpgradeable::new(dgf_address, &eth_rpc_provider)
+        .owner()
+        .stall_with_context(context.clone(), "DisputeGameFactory::owner")
+        .await;
+    info!("DisputeGameFactory owner: {factory_owner_address}");
+
+    // plan, simulate and apply the change
+    let kailua_treasury = KailuaTreasury::new(before.deployment.treasury, &eth_rpc_provider);
+    let dispute_game_factory = IDisputeGameFactory::new(dgf_address, &eth_rpc_provider);
+    let mut expected = before.clone();
+    match args.command.clone() {
+        AdminCommand::SetParticipationBond { amount } => {
+            expected.participation_bond = amount;
+            await_tel!(
+                context,
+                apply_safe_change(
+                    &args,
+                    config.l1_chain_id,
+                    kailua_treasury.setParticipationBond(amount),
+                    "KailuaTreasury::setParticipationBond",
+                    factory_owner_address,
+                    &before,
+                    &expected,
+                )
+            )?;
+        }
+        AdminCommand::AssignVanguard {
+            vanguard_address,
+            vanguard_advantage,
+        } => {
+            expected.vanguard = vanguard_address;
+            expected.vanguard_advantage = vanguard_advantage;
+            await_tel!(
+                context,
+                apply_safe_change(
+                    &args,
+                    config.l1_chain_id,
+                    kailua_treasury.assignVanguard(vanguard_address, vanguard_advantage),
+                    "KailuaTreasury::assignVanguard",
+                    factory_owner_address,
+                    &before,
+                    &expected,
+                )
+            )?;
+        }
+        AdminCommand::SetImplementation { implementation } => {
+            expected = await_tel_res!(
+                context,
+                GovernanceState::load(
+                    &eth_rpc_provider,
+                    dgf_address,
+                    portal_address,
+                    Some(implementation)
+                ),
+                "GovernanceState::load"
+            )?;
+            await_tel!(
+                context,
+                apply_safe_change(
+                    &args,
+                    config.l1_chain_id,
+                    dispute_game_factory.setImplementation(KAILUA_GAME_TYPE, implementation),
+                    "DisputeGameFactory::setImplementation",
+                    factory_owner_address,
+                    &before,
+                    &expected,
+                )
+            )?;
+        }
+        AdminCommand::SetInitBond { amount } => {
+            expected.init_bond = amount;
+            await_tel!(
+                context,
+                apply_safe_change(
+                    &args,
+                    config.l1_chain_id,
+                    dispute_game_factory.setInitBond(KAILUA_GAME_TYPE, amount),
+                    "DisputeGameFactory::setInitBond",
+                    factory_owner_address,
+                    &before,
+                    &expected,
+                )
+            )?;
+        }
+        AdminCommand::SetRespectedGameType { game_type } => {
+            expected.respected_game_type = game_type;
+            let optimism_portal = OptimismPortal2::new(portal_address, &eth_rpc_provider);
+            let guardian_address = optimism_portal
+                .guardian()
+                .stall_with_context(context.clone(), "OptimismPortal2::guardian")
+                .await;
+            await_tel_res!(
+                context,
+                simulate(
+                    optimism_portal.setRespectedGameType(game_type),
+                    "OptimismPortal2::setRespectedGameType",
+                    guardian_address,
+                ),
+                "simulate"
+            )?;
+            print_diff(&before, &expected);
+            if !args.dry_run {
+                // initialize guardian wallet
+                info!("Initializing guardian wallet.");
+                let guardian_wallet = await_tel_res!(
+                    context,
+                    tracer,
+                    "GuardianSignerArgs::wallet",
+                    args.guardian_signer
+                        .as_ref()
+                        .ok_or_else(|| anyhow!("Guardian signer not provided"))?
+                        .wallet(Some(config.l1_chain_id))
+                )?;
+                if guardian_wallet.default_signer().address() != guardian_address {
+                    bail!(
+                        "OptimismPortal2 Guardian is {guardian_address}. Provided key has account address {}.",
+                        guardian_wallet.default_signer().address()
+                    );
+                }
+                let guardian_provider = args
+                    .txn_args
+                    .premium_provider::<Ethereum>()
+                    .wallet(&guardian_wallet)
+                    .connect_client(failover_client(&args.eth_rpc_url)?);
+                let receipt = OptimismPortal2::new(portal_address, &guardian_provider)
+                    .setRespectedGameType(game_type)
+                    .transact_with_context(context.clone(), "OptimismPortal2::setRespectedGameType")
+                    .await
+                    .context("OptimismPortal2::setRespectedGameType")?;
+                info!(
+                    "OptimismPortal2::setRespectedGameType: {} gas",
+                    receipt.gas_used
+                );
+            }
+        }
+    }
+    if args.dry_run {
+        info!("Simulation complete. No transactions were published.");
+        context.span().set_status(Status::Ok);
+        return Ok(());
+    }
+
+    // report the resulting state
+    let after = await_tel_res!(
+        context,
+        GovernanceState::load(
+            &eth_rpc_provider,
+            dgf_address,
+            portal_address,
+            Some(expected.deployment.game)
+        ),
+        "GovernanceState::load"
+    )?;
+    println!("APPLIED:");
+    print_diff(&before, &after);
+    context.span().set_status(Status::Ok);
+    Ok(())
+}
+
+/// Executes the call against the latest state as the given sender without publishing it
+pub async fn simulate<C: SolCall>(
+    call: SolCallBuilder<&RootProvider, C>,
+    span: &'static str,
+    from: Address,
+) -> anyhow::Result<SolCallBuilder<&RootProvider, C>> {
+    let call = call.from(from);
+    call.call_raw()
+        .await
+        .with_context(|| format!("Simulation of {span} from {from} failed"))?;
+    info!("Simulation of {span} from {from} succeeded.");
+    Ok(call)
+}
+
+/// Simulates the change as the factory owner Safe and executes it through the Safe unless this is
+/// a dry run
+pub async fn apply_safe_change<C: SolCall>(
+    args: &AdminArgs,
+    chain_id: u64,
+    call: SolCallBuilder<&RootProvider, C>,
+    span: &'static str,
+    factory_owner_address: Address,
+    before: &GovernanceState,
+    expected: &GovernanceState,
+) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("apply_safe_change"));
+
+    let call = await_tel_res!(
+        context,
+        simulate(call, span, factory_owner_address),
+        "simulate"
+    )?;
+    println!("EXPECTED:");
+    print_diff(before, expected);
+    if args.dry_run {
+        return Ok(());
+    }
+
+    // initialize owner wallet
+    info!("Initializing owner wallet.");
+    let owner_wallet = await_tel_res!(
+        context,
+        tracer,
+        "OwnerSignerArgs::wallet",
+        args.owner_signer
+            .as_ref()
+            .ok_or_else(|| anyhow!("Owner signer not provided"))?
+            .wallet(Some(chain_id))
+    )?;
+    let owner_address = owner_wallet.default_signer().address();
+    let owner_provider = args
+        .txn_args
+        .premium_provider::<Ethereum>()
+        .wallet(&owner_wallet)
+        .connect_client(failover_client(&args.eth_rpc_url)?);
+    let factory_owner_safe = Safe::new(factory_owner_address, &owner_provider);
+    let safe_cosigners = await_tel_res!(
+        context,
+        tracer,
+        "SafeCosignerArgs::cosigners",
+        args.safe_cosigners.cosigners(Some(chain_id))
+    )?;
+    await_tel_res!(
+        context,
+        tracer,
+        "exec_safe_txn",
+        exec_safe_txn(call, &factory_owner_safe, owner_address, &safe_cosigners)
+    )?;
+    info!("{span} executed.");
+    Ok(())
+}
@@ -15,6 +15,7 @@
 use alloy::primitives::map::{Entry, HashMap};
 use alloy::providers::{Provider, ProviderBuilder};
 use kailua_sync::args::SyncArgs;
+use kailua_sync::provider::{failover_client, primary_url};
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt, Span, Status, TraceContextExt, Tracer};
 use std::cmp::Ordering;
@@ -67,7 +68,7 @@ pub async fn benchmark(args: BenchArgs, verbosity: u8) -> anyhow::Result<()> {
     let context = opentelemetry::Context::current_with_span(tracer.start("benchmark"));
 
     let l2_node_provider =
-        ProviderBuilder::new().connect_http(args.sync.provider.op_geth_url.as_str().try_into()?);
+        ProviderBuilder::new().connect_client(failover_client(&args.sync.provider.op_geth_url)?);
     let mut cache: HashMap<u64, u64> = HashMap::new();
     // Scan L2 blocks for highest transaction counts
     let bench_end = args.bench_start + args.bench_range;
@@ -137,10 +138,10 @@ pub async fn benchmark(args: BenchArgs, verbosity: u8) -> anyhow::Result<()> {
             "prove",
             &block_number,
             &block_count,
-            &args.sync.provider.eth_rpc_url,
-            &args.sync.provider.beacon_rpc_url,
-            &args.sync.provider.op_geth_url,
-            &args.sync.provider.op_node_url,
+            primary_url(&args.sync.provider.eth_rpc_url),
+            primary_url(&args.sync.provider.beacon_rpc_url),
+            primary_url(&args.sync.provider.op_geth_url),
+            primary_url(&args.sync.provider.op_node_url),
             data_dir.to_str().unwrap(),
             "debug",
             &verbosity_level,
@@ -12,34 +12,74 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use anyhow::{anyhow, Context};
+use crate::proof::{detect_image, read_manifest, store_recovered_proof};
+use anyhow::{anyhow, bail, Context};
 use bonsai_sdk::non_blocking::{Client, SessionId};
-use kailua_build::KAILUA_FPVM_KONA_ID;
-use kailua_prover::proof::save_to_bincoded_file;
-use kailua_prover::proof::{proof_file_name, read_bincoded_file};
 use kailua_prover::risczero::{KailuaProveInfo, KailuaSessionStats};
+use kailua_prover::store::{ProofStore, ProofStoreArgs};
 use kailua_prover::ProvingError;
 use kailua_sync::telemetry::TelemetryArgs;
 use risc0_zkvm::Receipt;
+use std::path::PathBuf;
 use std::time::Duration;
 use tracing::{error, info, warn};
 
 #[derive(clap::Args, Debug, Clone)]
 pub struct BonsaiArgs {
+    /// Comma-separated ids of the Bonsai sessions to recover proofs from
+    #[clap(long, env, value_delimiter = ',')]
+    pub session_id: Vec<String>,
+    /// Path of a file listing additional session ids, one per line
     #[clap(long, env)]
-    pub session_id: String,
+    pub manifest: Option<PathBuf>,
+    #[clap(flatten)]
+    pub proof_store: ProofStoreArgs,
     #[clap(flatten)]
     pub telemetry: TelemetryArgs,
 }
 
 pub async fn bonsai(args: BonsaiArgs) -> anyhow::Result<()> {
+    let mut session_ids = args.session_id.clone();
+    if let Some(manifest) = &args.manifest {
+        session_ids.extend(read_manifest(manifest).await?);
+    }
+    if session_ids.is_empty() {
+        bail!("No session ids provided.");
+    }
+
     // Instantiate client
     let client = Client::from_env(risc0_zkvm::VERSION)?;
+    let store = ProofStore::new(&args.proof_store)
+        .await
+        .context("ProofStore::new")?;
+
+    let mut failures = 0;
+    for session_id in &session_ids {
+        if let Err(err) = recover_session(&client, &store, session_id).await {
+            error!("Failed to recover Bonsai session {session_id}: {err:?}");
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        bail!(
+            "Failed to recover {failures} of {} Bonsai sessions.",
+            session_ids.len()
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn recover_session(
+    client: &Client,
+    store: &ProofStore,
+    session_id: &str,
+) -> anyhow::Result<()> {
     // fetch session
-    let session = SessionId::new(args.session_id);
+    let session = SessionId::new(session_id.to_string());
     // wait for receipt
     let kailua_prove_info = loop {
-        let res = session.status(&client).await?;
+        let res = session.status(client).await?;
 
         if res.status == "RUNNING" {
             tokio::time::sleep(Duration::from_secs(1)).await;
@@ -48,14 +88,13 @@ pub async fn bonsai(args: BonsaiArgs) -> anyhow::Result<()> {
         }
 
         if res.status != "SUCCEEDED" {
-            error!(
+            bail!(
                 "Bonsai prover workflow [{}] exited: {} err: {}",
                 session.uuid,
                 res.status,
                 res.error_msg
                     .unwrap_or("Bonsai workflow missing error_msg".into()),
             );
-            return Ok(());
         }
 
         // Download the receipt, containing the output
@@ -75,9 +114,6 @@ pub async fn bonsai(args: BonsaiArgs) -> anyhow::Result<()> {
         let receipt_buf = client.download(&receipt_url).await?;
         let receipt: Receipt = bincode::deserialize(&receipt_buf)?;
 
-        info!("Verifying receipt received from Bonsai.");
-        receipt.verify(KAILUA_FPVM_KONA_ID)?;
-
         break KailuaProveInfo {
             receipt,
             stats: KailuaSessionStats {
@@ -91,25 +127,10 @@ pub async fn bonsai(args: BonsaiArgs) -> anyhow::Result<()> {
         };
     };
 
-    let file_name = proof_file_name(
-        KAILUA_FPVM_KONA_ID,
-        kailua_prove_info.receipt.journal.clone(),
-    );
-
-    info!("Writing proof to {file_name}.");
-    if let Ok(prior_receipt) = read_bincoded_file::<Receipt>(&file_name).await {
-        if prior_receipt.verify(KAILUA_FPVM_KONA_ID).is_ok() {
-            info!("Skipping overwriting valid receipt file.");
-            return Ok(());
-        }
-        info!("Overwriting invalid receipt file.");
-    }
-
-    if let Err(err) = save_to_bincoded_file(&kailua_prove_info.receipt, &file_name).await {
-        error!("Failed to write proof to {file_name}: {err:?}");
-    }
-
-    info!("Proof written to {file_name}.");
+    let (label, image_id) = detect_image(&kailua_prove_info.receipt)
+        .ok_or_else(|| anyhow!("Receipt of session {session_id} is not of a known image."))?;
+    info!("Verifying {label} receipt received from Bonsai.");
+    store_recovered_proof(store, &kailua_prove_info.receipt, label, image_id).await?;
 
     Ok(())
 }
@@ -12,18 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloy::primitives::U256;
+use crate::proof::{image_by_hash, read_manifest, store_recovered_proof};
+use alloy::primitives::{B256, U256};
 use alloy::signers::local::PrivateKeySigner;
 use alloy::transports::http::reqwest::Url;
-use anyhow::Context;
+use anyhow::{anyhow, bail, Context};
 use boundless_market::{Client, StandardStorageProvider, StorageProviderConfig};
-use kailua_kona::journal::ProofJournal;
-use kailua_prover::proof::{proof_file_name, read_bincoded_file, save_to_bincoded_file};
 use kailua_prover::risczero::boundless::retrieve_proof;
+use kailua_prover::store::{ProofStore, ProofStoreArgs};
 use kailua_sync::retry_res_timeout;
 use kailua_sync::telemetry::TelemetryArgs;
 use kailua_validator::proposals::dispatch::current_time;
-use risc0_zkvm::Receipt;
+use std::path::PathBuf;
 use std::str::FromStr;
 use tracing::{error, info};
 
@@ -33,13 +33,27 @@ pub struct BoundlessArgs {
     #[clap(long, env, required = false)]
     pub boundless_rpc_url: Url,
 
+    /// Comma-separated ids of the Boundless requests to recover proofs from
+    #[clap(long, env, value_delimiter = ',')]
+    pub request_id: Vec<String>,
+    /// Path of a file listing additional request ids, one per line
     #[clap(long, env)]
-    pub request_id: String,
+    pub manifest: Option<PathBuf>,
+    #[clap(flatten)]
+    pub proof_store: ProofStoreArgs,
     #[clap(flatten)]
     pub telemetry: TelemetryArgs,
 }
 
 pub async fn boundless(args: BoundlessArgs) -> anyhow::Result<()> {
+    let mut request_ids = args.request_id.clone();
+    if let Some(manifest) = &args.manifest {
+        request_ids.extend(read_manifest(manifest).await?);
+    }
+    if request_ids.is_empty() {
+        bail!("No request ids provided.");
+    }
+
     let boundless_client = retry_res_timeout!(
         15,
         Client::builder()
@@ -53,39 +67,50 @@ pub async fn boundless(args: BoundlessArgs) -> anyhow::Result<()> {
             .context("ClientBuilder::build()")
     )
     .await;
+    let store = ProofStore::new(&args.proof_store)
+        .await
+        .context("ProofStore::new")?;
 
-    let (request, _) = boundless_client
-        .fetch_proof_request(U256::from_str(args.request_id.as_str())?, None, None)
-        .await?;
-
-    let image_id = request.requirements.imageId.0;
+    let mut failures = 0;
+    for request_id in &request_ids {
+        if let Err(err) = recover_request(&boundless_client, &store, request_id).await {
+            error!("Failed to recover Boundless request {request_id}: {err:?}");
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        bail!(
+            "Failed to recover {failures} of {} Boundless requests.",
+            request_ids.len()
+        );
+    }
 
-    let receipt = retrieve_proof(
-        &boundless_client,
-        U256::from_str(args.request_id.as_str())?,
-        image_id,
-        12,
-        current_time(),
-    )
-    .await?;
+    Ok(())
+}
 
-    let proof_journal = ProofJournal::decode_packed(receipt.journal.as_ref());
-    let file_name = proof_file_name(image_id, &proof_journal);
+pub async fn recover_request(
+    boundless_client: &Client,
+    store: &ProofStore,
+    request_id: &str,
+) -> anyhow::Result<()> {
+    let request_id = U256::from_str(request_id).context("U256::from_str")?;
+    let (request, _) = boundless_client
+        .fetch_proof_request(request_id, None, None)
+        .await?;
 
-    info!("Writing proof to {file_name}.");
-    if let Ok(prior_receipt) = read_bincoded_file::<Receipt>(&file_name).await {
-        if prior_receipt.verify(image_id).is_ok() {
-            info!("Skipping overwriting valid receipt file.");
-            return Ok(());
-        }
-        info!("Overwriting invalid receipt file.");
-    }
+    let (label, image_id) =
+        image_by_hash(B256::from(request.requirements.imageId.0)).ok_or_else(|| {
+            anyhow!(
+                "Request 0x{request_id:x} is for unknown image {}.",
+                request.requirements.imageId
+            )
+        })?;
+    info!("Recovering {label} proof of request 0x{request_id:x}.");
 
-    if let Err(err) = save_to_bincoded_file(&receipt, &file_name).await {
-        error!("Failed to write proof to {file_name}: {err:?}");
-    }
+    let receipt =
+        retrieve_proof(boundless_client, request_id, image_id, 12, current_time()).await?;
 
-    info!("Proof written to {file_name}.");
+    store_recovered_proof(store, &receipt, label, image_id).await?;
 
     Ok(())
 }
@@ -97,9 +97,16 @@ pub async fn demo(args: DemoArgs, verbosity: u8, data_dir: PathBuf) -> anyhow::R
         fast_forward_start: 0,
         fast_forward_target: 0,
         num_concurrent_provers: args.num_concurrent_provers,
+        coordinator: Default::default(),
         enable_experimental_witness_endpoint: args.enable_experimental_witness_endpoint,
         max_fault_proving_delay: 0,
         max_validity_proving_delay: 0,
+        proving_policy: Default::default(),
+        watchdog: Default::default(),
+        watch_only: false,
+        fault_report_dir: None,
+        export_bundle_dir: None,
+        bundle_sender: None,
         #[cfg(feature = "devnet")]
         l1_head_jump_back: 0,
         validator_signer: ValidatorSignerArgs {
@@ -112,9 +119,14 @@ pub async fn demo(args: DemoArgs, verbosity: u8, data_dir: PathBuf) -> anyhow::R
             txn_timeout: 0,
             exec_gas_premium: 0,
             blob_gas_premium: 0,
+            txn_max_fee_bumps: 3,
+            txn_fee_bump: 25,
+            txn_max_fee_per_gas: None,
+            txn_max_fee_per_blob_gas: None,
         },
         proving: args.proving,
         boundless: args.boundless,
+        bonds: Default::default(),
     };
     let handle_proof_requests = spawn(
         kailua_validator::requests::handle_proof_requests(
@@ -122,6 +134,7 @@ pub async fn demo(args: DemoArgs, verbosity: u8, data_dir: PathBuf) -> anyhow::R
             validate_args,
             verbosity,
             data_dir,
+            None,
         )
         .with_context(context.clone()),
     );
@@ -0,0 +1,100 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Context;
+use human_bytes::human_bytes;
+use kailua_prover::args::ProveArgs;
+use kailua_prover::estimate::{estimate as estimate_proofs, projected_price};
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use std::time::Instant;
+use tracing::warn;
+
+/// Estimate the cost of proving an L2 block range without generating any proofs
+#[derive(clap::Args, Debug, Clone)]
+pub struct EstimateArgs {
+    #[clap(flatten)]
+    pub prove: ProveArgs,
+}
+
+pub async fn estimate(args: EstimateArgs) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("estimate"));
+
+    let boundless = args.prove.boundless.clone();
+    let start = Instant::now();
+    let estimates = estimate_proofs(args.prove)
+        .with_context(context.clone())
+        .await
+        .context("estimate")?;
+    let elapsed = start.elapsed();
+    if estimates.is_empty() {
+        warn!("No proofs were executed. Proofs of this range may already be stored.");
+        return Ok(());
+    }
+
+    // Report each proof that would be requested
+    for estimate in &estimates {
+        let (min_price, max_price) = projected_price(estimate.total_cycles, &boundless);
+        println!("PROOF: {}", estimate.proof_id);
+        if let Some(claimed_l2_block_number) = estimate.claimed_l2_block_number {
+            println!("  claimed_l2_block_number: {claimed_l2_block_number}");
+        }
+        println!("  segments: {}", estimate.segments);
+        println!("  total_cycles: {}", estimate.total_cycles);
+        println!("  user_cycles: {}", estimate.user_cycles);
+        println!(
+            "  witness_size: {}",
+            human_bytes(estimate.witness_size as f64)
+        );
+        println!("  stitched_proofs: {}", estimate.stitched_proofs);
+        println!("  boundless_price: {min_price} - {max_price} wei");
+        println!("  recorded: {}", estimate.recorded);
+    }
+
+    // Report totals
+    let segments = estimates.iter().map(|e| e.segments).sum::<usize>();
+    let total_cycles = estimates.iter().map(|e| e.total_cycles).sum::<u64>();
+    let user_cycles = estimates.iter().map(|e| e.user_cycles).sum::<u64>();
+    let witness_size = estimates.iter().map(|e| e.witness_size).sum::<usize>();
+    let max_cycles = estimates
+        .iter()
+        .map(|e| e.total_cycles)
+        .max()
+        .unwrap_or_default();
+    let (min_price, max_price) = projected_price(total_cycles, &boundless);
+    println!("TOTAL:");
+    println!("  proofs: {}", estimates.len());
+    println!("  segments: {segments}");
+    println!("  total_cycles: {total_cycles}");
+    println!("  user_cycles: {user_cycles}");
+    println!("  witness_size: {}", human_bytes(witness_size as f64));
+    println!("  boundless_price: {min_price} - {max_price} wei");
+    println!("  elapsed: {} seconds", elapsed.as_secs());
+    println!("  max_proof_cycles: {max_cycles}");
+    // Boundless reads the recorded cycle counts instead of preflighting these exact proofs
+    let recorded = estimates.iter().filter(|e| e.recorded).count();
+    println!(
+        "Recorded cycle counts for Boundless requests of {recorded}/{} proofs.",
+        estimates.len()
+    );
+    if estimates.len() > 1 {
+        // The stitching proof verifies the receipts of the proofs above, which are never produced
+        println!(
+            "The totals exclude the derivation proof stitching these proofs together, which can not be executed without their receipts."
+        );
+    }
+
+    Ok(())
+}
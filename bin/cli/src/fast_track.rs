@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloy::network::{Ethereum, Network, ReceiptResponse, TxSigner};
+use crate::admin::GovernanceState;
+use alloy::contract::SolCallBuilder;
+use alloy::eips::BlockId;
+use alloy::network::{Ethereum, Network, ReceiptResponse, TransactionBuilder, TxSigner};
+use alloy::node_bindings::Anvil;
 use alloy::primitives::{Address, Bytes, B256, U256};
 use alloy::providers::{Provider, RootProvider};
 use alloy::sol_types::SolValue;
@@ -24,7 +28,7 @@ use kailua_sync::provider::optimism::fetch_rollup_config;
 use kailua_sync::provider::optimism::OpNodeProvider;
 use kailua_sync::stall::Stall;
 use kailua_sync::telemetry::TelemetryArgs;
-use kailua_sync::transact::safe::exec_safe_txn;
+use kailua_sync::transact::safe::{exec_safe_txn, SafeCosignerArgs, SafeCosigners};
 use kailua_sync::transact::signer::{DeployerSignerArgs, GuardianSignerArgs, OwnerSignerArgs};
 use kailua_sync::transact::{Transact, TransactArgs};
 use kailua_sync::{await_tel, await_tel_res, retry_res_ctx_timeout, KAILUA_GAME_TYPE};
@@ -81,6 +85,12 @@ pub struct FastTrackArgs {
     /// Secret key of L1 wallet that (indirectly) owns `DisputeGameFactory`
     #[clap(flatten)]
     pub owner_signer: OwnerSignerArgs,
+    /// Signatures of the other owners of the `DisputeGameFactory` owner Safe.
+    ///
+    /// Each run redeploys the contracts and changes every Safe transaction hash, so the remaining
+    /// owners must sign on demand through `--safe-cosigner-*` keys instead of signature files.
+    #[clap(flatten)]
+    pub safe_cosigners: SafeCosignerArgs,
     /// Secret key of L1 guardian wallet
     #[clap(flatten)]
     pub guardian_signer: Option<GuardianSignerArgs>,
@@ -96,6 +106,10 @@ pub struct FastTrackArgs {
     #[clap(long, env)]
     pub respect_kailua_proposals: bool,
 
+    /// Whether to only run the migration against a local anvil fork of the L1 without publishing it
+    #[clap(long, env, default_value_t = false)]
+    pub dry_run: bool,
+
     #[clap(flatten)]
     pub telemetry: TelemetryArgs,
 }
@@ -107,8 +121,34 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
     let op_node_provider = OpNodeProvider(RootProvider::new_http(
         args.op_node_url.as_str().try_into()?,
     ));
-    let eth_rpc_provider =
-        RootProvider::<Ethereum>::new_http(args.eth_rpc_url.as_str().try_into()?);
+
+    // fork the l1 locally to simulate the migration
+    let anvil = if args.dry_run {
+        info!("Forking L1 rpc using anvil.");
+        Some(
+            Anvil::new()
+                .fork(args.eth_rpc_url.clone())
+                .try_spawn()
+                .context("Anvil::try_spawn")?,
+        )
+    } else {
+        None
+    };
+    let eth_rpc_url = anvil
+        .as_ref()
+        .map(|anvil| anvil.endpoint())
+        .unwrap_or_else(|| args.eth_rpc_url.clone());
+    let eth_rpc_provider = RootProvider::<Ethereum>::new_http(eth_rpc_url.as_str().try_into()?);
+    let fork_provider = anvil.as_ref().map(|_| &eth_rpc_provider);
+    let fork_block = match fork_provider {
+        Some(fork_provider) => await_tel_res!(
+            context,
+            tracer,
+            "Provider::get_block_number",
+            fork_provider.get_block_number()
+        )?,
+        None => 0,
+    };
 
     info!("Fetching rollup configuration from rpc endpoints.");
     // fetch rollup config
@@ -148,7 +188,7 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
         .txn_args
         .premium_provider::<Ethereum>()
         .wallet(&owner_wallet)
-        .connect_http(args.eth_rpc_url.as_str().try_into()?);
+        .connect_http(eth_rpc_url.as_str().try_into()?);
 
     // Init factory contract
     let dispute_game_factory = IDisputeGameFactory::new(dgf_address, &owner_provider);
@@ -170,12 +210,40 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
         .stall_with_context(context.clone(), "Safe::getOwners")
         .await;
     info!("Safe::owners({:?})", &safe_owners);
+    let safe_threshold = factory_owner_safe
+        .getThreshold()
+        .stall_with_context(context.clone(), "Safe::getThreshold")
+        .await;
+    info!("Safe::threshold({safe_threshold})");
     let owner_address = owner_wallet.default_signer().address();
-    if safe_owners.first().unwrap() != &owner_address {
+    if !safe_owners.contains(&owner_address) {
         bail!("Incorrect owner key.");
-    } else if safe_owners.len() != 1 {
-        bail!("Expected exactly one owner of safe account.");
     }
+    if let Some(fork_provider) = fork_provider {
+        // act as the safe on the fork regardless of its threshold
+        await_tel_res!(
+            context,
+            tracer,
+            "anvil_impersonateAccount",
+            fork_provider
+                .raw_request::<_, ()>("anvil_impersonateAccount".into(), (factory_owner_address,))
+        )?;
+        await_tel_res!(
+            context,
+            tracer,
+            "anvil_setBalance",
+            fork_provider.raw_request::<_, ()>(
+                "anvil_setBalance".into(),
+                (factory_owner_address, U256::from(10).pow(U256::from(21)))
+            )
+        )?;
+    }
+    let safe_cosigners = await_tel_res!(
+        context,
+        tracer,
+        "SafeCosignerArgs::cosigners",
+        args.safe_cosigners.cosigners(Some(config.l1_chain_id))
+    )?;
 
     // initialize deployment wallet
     info!("Initializing deployer wallet.");
@@ -189,7 +257,7 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
         .txn_args
         .premium_provider::<Ethereum>()
         .wallet(&deployer_wallet)
-        .connect_http(args.eth_rpc_url.as_str().try_into()?);
+        .connect_http(eth_rpc_url.as_str().try_into()?);
 
     // Deploy or reuse existing RISCZeroVerifier contracts
     let verifier_contract_address = match &args.verifier_contract {
@@ -246,6 +314,8 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
             dispute_game_factory.setImplementation(KAILUA_GAME_TYPE, kailua_treasury_impl_addr),
             &factory_owner_safe,
             owner_address,
+            &safe_cosigners,
+            fork_provider,
         )
     )?;
     assert_eq!(
@@ -267,10 +337,12 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
             context,
             tracer,
             "DisputeGameFactory::setInitBond",
-            exec_safe_txn(
+            exec_owner_txn(
                 dispute_game_factory.setInitBond(KAILUA_GAME_TYPE, U256::ZERO),
                 &factory_owner_safe,
                 owner_address,
+                &safe_cosigners,
+                fork_provider,
             )
         )?;
         assert_eq!(
@@ -324,10 +396,12 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
             context,
             tracer,
             "KailuaTreasury::resolve",
-            exec_safe_txn(
+            exec_owner_txn(
                 kailua_treasury_instance.resolve(),
                 &factory_owner_safe,
                 owner_address,
+                &safe_cosigners,
+                fork_provider,
             )
         )?;
     } else {
@@ -348,6 +422,8 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
             kailua_treasury_implementation.setParticipationBond(bond_value),
             &factory_owner_safe,
             owner_address,
+            &safe_cosigners,
+            fork_provider,
         )
     )?;
     assert_eq!(
@@ -390,6 +466,8 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
                 .setImplementation(KAILUA_GAME_TYPE, *kailua_game_contract.address()),
             &factory_owner_safe,
             owner_address,
+            &safe_cosigners,
+            fork_provider,
         )
     )?;
 
@@ -403,10 +481,12 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
             context,
             tracer,
             "KailuaTreasury::assignVanguard",
-            exec_safe_txn(
+            exec_owner_txn(
                 kailua_treasury_implementation.assignVanguard(vanguard_address, vanguard_advantage),
                 &factory_owner_safe,
                 owner_address,
+                &safe_cosigners,
+                fork_provider,
             )
         )?;
     }
@@ -428,7 +508,7 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
             .txn_args
             .premium_provider::<Ethereum>()
             .wallet(&guardian_wallet)
-            .connect_http(args.eth_rpc_url.as_str().try_into()?);
+            .connect_http(eth_rpc_url.as_str().try_into()?);
         let optimism_portal = OptimismPortal2::new(portal_address, &guardian_provider);
         let portal_guardian_address = optimism_portal
             .guardian()
@@ -450,11 +530,112 @@ pub async fn fast_track(args: FastTrackArgs) -> anyhow::Result<()> {
         );
     }
 
+    if let Some(fork_provider) = fork_provider {
+        // report the simulated migration
+        let kailua_game_address = *kailua_game_contract.address();
+        let contract_names = [
+            (verifier_contract_address, "RiscZeroVerifierRouter"),
+            (kailua_treasury_impl_addr, "KailuaTreasury"),
+            (kailua_treasury_instance_address, "KailuaTreasury(instance)"),
+            (kailua_game_address, "KailuaGame"),
+            (dgf_address, "DisputeGameFactory"),
+            (portal_address, "OptimismPortal2"),
+            (factory_owner_address, "Safe"),
+        ];
+        let contract_name = |address: Address| {
+            contract_names
+                .iter()
+                .find(|(known, _)| *known == address)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| address.to_string())
+        };
+        println!("TRANSACTIONS:");
+        let mut total_gas = 0;
+        let latest_block = await_tel_res!(
+            context,
+            tracer,
+            "Provider::get_block_number",
+            fork_provider.get_block_number()
+        )?;
+        for block_number in fork_block + 1..=latest_block {
+            let receipts = await_tel_res!(
+                context,
+                tracer,
+                "Provider::get_block_receipts",
+                fork_provider.get_block_receipts(BlockId::number(block_number))
+            )?
+            .unwrap_or_default();
+            for receipt in receipts {
+                let action = match (receipt.contract_address, receipt.to) {
+                    (Some(created), _) => format!("deploy {} at {created}", contract_name(created)),
+                    (None, Some(to)) => format!("call {}", contract_name(to)),
+                    (None, None) => String::from("unknown"),
+                };
+                println!(
+                    "  {} from {}: {action} ({} gas)",
+                    receipt.transaction_hash,
+                    contract_name(receipt.from),
+                    receipt.gas_used
+                );
+                total_gas += receipt.gas_used;
+            }
+        }
+        println!("  TOTAL: {total_gas} gas");
+        println!("DEPLOYMENT:");
+        println!("  TREASURY_INSTANCE: {kailua_treasury_instance_address}");
+        let deployment = await_tel_res!(
+            context,
+            GovernanceState::load(
+                fork_provider,
+                dgf_address,
+                portal_address,
+                Some(kailua_game_address)
+            ),
+            "GovernanceState::load"
+        )?;
+        for (name, value) in deployment.parameters() {
+            println!("  {name}: {value}");
+        }
+        info!("Simulation complete. No transactions were published.");
+        context.span().set_status(Status::Ok);
+        return Ok(());
+    }
+
     info!("Kailua upgrade complete.");
     context.span().set_status(Status::Ok);
     Ok(())
 }
 
+/// Executes the transaction through the factory owner Safe, or as the impersonated Safe when
+/// running against a fork
+async fn exec_owner_txn<P1: Provider, P2: Provider, C>(
+    txn: SolCallBuilder<P1, C>,
+    safe: &Safe::SafeInstance<P2>,
+    owner_address: Address,
+    cosigners: &SafeCosigners,
+    fork_provider: Option<&RootProvider>,
+) -> anyhow::Result<()> {
+    let Some(fork_provider) = fork_provider else {
+        return exec_safe_txn(txn, safe, owner_address, cosigners).await;
+    };
+    let request = txn.into_transaction_request().with_from(*safe.address());
+    let receipt = fork_provider
+        .send_transaction(request)
+        .await
+        .context("Provider::send_transaction")?
+        .get_receipt()
+        .await
+        .context("PendingTransactionBuilder::get_receipt")?;
+    if !receipt.status() {
+        bail!(
+            "Transaction {} from Safe {} reverted.",
+            receipt.transaction_hash,
+            safe.address()
+        );
+    }
+    Ok(())
+}
+
 #[allow(deprecated)]
 pub async fn deploy_verifier<P1: Provider<N>, P2: Provider<N>, N: Network>(
     deployer_provider: P1,
@@ -15,7 +15,6 @@
 use alloy::eips::eip4844::FIELD_ELEMENTS_PER_BLOB;
 use alloy::network::Ethereum;
 use alloy::primitives::{Bytes, B256, U256};
-use alloy::providers::RootProvider;
 use alloy::sol_types::SolValue;
 use anyhow::Context;
 use kailua_contracts::*;
@@ -25,6 +24,7 @@ use kailua_proposer::args::ProposeArgs;
 use kailua_sync::proposal::Proposal;
 use kailua_sync::provider::optimism::fetch_rollup_config;
 use kailua_sync::provider::optimism::OpNodeProvider;
+use kailua_sync::provider::{failover_client, failover_provider};
 use kailua_sync::stall::Stall;
 use kailua_sync::transact::Transact;
 use kailua_sync::{await_tel, await_tel_res, retry_res_ctx_timeout, KAILUA_GAME_TYPE};
@@ -51,22 +51,10 @@ pub async fn fault(args: FaultArgs) -> anyhow::Result<()> {
     let tracer = tracer("kailua");
     let context = opentelemetry::Context::current_with_span(tracer.start("fault"));
 
-    let op_node_provider = OpNodeProvider(RootProvider::new_http(
-        args.propose_args
-            .sync
-            .provider
-            .op_node_url
-            .as_str()
-            .try_into()?,
-    ));
-    let eth_rpc_provider = RootProvider::<Ethereum>::new_http(
-        args.propose_args
-            .sync
-            .provider
-            .eth_rpc_url
-            .as_str()
-            .try_into()?,
-    );
+    let op_node_provider = OpNodeProvider(failover_provider(
+        &args.propose_args.sync.provider.op_node_url,
+    )?);
+    let eth_rpc_provider = failover_provider(&args.propose_args.sync.provider.eth_rpc_url)?;
 
     info!("Fetching rollup configuration from rpc endpoints.");
     // fetch rollup config
@@ -105,14 +93,9 @@ pub async fn fault(args: FaultArgs) -> anyhow::Result<()> {
         .txn_args
         .premium_provider::<Ethereum>()
         .wallet(tester_wallet)
-        .connect_http(
-            args.propose_args
-                .sync
-                .provider
-                .eth_rpc_url
-                .as_str()
-                .try_into()?,
-        );
+        .connect_client(failover_client(
+            &args.propose_args.sync.provider.eth_rpc_url,
+        )?);
 
     let dispute_game_factory = IDisputeGameFactory::new(dgf_address, &tester_provider);
     let kailua_game_implementation = KailuaGame::new(
@@ -16,14 +16,20 @@ use kailua_sync::telemetry::TelemetryArgs;
 use kailua_validator::args;
 use std::path::PathBuf;
 
+pub mod admin;
 pub mod bench;
 pub mod bonsai;
 pub mod boundless;
 pub mod config;
 pub mod demo;
+pub mod estimate;
 pub mod export;
 pub mod fast_track;
 pub mod fault;
+pub mod proof;
+pub mod rollback;
+pub mod submit_bundle;
+pub mod treasury;
 
 /// The Kailua all-in-one CLI utility suite for securing rollups
 #[derive(clap::Parser, Debug, Clone)]
@@ -62,6 +68,12 @@ pub enum KailuaCli {
         #[clap(flatten)]
         cli: CliArgs,
     },
+    ProveWorker {
+        #[clap(flatten)]
+        args: kailua_validator::worker::WorkerArgs,
+        #[clap(flatten)]
+        cli: CliArgs,
+    },
     TestFault {
         #[clap(flatten)]
         args: fault::FaultArgs,
@@ -98,6 +110,42 @@ pub enum KailuaCli {
         #[clap(flatten)]
         cli: CliArgs,
     },
+    Treasury {
+        #[clap(flatten)]
+        args: treasury::TreasuryArgs,
+        #[clap(flatten)]
+        cli: CliArgs,
+    },
+    Admin {
+        #[clap(flatten)]
+        args: admin::AdminArgs,
+        #[clap(flatten)]
+        cli: CliArgs,
+    },
+    Rollback {
+        #[clap(flatten)]
+        args: rollback::RollbackArgs,
+        #[clap(flatten)]
+        cli: CliArgs,
+    },
+    SubmitBundle {
+        #[clap(flatten)]
+        args: submit_bundle::SubmitBundleArgs,
+        #[clap(flatten)]
+        cli: CliArgs,
+    },
+    Proof {
+        #[clap(flatten)]
+        args: proof::ProofArgs,
+        #[clap(flatten)]
+        cli: CliArgs,
+    },
+    Estimate {
+        #[clap(flatten)]
+        args: estimate::EstimateArgs,
+        #[clap(flatten)]
+        cli: CliArgs,
+    },
     Export {
         #[clap(long, env)]
         data_dir: Option<PathBuf>,
@@ -122,12 +170,19 @@ impl KailuaCli {
             KailuaCli::Propose { cli, .. } => cli.v,
             KailuaCli::Validate { cli, .. } => cli.v,
             KailuaCli::Prove { cli, .. } => cli.v,
+            KailuaCli::ProveWorker { cli, .. } => cli.v,
             KailuaCli::TestFault { cli, .. } => cli.v,
             KailuaCli::Benchmark { cli, .. } => cli.v,
             KailuaCli::Demo { cli, .. } => cli.v,
             KailuaCli::Rpc { cli, .. } => cli.v,
             KailuaCli::Bonsai { cli, .. } => cli.v,
             KailuaCli::Boundless { cli, .. } => cli.v,
+            KailuaCli::Treasury { cli, .. } => cli.v,
+            KailuaCli::Admin { cli, .. } => cli.v,
+            KailuaCli::Rollback { cli, .. } => cli.v,
+            KailuaCli::SubmitBundle { cli, .. } => cli.v,
+            KailuaCli::Proof { cli, .. } => cli.v,
+            KailuaCli::Estimate { cli, .. } => cli.v,
             KailuaCli::Export { cli, .. } => cli.v,
         }
     }
@@ -137,6 +192,7 @@ impl KailuaCli {
             KailuaCli::Propose { args, .. } => args.sync.data_dir.clone(),
             KailuaCli::Validate { args, .. } => args.sync.data_dir.clone(),
             KailuaCli::Prove { args, .. } => args.kona.data_dir.clone(),
+            KailuaCli::Estimate { args, .. } => args.prove.kona.data_dir.clone(),
             KailuaCli::Demo { args, .. } => args.data_dir.clone(),
             KailuaCli::Rpc { args, .. } => args.sync.data_dir.clone(),
             KailuaCli::Export { data_dir, .. } => data_dir.clone(),
@@ -151,12 +207,19 @@ impl KailuaCli {
             KailuaCli::Propose { args, .. } => &args.sync.telemetry,
             KailuaCli::Validate { args, .. } => &args.sync.telemetry,
             KailuaCli::Prove { args, .. } => &args.telemetry,
+            KailuaCli::ProveWorker { args, .. } => &args.telemetry,
             KailuaCli::TestFault { args, .. } => &args.propose_args.sync.telemetry,
             KailuaCli::Benchmark { args, .. } => &args.sync.telemetry,
             KailuaCli::Demo { args, .. } => &args.telemetry,
             KailuaCli::Rpc { args, .. } => &args.sync.telemetry,
             KailuaCli::Bonsai { args, .. } => &args.telemetry,
             KailuaCli::Boundless { args, .. } => &args.telemetry,
+            KailuaCli::Treasury { args, .. } => &args.telemetry,
+            KailuaCli::Admin { args, .. } => &args.telemetry,
+            KailuaCli::Rollback { args, .. } => &args.telemetry,
+            KailuaCli::SubmitBundle { args, .. } => &args.telemetry,
+            KailuaCli::Proof { args, .. } => &args.telemetry,
+            KailuaCli::Estimate { args, .. } => &args.prove.telemetry,
             KailuaCli::Export { telemetry, .. } => telemetry,
         }
     }
@@ -55,6 +55,9 @@ async fn main() -> anyhow::Result<()> {
             maybe_restrict_permits(&args.proving).await;
             await_tel!(context, kailua_prover::prove::prove(args))
         }
+        KailuaCli::ProveWorker { args, cli } => {
+            await_tel!(context, kailua_validator::worker::prove_worker(args, cli.v))
+        }
         KailuaCli::TestFault {
             #[cfg(feature = "devnet")]
             args,
@@ -81,6 +84,25 @@ async fn main() -> anyhow::Result<()> {
         KailuaCli::Boundless { args, .. } => {
             await_tel!(context, kailua_cli::boundless::boundless(args))
         }
+        KailuaCli::Treasury { args, .. } => {
+            await_tel!(context, kailua_cli::treasury::treasury(args))
+        }
+        KailuaCli::Admin { args, .. } => {
+            await_tel!(context, kailua_cli::admin::admin(args))
+        }
+        KailuaCli::Rollback { args, .. } => {
+            await_tel!(context, kailua_cli::rollback::rollback(args))
+        }
+        KailuaCli::SubmitBundle { args, .. } => {
+            await_tel!(context, kailua_cli::submit_bundle::submit_bundle(args))
+        }
+        KailuaCli::Proof { args, .. } => {
+            await_tel!(context, kailua_cli::proof::proof(args))
+        }
+        KailuaCli::Estimate { args, .. } => {
+            maybe_restrict_permits(&args.prove.proving).await;
+            await_tel!(context, kailua_cli::estimate::estimate(args))
+        }
         KailuaCli::Export { .. } => {
             await_tel!(context, kailua_cli::export::export(data_dir))
         }
@@ -0,0 +1,451 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::primitives::{Address, B256, U256};
+use anyhow::{anyhow, bail, Context};
+use kailua_build::{
+    KAILUA_DA_HOKULEA_ID, KAILUA_FPVM_HANA_ID, KAILUA_FPVM_HOKULEA_ID, KAILUA_FPVM_KONA_ID,
+};
+use kailua_contracts::*;
+use kailua_kona::journal::ProofJournal;
+use kailua_kona::precondition::{blobs_hash, validity_precondition_hash};
+use kailua_prover::proof::read_bincoded_file;
+use kailua_prover::store::ProofStore;
+use kailua_sync::args::parse_address;
+use kailua_sync::provider::failover_provider;
+use kailua_sync::stall::Stall;
+use kailua_sync::telemetry::TelemetryArgs;
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{TraceContextExt, Tracer};
+use risc0_zkvm::{InnerReceipt, Receipt};
+use std::iter::once;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Length of a packed [ProofJournal]
+const PROOF_JOURNAL_LENGTH: usize = 220;
+
+/// The FPVM images whose journals commit to their own image id
+pub const FPVM_IMAGES: [(&str, [u32; 8]); 3] = [
+    ("KAILUA_FPVM_KONA", KAILUA_FPVM_KONA_ID),
+    ("KAILUA_FPVM_HOKULEA", KAILUA_FPVM_HOKULEA_ID),
+    ("KAILUA_FPVM_HANA", KAILUA_FPVM_HANA_ID),
+];
+
+/// The DA images whose proofs are stitched into FPVM proofs
+pub const DA_IMAGES: [(&str, [u32; 8]); 1] = [("KAILUA_DA_HOKULEA", KAILUA_DA_HOKULEA_ID)];
+
+/// Inspect and verify stored proof receipts
+#[derive(clap::Args, Debug, Clone)]
+pub struct ProofArgs {
+    #[clap(subcommand)]
+    pub command: ProofCommand,
+
+    #[clap(flatten)]
+    pub telemetry: TelemetryArgs,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum ProofCommand {
+    /// Print the journal and receipt type of a proof file
+    Inspect {
+        /// Path of the proof file
+        file: PathBuf,
+    },
+    /// Verify a proof file locally and optionally against an on-chain proposal
+    Verify {
+        /// Path of the proof file
+        file: PathBuf,
+        /// Comma-separated addresses of the ethereum rpc endpoints to use (eth namespace required)
+        #[clap(long, env, requires = "game")]
+        eth_rpc_url: Option<String>,
+        /// Address of the KailuaTournament proposal whose dispute the proof settles
+        #[clap(long, value_parser = parse_address, requires = "eth_rpc_url")]
+        game: Option<Address>,
+        /// Address of the KailuaTournament whose l1 head the proof uses (defaults to the game)
+        #[clap(long, value_parser = parse_address)]
+        l1_head_source: Option<Address>,
+    },
+}
+
+/// A stored proof and its decoded journal
+pub struct InspectedProof {
+    pub receipt: Receipt,
+    pub journal: ProofJournal,
+    /// Name and id of the FPVM image matching the journal, if any
+    pub image: Option<(&'static str, [u32; 8])>,
+}
+
+pub async fn proof(args: ProofArgs) -> anyhow::Result<()> {
+    match args.command {
+        ProofCommand::Inspect { file } => {
+            inspect(&file).await?;
+        }
+        ProofCommand::Verify {
+            file,
+            eth_rpc_url,
+            game,
+            l1_head_source,
+        } => {
+            let inspected = inspect(&file).await?;
+            let Some((label, image_id)) = inspected.image else {
+                bail!("Can not verify proof of unknown FPVM image.");
+            };
+            inspected
+                .receipt
+                .verify(image_id)
+                .with_context(|| format!("Receipt verification against {label} failed."))?;
+            println!("RECEIPT: Verified against {label}.");
+
+            if let (Some(eth_rpc_url), Some(game)) = (eth_rpc_url, game) {
+                verify_against_game(
+                    &inspected.journal,
+                    &eth_rpc_url,
+                    game,
+                    l1_head_source.unwrap_or(game),
+                )
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads the proof file and prints its contents
+pub async fn inspect(file: &Path) -> anyhow::Result<InspectedProof> {
+    let file_name = file
+        .to_str()
+        .ok_or_else(|| anyhow!("Invalid proof file path {}", file.display()))?;
+    let receipt = read_bincoded_file::<Receipt>(file_name)
+        .await
+        .context("read_bincoded_file")?;
+    if receipt.journal.bytes.len() != PROOF_JOURNAL_LENGTH {
+        bail!(
+            "Journal of {} bytes is not a Kailua proof journal.",
+            receipt.journal.bytes.len()
+        );
+    }
+    let journal = ProofJournal::decode_packed(receipt.journal.as_ref());
+    let image = fpvm_image(&journal);
+
+    println!("FILE: {}", file.display());
+    println!("RECEIPT_TYPE: {}", receipt_type(&receipt));
+    println!(
+        "IMAGE: {}",
+        image.map(|(label, _)| label).unwrap_or("UNKNOWN")
+    );
+    println!("JOURNAL:");
+    println!("  payout_recipient: {}", journal.payout_recipient);
+    println!("  precondition_hash: {}", journal.precondition_hash);
+    println!("  l1_head: {}", journal.l1_head);
+    println!("  agreed_l2_output_root: {}", journal.agreed_l2_output_root);
+    println!(
+        "  claimed_l2_output_root: {}",
+        journal.claimed_l2_output_root
+    );
+    println!(
+        "  claimed_l2_block_number: {}",
+        journal.claimed_l2_block_number
+    );
+    println!("  config_hash: {}", journal.config_hash);
+    println!("  fpvm_image_id: {}", journal.fpvm_image_id);
+
+    Ok(InspectedProof {
+        receipt,
+        journal,
+        image,
+    })
+}
+
+/// Checks that the journal would be accepted by the proposal's parent tournament
+pub async fn verify_against_game(
+    journal: &ProofJournal,
+    eth_rpc_url: &str,
+    game: Address,
+    l1_head_source: Address,
+) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("verify_against_game"));
+
+    let eth_rpc_provider = failover_provider(eth_rpc_url)?;
+    let game_contract = KailuaTournament::new(game, &eth_rpc_provider);
+    let parent = game_contract
+        .parentGame()
+        .stall_with_context(context.clone(), "KailuaTournament::parentGame")
+        .await;
+    let parent_contract = KailuaTournament::new(parent, &eth_rpc_provider);
+    let signature = game_contract
+        .signature()
+        .stall_with_context(context.clone(), "KailuaTournament::signature")
+        .await;
+    let proof_status = parent_contract
+        .proofStatus(signature)
+        .stall_with_context(context.clone(), "KailuaTournament::proofStatus")
+        .await;
+    let l1_head = KailuaTournament::new(l1_head_source, &eth_rpc_provider)
+        .l1Head()
+        .stall_with_context(context.clone(), "KailuaTournament::l1Head")
+        .await;
+    let config_hash = game_contract
+        .ROLLUP_CONFIG_HASH()
+        .stall_with_context(context.clone(), "KailuaTournament::ROLLUP_CONFIG_HASH")
+        .await;
+    let fpvm_image_id = game_contract
+        .FPVM_IMAGE_ID()
+        .stall_with_context(context.clone(), "KailuaTournament::FPVM_IMAGE_ID")
+        .await;
+    let root_claim = game_contract
+        .rootClaim()
+        .stall_with_context(context.clone(), "KailuaTournament::rootClaim")
+        .await;
+    let l2_block_number: u64 = game_contract
+        .l2BlockNumber()
+        .stall_with_context(context.clone(), "KailuaTournament::l2BlockNumber")
+        .await
+        .to();
+    let parent_l2_block_number: u64 = parent_contract
+        .l2BlockNumber()
+        .stall_with_context(context.clone(), "KailuaTournament::l2BlockNumber")
+        .await
+        .to();
+    let parent_root_claim = parent_contract
+        .rootClaim()
+        .stall_with_context(context.clone(), "KailuaTournament::rootClaim")
+        .await;
+    let proposal_output_count = parent_contract
+        .PROPOSAL_OUTPUT_COUNT()
+        .stall_with_context(context.clone(), "KailuaTournament::PROPOSAL_OUTPUT_COUNT")
+        .await;
+    let output_block_span = parent_contract
+        .OUTPUT_BLOCK_SPAN()
+        .stall_with_context(context.clone(), "KailuaTournament::OUTPUT_BLOCK_SPAN")
+        .await;
+    let proposal_blobs = game_contract
+        .PROPOSAL_BLOBS()
+        .stall_with_context(context.clone(), "KailuaTournament::PROPOSAL_BLOBS")
+        .await;
+    let mut blob_hashes = Vec::with_capacity(proposal_blobs as usize);
+    for i in 0..proposal_blobs {
+        blob_hashes.push(
+            game_contract
+                .proposalBlobHashes(U256::from(i))
+                .stall_with_context(context.clone(), "KailuaTournament::proposalBlobHashes")
+                .await,
+        );
+    }
+
+    println!("PROPOSAL: {game}");
+    println!("  parent: {parent}");
+    println!("  signature: {signature}");
+    println!(
+        "  proof_status: {}",
+        match proof_status {
+            0 => "NONE",
+            1 => "FAULT",
+            2 => "VALIDITY",
+            _ => "UNKNOWN",
+        }
+    );
+    // Fault proofs never commit to a precondition, so only the claims of validity proofs of
+    // single-output proposals tell them apart
+    let proves_validity = if proposal_output_count > 1 {
+        journal.precondition_hash != B256::ZERO
+    } else {
+        journal.claimed_l2_output_root == root_claim
+    };
+    if proves_validity {
+        println!("  claim: The journal proves the validity of the proposal.");
+    } else {
+        println!(
+            "  claim: The journal proves the output at block {}.",
+            journal.claimed_l2_block_number
+        );
+    }
+
+    let mut checklist = vec![
+        (
+            format!("l1 head matches l1Head of {l1_head_source}"),
+            journal.l1_head == l1_head,
+        ),
+        (
+            String::from("config hash matches ROLLUP_CONFIG_HASH"),
+            journal.config_hash == config_hash,
+        ),
+        (
+            String::from("image id matches FPVM_IMAGE_ID"),
+            journal.fpvm_image_id == fpvm_image_id,
+        ),
+        (
+            format!("claimed block lies within ({parent_l2_block_number}, {l2_block_number}]"),
+            parent_l2_block_number < journal.claimed_l2_block_number
+                && journal.claimed_l2_block_number <= l2_block_number,
+        ),
+        (
+            format!("claimed block is a multiple of {output_block_span} blocks after the parent"),
+            output_block_span > 0
+                && journal
+                    .claimed_l2_block_number
+                    .saturating_sub(parent_l2_block_number)
+                    % output_block_span
+                    == 0,
+        ),
+    ];
+    if proves_validity {
+        // proveValidity commits to the blobs of the proposal unless it has a single output
+        let expected_precondition_hash = if proposal_output_count > 1 {
+            validity_precondition_hash(
+                &parent_l2_block_number,
+                &proposal_output_count,
+                &output_block_span,
+                blobs_hash(blob_hashes.iter()),
+            )
+        } else {
+            B256::ZERO
+        };
+        checklist.extend([
+            (
+                String::from("precondition hash matches the blobs of the proposal"),
+                journal.precondition_hash == expected_precondition_hash,
+            ),
+            (
+                format!("agreed output matches rootClaim of {parent}"),
+                journal.agreed_l2_output_root == parent_root_claim,
+            ),
+            (
+                String::from("claimed output matches rootClaim"),
+                journal.claimed_l2_output_root == root_claim,
+            ),
+            (
+                String::from("claimed block matches l2BlockNumber"),
+                journal.claimed_l2_block_number == l2_block_number,
+            ),
+            (
+                String::from("claimed output and blobs match the proposal signature"),
+                blobs_hash(once(&journal.claimed_l2_output_root).chain(blob_hashes.iter()))
+                    == signature,
+            ),
+        ]);
+    } else {
+        checklist.push((
+            String::from("precondition hash is empty"),
+            journal.precondition_hash == B256::ZERO,
+        ));
+        // Agreed intermediate outputs are only published in the proposal blobs
+        if journal.claimed_l2_block_number == parent_l2_block_number + output_block_span {
+            checklist.push((
+                format!("agreed output matches rootClaim of {parent}"),
+                journal.agreed_l2_output_root == parent_root_claim,
+            ));
+        }
+        if journal.claimed_l2_block_number == l2_block_number {
+            checklist.push((
+                String::from("claimed output contradicts rootClaim"),
+                journal.claimed_l2_output_root != root_claim,
+            ));
+        }
+    }
+    println!("CHECKLIST:");
+    for (item, passed) in &checklist {
+        println!("  [{}] {item}", if *passed { "x" } else { " " });
+    }
+    if !checklist.iter().all(|(_, passed)| *passed) {
+        bail!("Proof journal does not match proposal {game}.");
+    }
+
+    Ok(())
+}
+
+/// Returns the FPVM image the journal commits to, if known
+pub fn fpvm_image(journal: &ProofJournal) -> Option<(&'static str, [u32; 8])> {
+    FPVM_IMAGES
+        .into_iter()
+        .find(|(_, image_id)| image_id_hash(*image_id) == journal.fpvm_image_id)
+}
+
+/// Returns the known image with the given hash
+pub fn image_by_hash(image_id: B256) -> Option<(&'static str, [u32; 8])> {
+    FPVM_IMAGES
+        .into_iter()
+        .chain(DA_IMAGES)
+        .find(|(_, known_id)| image_id_hash(*known_id) == image_id)
+}
+
+/// Identifies the known image that produced the receipt
+pub fn detect_image(receipt: &Receipt) -> Option<(&'static str, [u32; 8])> {
+    // FPVM journals commit to their image id
+    if receipt.journal.bytes.len() == PROOF_JOURNAL_LENGTH {
+        if let Some(image) = fpvm_image(&ProofJournal::decode_packed(receipt.journal.as_ref())) {
+            return Some(image);
+        }
+    }
+    // DA journals do not, so fall back to trial verification
+    DA_IMAGES
+        .into_iter()
+        .find(|(_, image_id)| receipt.verify(*image_id).is_ok())
+}
+
+/// Reads the non-empty lines of a recovery manifest, ignoring `#` comments
+pub async fn read_manifest(path: &Path) -> anyhow::Result<Vec<String>> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Verifies a recovered receipt and saves it to the store unless a valid copy already exists
+pub async fn store_recovered_proof(
+    store: &ProofStore,
+    receipt: &Receipt,
+    label: &str,
+    image_id: [u32; 8],
+) -> anyhow::Result<String> {
+    receipt
+        .verify(image_id)
+        .with_context(|| format!("Receipt verification against {label} failed."))?;
+
+    let file_name = ProofStore::key(image_id, receipt.journal.clone());
+    if let Ok(prior_receipt) = store.load(&file_name).await {
+        if prior_receipt.verify(image_id).is_ok() {
+            info!("Skipping overwriting valid receipt file {file_name}.");
+            return Ok(file_name);
+        }
+        info!("Overwriting invalid receipt file {file_name}.");
+    }
+    store
+        .save(image_id, receipt)
+        .await
+        .with_context(|| format!("Failed to write proof to {file_name}"))?;
+    info!("{label} proof written to {file_name}.");
+    Ok(file_name)
+}
+
+/// Returns the journal encoding of the image id
+pub fn image_id_hash(image_id: [u32; 8]) -> B256 {
+    B256::from(bytemuck::cast::<[u32; 8], [u8; 32]>(image_id))
+}
+
+pub fn receipt_type(receipt: &Receipt) -> &'static str {
+    match &receipt.inner {
+        InnerReceipt::Groth16(_) => "Groth16",
+        InnerReceipt::Succinct(_) => "Succinct",
+        InnerReceipt::Composite(_) => "Composite",
+        InnerReceipt::Fake(_) => "Fake",
+        _ => "Unknown",
+    }
+}
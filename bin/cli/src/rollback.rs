@@ -0,0 +1,410 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::admin::simulate;
+use alloy::consensus::BlockHeader;
+use alloy::eips::BlockNumberOrTag;
+use alloy::network::{Ethereum, TxSigner};
+use alloy::primitives::{Address, U256};
+use anyhow::{anyhow, bail, Context};
+use kailua_contracts::*;
+use kailua_sync::args::parse_address;
+use kailua_sync::provider::optimism::fetch_rollup_config;
+use kailua_sync::provider::{failover_client, failover_provider};
+use kailua_sync::stall::Stall;
+use kailua_sync::telemetry::TelemetryArgs;
+use kailua_sync::transact::rpc::get_block;
+use kailua_sync::transact::safe::{exec_safe_txn, SafeCosignerArgs};
+use kailua_sync::transact::signer::{GuardianSignerArgs, OwnerSignerArgs};
+use kailua_sync::transact::{Transact, TransactArgs};
+use kailua_sync::{await_tel, await_tel_res, KAILUA_GAME_TYPE};
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{FutureExt, Status, TraceContextExt, Tracer};
+use tracing::{info, warn};
+
+/// Revert the respected game type of a rollup away from Kailua
+#[derive(clap::Args, Debug, Clone)]
+pub struct RollbackArgs {
+    /// Address of the OP-NODE endpoint to use
+    #[clap(long, env)]
+    pub op_node_url: String,
+    /// Address of the OP-GETH endpoint to use (eth and debug namespace required).
+    #[clap(long, env)]
+    pub op_geth_url: String,
+    /// Comma-separated addresses of the ethereum rpc endpoints to use (eth namespace required)
+    #[clap(long, env)]
+    pub eth_rpc_url: String,
+    /// Whether to bypass loading rollup chain configurations from the kona registry
+    #[clap(long, env, default_value_t = false)]
+    pub bypass_chain_registry: bool,
+
+    /// The game type to restore as respected by the OptimismPortal (e.g. 0 for Cannon)
+    #[clap(long, env)]
+    pub restore_game_type: u32,
+    /// Comma-separated addresses of dispute games to blacklist in the OptimismPortal
+    #[clap(long, env, value_delimiter = ',', value_parser = parse_address)]
+    pub blacklist_games: Vec<Address>,
+    /// Index of the DisputeGameFactory game from which to blacklist all Kailua games
+    #[clap(long, env)]
+    pub blacklist_from_index: Option<u64>,
+    /// Whether to remove the KailuaGame implementation from the DisputeGameFactory
+    #[clap(long, env, default_value_t = false)]
+    pub remove_implementation: bool,
+
+    /// Whether to only simulate the rollback against the live state without publishing it
+    #[clap(long, env, default_value_t = false)]
+    pub dry_run: bool,
+
+    /// Transaction publication configuration
+    #[clap(flatten)]
+    pub txn_args: TransactArgs,
+    /// Secret key of L1 wallet that (indirectly) owns `DisputeGameFactory`
+    #[clap(flatten)]
+    pub owner_signer: Option<OwnerSignerArgs>,
+    /// Signatures of the other owners of the `DisputeGameFactory` owner Safe
+    #[clap(flatten)]
+    pub safe_cosigners: SafeCosignerArgs,
+    /// Secret key of L1 guardian wallet
+    #[clap(flatten)]
+    pub guardian_signer: Option<GuardianSignerArgs>,
+
+    #[clap(flatten)]
+    pub telemetry: TelemetryArgs,
+}
+
+pub async fn rollback(args: RollbackArgs) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("rollback"));
+
+    if args.restore_game_type == KAILUA_GAME_TYPE {
+        bail!("Cannot roll back to the Kailua game type {KAILUA_GAME_TYPE}.");
+    }
+
+    // fetch rollup config
+    let config = await_tel!(
+        context,
+        fetch_rollup_config(
+            &args.op_node_url,
+            &args.op_geth_url,
+            None,
+            args.bypass_chain_registry
+        )
+    )
+    .context("fetch_rollup_config")?;
+    let eth_rpc_provider = failover_provider(&args.eth_rpc_url)?;
+
+    // load the current portal state
+    let system_config = SystemConfig::new(config.l1_system_config_address, &eth_rpc_provider);
+    let portal_address = system_config
+        .optimismPortal()
+        .stall_with_context(context.clone(), "SystemConfig::optimismPortal")
+        .await;
+    let dgf_address = system_config
+        .disputeGameFactory()
+        .stall_with_context(context.clone(), "SystemConfig::disputeGameFactory")
+        .await;
+    let optimism_portal = OptimismPortal2::new(portal_address, &eth_rpc_provider);
+    let dispute_game_factory = IDisputeGameFactory::new(dgf_address, &eth_rpc_provider);
+    let guardian_address = optimism_portal
+        .guardian()
+        .stall_with_context(context.clone(), "OptimismPortal2::guardian")
+        .await;
+    let respected_game_type = optimism_portal
+        .respectedGameType()
+        .stall_with_context(context.clone(), "OptimismPortal2::respectedGameType")
+        .await;
+    info!("OptimismPortal2 guardian: {guardian_address}");
+    info!("OptimismPortal2 respected game type: {respected_game_type}");
+
+    // ensure the restored game type can still be played
+    let restored_implementation = dispute_game_factory
+        .gameImpls(args.restore_game_type)
+        .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
+        .await;
+    if restored_implementation.is_zero() {
+        bail!(
+            "DisputeGameFactory {dgf_address} has no implementation for game type {}.",
+            args.restore_game_type
+        );
+    }
+    let kailua_implementation = dispute_game_factory
+        .gameImpls(KAILUA_GAME_TYPE)
+        .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
+        .await;
+
+    // determine which games to blacklist
+    let mut affected_games = args.blacklist_games.clone();
+    if let Some(start_index) = args.blacklist_from_index {
+        let game_count: u64 = dispute_game_factory
+            .gameCount()
+            .stall_with_context(context.clone(), "DisputeGameFactory::gameCount")
+            .await
+            .to();
+        info!("Scanning DisputeGameFactory games {start_index}..{game_count} for Kailua games.");
+        for index in start_index..game_count {
+            let IDisputeGameFactory::gameAtIndexReturn {
+                gameType_: game_type,
+                proxy_: game_address,
+                ..
+            } = dispute_game_factory
+                .gameAtIndex(U256::from(index))
+                .stall_with_context(context.clone(), "DisputeGameFactory::gameAtIndex")
+                .await;
+            if game_type == KAILUA_GAME_TYPE && !affected_games.contains(&game_address) {
+                affected_games.push(game_address);
+            }
+        }
+    }
+    let mut blacklist = vec![];
+    for game_address in &affected_games {
+        let is_blacklisted = optimism_portal
+            .disputeGameBlacklist(*game_address)
+            .stall_with_context(context.clone(), "OptimismPortal2::disputeGameBlacklist")
+            .await;
+        if is_blacklisted {
+            info!("Game {game_address} is already blacklisted.");
+        } else {
+            blacklist.push(*game_address);
+        }
+    }
+
+    // simulate all changes
+    let factory_owner_address = OwnableUpgradeable::new(dgf_address, &eth_rpc_provider)
+        .owner()
+        .stall_with_context(context.clone(), "DisputeGameFactory::owner")
+        .await;
+    let restore_game_type = respected_game_type != args.restore_game_type;
+    if restore_game_type {
+        await_tel_res!(
+            context,
+            simulate(
+                optimism_portal.setRespectedGameType(args.restore_game_type),
+                "OptimismPortal2::setRespectedGameType",
+                guardian_address,
+            ),
+            "simulate"
+        )?;
+    } else {
+        warn!(
+            "OptimismPortal2 already respects game type {}.",
+            args.restore_game_type
+        );
+    }
+    for game_address in &blacklist {
+        await_tel_res!(
+            context,
+            simulate(
+                optimism_portal.blacklistDisputeGame(*game_address),
+                "OptimismPortal2::blacklistDisputeGame",
+                guardian_address,
+            ),
+            "simulate"
+        )?;
+    }
+    let remove_implementation = args.remove_implementation && !kailua_implementation.is_zero();
+    if remove_implementation {
+        await_tel_res!(
+            context,
+            simulate(
+                dispute_game_factory.setImplementation(KAILUA_GAME_TYPE, Address::ZERO),
+                "DisputeGameFactory::setImplementation",
+                factory_owner_address,
+            ),
+            "simulate"
+        )?;
+    }
+
+    println!("PLAN:");
+    if restore_game_type {
+        println!(
+            "* RESPECTED_GAME_TYPE: {respected_game_type} -> {}",
+            args.restore_game_type
+        );
+    }
+    for game_address in &blacklist {
+        println!("* BLACKLIST: {game_address}");
+    }
+    if remove_implementation {
+        println!(
+            "* KAILUA_IMPLEMENTATION: {kailua_implementation} -> {}",
+            Address::ZERO
+        );
+    }
+    if args.dry_run {
+        info!("Simulation complete. No transactions were published.");
+        context.span().set_status(Status::Ok);
+        return Ok(());
+    }
+
+    // apply guardian changes
+    let rollback_time = await_tel!(
+        context,
+        get_block(&eth_rpc_provider, BlockNumberOrTag::Latest)
+    )
+    .header()
+    .timestamp();
+    if restore_game_type || !blacklist.is_empty() {
+        info!("Initializing guardian wallet.");
+        let guardian_wallet = await_tel_res!(
+            context,
+            tracer,
+            "GuardianSignerArgs::wallet",
+            args.guardian_signer
+                .as_ref()
+                .ok_or_else(|| anyhow!("Guardian signer not provided"))?
+                .wallet(Some(config.l1_chain_id))
+        )?;
+        if guardian_wallet.default_signer().address() != guardian_address {
+            bail!(
+                "OptimismPortal2 Guardian is {guardian_address}. Provided key has account address {}.",
+                guardian_wallet.default_signer().address()
+            );
+        }
+        let guardian_provider = args
+            .txn_args
+            .premium_provider::<Ethereum>()
+            .wallet(&guardian_wallet)
+            .connect_client(failover_client(&args.eth_rpc_url)?);
+        let optimism_portal = OptimismPortal2::new(portal_address, &guardian_provider);
+        if restore_game_type {
+            let receipt = optimism_portal
+                .setRespectedGameType(args.restore_game_type)
+                .transact_with_context(context.clone(), "OptimismPortal2::setRespectedGameType")
+                .await
+                .context("OptimismPortal2::setRespectedGameType")?;
+            info!(
+                "OptimismPortal2::setRespectedGameType: {} gas",
+                receipt.gas_used
+            );
+        }
+        for game_address in &blacklist {
+            let receipt = optimism_portal
+                .blacklistDisputeGame(*game_address)
+                .transact_with_context(context.clone(), "OptimismPortal2::blacklistDisputeGame")
+                .await
+                .context("OptimismPortal2::blacklistDisputeGame")?;
+            info!(
+                "OptimismPortal2::blacklistDisputeGame({game_address}): {} gas",
+                receipt.gas_used
+            );
+        }
+    }
+
+    // apply owner changes
+    if remove_implementation {
+        info!("Initializing owner wallet.");
+        let owner_wallet = await_tel_res!(
+            context,
+            tracer,
+            "OwnerSignerArgs::wallet",
+            args.owner_signer
+                .as_ref()
+                .ok_or_else(|| anyhow!("Owner signer not provided"))?
+                .wallet(Some(config.l1_chain_id))
+        )?;
+        let owner_address = owner_wallet.default_signer().address();
+        let owner_provider = args
+            .txn_args
+            .premium_provider::<Ethereum>()
+            .wallet(&owner_wallet)
+            .connect_client(failover_client(&args.eth_rpc_url)?);
+        let factory_owner_safe = Safe::new(factory_owner_address, &owner_provider);
+        let safe_cosigners = await_tel_res!(
+            context,
+            tracer,
+            "SafeCosignerArgs::cosigners",
+            args.safe_cosigners.cosigners(Some(config.l1_chain_id))
+        )?;
+        await_tel_res!(
+            context,
+            tracer,
+            "exec_safe_txn",
+            exec_safe_txn(
+                IDisputeGameFactory::new(dgf_address, &owner_provider)
+                    .setImplementation(KAILUA_GAME_TYPE, Address::ZERO),
+                &factory_owner_safe,
+                owner_address,
+                &safe_cosigners
+            )
+        )?;
+        info!("DisputeGameFactory::setImplementation executed.");
+    }
+
+    // verify the resulting state
+    let mut checklist = vec![];
+    let respected_game_type = optimism_portal
+        .respectedGameType()
+        .stall_with_context(context.clone(), "OptimismPortal2::respectedGameType")
+        .await;
+    checklist.push((
+        respected_game_type == args.restore_game_type,
+        format!("OptimismPortal2 respects game type {respected_game_type}"),
+    ));
+    if restore_game_type {
+        // Games created before this time can no longer be used to finalize withdrawals
+        let updated_at = optimism_portal
+            .respectedGameTypeUpdatedAt()
+            .stall_with_context(
+                context.clone(),
+                "OptimismPortal2::respectedGameTypeUpdatedAt",
+            )
+            .await;
+        checklist.push((
+            updated_at >= rollback_time,
+            format!("OptimismPortal2 respected game type updated at {updated_at}"),
+        ));
+    }
+    let restored_implementation = dispute_game_factory
+        .gameImpls(args.restore_game_type)
+        .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
+        .await;
+    checklist.push((
+        !restored_implementation.is_zero(),
+        format!(
+            "DisputeGameFactory implements game type {} at {restored_implementation}",
+            args.restore_game_type
+        ),
+    ));
+    for game_address in &affected_games {
+        let is_blacklisted = optimism_portal
+            .disputeGameBlacklist(*game_address)
+            .stall_with_context(context.clone(), "OptimismPortal2::disputeGameBlacklist")
+            .await;
+        checklist.push((
+            is_blacklisted,
+            format!("OptimismPortal2 blacklists game {game_address}"),
+        ));
+    }
+    if args.remove_implementation {
+        let kailua_implementation = dispute_game_factory
+            .gameImpls(KAILUA_GAME_TYPE)
+            .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
+            .await;
+        checklist.push((
+            kailua_implementation.is_zero(),
+            format!("DisputeGameFactory implements game type {KAILUA_GAME_TYPE} at {kailua_implementation}"),
+        ));
+    }
+
+    println!("CHECKLIST:");
+    for (passed, item) in &checklist {
+        println!("[{}] {item}", if *passed { "x" } else { " " });
+    }
+    if checklist.iter().any(|(passed, _)| !passed) {
+        bail!("Rollback verification failed.");
+    }
+    info!("Rollback complete.");
+    context.span().set_status(Status::Ok);
+    Ok(())
+}
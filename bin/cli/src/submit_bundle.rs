@@ -0,0 +1,117 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::network::ReceiptResponse;
+use alloy::primitives::Bytes;
+use alloy::providers::Provider;
+use anyhow::{bail, Context};
+use kailua_sync::await_tel_res;
+use kailua_sync::provider::failover_provider;
+use kailua_sync::telemetry::TelemetryArgs;
+use kailua_sync::transact::bundle::TransactionBundle;
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
+
+/// Broadcast a transaction bundle exported by the validator after it was signed offline
+#[derive(clap::Args, Debug, Clone)]
+pub struct SubmitBundleArgs {
+    /// Comma-separated addresses of the ethereum rpc endpoints to use (eth namespace required)
+    #[clap(long, env)]
+    pub eth_rpc_url: String,
+    /// Path to the bundle file
+    #[clap(long, env)]
+    pub bundle: PathBuf,
+    /// The EIP-2718 encoded signed transaction, if not already included in the bundle file
+    #[clap(long, env)]
+    pub signed_transaction: Option<Bytes>,
+    /// Transaction Confirmation Timeout
+    #[clap(long, env, required = false, default_value_t = 120)]
+    pub txn_timeout: u64,
+
+    #[clap(flatten)]
+    pub telemetry: TelemetryArgs,
+}
+
+pub async fn submit_bundle(args: SubmitBundleArgs) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("submit_bundle"));
+
+    // load the signed bundle
+    let mut bundle = await_tel_res!(
+        context,
+        TransactionBundle::load(&args.bundle),
+        "TransactionBundle::load"
+    )?;
+    if args.signed_transaction.is_some() {
+        bundle.signed_transaction = args.signed_transaction;
+    }
+    let envelope = bundle.verify_signed()?;
+    info!(
+        "Loaded {} transaction {} from {} to {}.",
+        bundle.description,
+        envelope.tx_hash(),
+        bundle.from,
+        bundle.to
+    );
+
+    // check that the bundle targets the connected chain
+    let eth_rpc_provider = failover_provider(&args.eth_rpc_url)?;
+    let chain_id = await_tel_res!(
+        context,
+        tracer,
+        "Provider::get_chain_id",
+        eth_rpc_provider.get_chain_id()
+    )?;
+    if chain_id != bundle.chain_id {
+        bail!(
+            "Bundle chain id {} does not match rpc chain id {chain_id}.",
+            bundle.chain_id
+        );
+    }
+
+    // broadcast the signed transaction
+    let signed_transaction = bundle.signed_transaction.unwrap_or_default();
+    let pending_txn = await_tel_res!(
+        context,
+        tracer,
+        "Provider::send_raw_transaction",
+        eth_rpc_provider.send_raw_transaction(&signed_transaction)
+    )?;
+    info!("Transaction published: {:?}", pending_txn.tx_hash());
+    let receipt = await_tel_res!(
+        context,
+        tracer,
+        "PendingTransactionBuilder::get_receipt",
+        pending_txn
+            .with_timeout(Some(Duration::from_secs(args.txn_timeout)))
+            .get_receipt()
+    )?;
+    if !receipt.status() {
+        bail!(
+            "Transaction {} reverted in block {:?}.",
+            receipt.transaction_hash,
+            receipt.block_number
+        );
+    }
+    info!(
+        "{}: {} gas (block {:?})",
+        bundle.description, receipt.gas_used, receipt.block_number
+    );
+    println!("TXN_HASH: {}", receipt.transaction_hash);
+
+    Ok(())
+}
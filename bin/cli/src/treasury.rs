@@ -0,0 +1,130 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::network::{Ethereum, TxSigner};
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use anyhow::Context;
+use kailua_sync::args::parse_address;
+use kailua_sync::bonds::{claim_bonds, fetch_bond_status, BondClaimArgs};
+use kailua_sync::provider::{failover_client, failover_provider};
+use kailua_sync::telemetry::TelemetryArgs;
+use kailua_sync::transact::signer::ClaimantSignerArgs;
+use kailua_sync::transact::TransactArgs;
+use kailua_sync::{await_tel, await_tel_res};
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use tracing::info;
+
+/// Inspect and claim the bonds held by a Kailua treasury
+#[derive(clap::Args, Debug, Clone)]
+pub struct TreasuryArgs {
+    /// Comma-separated addresses of the ethereum rpc endpoints to use (eth namespace required)
+    #[clap(long, env)]
+    pub eth_rpc_url: String,
+    /// Address of the KailuaTreasury contract
+    #[clap(long, env, value_parser = parse_address)]
+    pub kailua_treasury: Address,
+
+    /// Whether to only report the bonds owed without claiming them
+    #[clap(long, env, default_value_t = false)]
+    pub skip_claims: bool,
+    /// Whether to leave the proposer bond locked in the treasury
+    #[clap(long, env, default_value_t = false)]
+    pub keep_proposer_bond: bool,
+    #[clap(flatten)]
+    pub claim: BondClaimArgs,
+
+    /// L1 wallet owed the bonds
+    #[clap(flatten)]
+    pub claimant_signer: ClaimantSignerArgs,
+    /// Transaction publication configuration
+    #[clap(flatten)]
+    pub txn_args: TransactArgs,
+
+    #[clap(flatten)]
+    pub telemetry: TelemetryArgs,
+}
+
+pub async fn treasury(args: TreasuryArgs) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("treasury"));
+
+    // initialize claimant wallet
+    let eth_rpc_provider = failover_provider(&args.eth_rpc_url)?;
+    let chain_id = await_tel_res!(
+        context,
+        tracer,
+        "Provider::get_chain_id",
+        eth_rpc_provider.get_chain_id()
+    )?;
+    info!("Initializing claimant wallet.");
+    let claimant_wallet = await_tel_res!(
+        context,
+        tracer,
+        "ClaimantSignerArgs::wallet",
+        args.claimant_signer.wallet(Some(chain_id))
+    )?;
+    let claimant_address = claimant_wallet.default_signer().address();
+    let claimant_provider = args
+        .txn_args
+        .premium_provider::<Ethereum>()
+        .wallet(&claimant_wallet)
+        .connect_client(failover_client(&args.eth_rpc_url)?);
+
+    // report bond status
+    let status = await_tel_res!(
+        context,
+        fetch_bond_status(&claimant_provider, args.kailua_treasury, claimant_address),
+        "fetch_bond_status"
+    )?;
+    println!("TREASURY: {}", status.treasury);
+    println!("ACCOUNT: {}", status.account);
+    println!("PAID_BOND: {}", status.paid_bond);
+    println!("ELIMINATION_ROUND: {}", status.elimination_round);
+    println!("LAST_PROPOSAL: {}", status.last_proposal);
+    println!("LAST_PROPOSAL_RESOLVED: {}", status.last_proposal_resolved);
+    println!(
+        "CLAIMABLE_PROPOSER_BOND: {}",
+        status.claimable_proposer_bond()
+    );
+    println!(
+        "UNCLAIMED_ELIMINATIONS: {}",
+        status.unclaimed_eliminations.len()
+    );
+    println!(
+        "UNCLAIMED_ELIMINATION_BONDS: {}",
+        status.unclaimed_elimination_bonds
+    );
+    if args.skip_claims {
+        return Ok(());
+    }
+
+    // claim bonds above threshold
+    let claimed = await_tel!(
+        context,
+        claim_bonds(
+            &claimant_provider,
+            args.kailua_treasury,
+            claimant_address,
+            !args.keep_proposer_bond,
+            &args.claim,
+            &args.txn_args
+        )
+    )
+    .context("claim_bonds")?;
+    println!("CLAIMED: {claimed}");
+
+    Ok(())
+}
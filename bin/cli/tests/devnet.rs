@@ -70,6 +70,10 @@ async fn deploy_kailua_contracts(challenge_timeout: u64) -> anyhow::Result<()> {
             txn_timeout: 12,
             exec_gas_premium: 0,
             blob_gas_premium: 0,
+            txn_max_fee_bumps: 3,
+            txn_fee_bump: 25,
+            txn_max_fee_per_gas: None,
+            txn_max_fee_per_blob_gas: None,
         },
         starting_block_number: 0,
         proposal_output_count: 5,
@@ -83,12 +87,14 @@ async fn deploy_kailua_contracts(challenge_timeout: u64) -> anyhow::Result<()> {
         owner_signer: OwnerSignerArgs::from(
             "0x7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6".to_string(),
         ),
+        safe_cosigners: Default::default(),
         guardian_signer: Some(GuardianSignerArgs::from(
             "0x2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6".to_string(),
         )),
         vanguard_address: Some("0x9965507D1a55bcC2695C58ba16FB37d819B0A4dc".to_string()),
         vanguard_advantage: Some(60),
         respect_kailua_proposals: true,
+        dry_run: false,
         telemetry: Default::default(),
         bypass_chain_registry: false,
     })
@@ -155,6 +161,8 @@ async fn proposer_validator() {
             op_geth_url: "http://127.0.0.1:9545".to_string(),
             op_node_url: "http://127.0.0.1:7545".to_string(),
             op_rpc_delay: 0,
+            op_node_quorum: None,
+            verify_output_roots: false,
             beacon_rpc_url: "http://127.0.0.1:5052".to_string(),
         },
         kailua_game_implementation: None,
@@ -169,6 +177,10 @@ async fn proposer_validator() {
         txn_timeout: 30,
         exec_gas_premium: 25,
         blob_gas_premium: 25,
+        txn_max_fee_bumps: 3,
+        txn_fee_bump: 25,
+        txn_max_fee_per_gas: None,
+        txn_max_fee_per_blob_gas: None,
     };
 
     // Instantiate proposer wallet
@@ -183,6 +195,7 @@ async fn proposer_validator() {
             bypass_chain_registry: false,
             proposer_signer: proposer_signer.clone(),
             txn_args: txn_args.clone(),
+            bonds: Default::default(),
         },
         proposer_data_dir.clone(),
     )
@@ -214,6 +227,7 @@ async fn proposer_validator() {
                 "0x4bbbf85ce3377467afe5d46f804f221813b2bb87f24d81f60f1fcdbf7cbf4356".to_string(),
             ),
             txn_args: txn_args.clone(),
+            bonds: Default::default(),
         },
         fault_offset: 1,
         fault_parent,
@@ -230,6 +244,7 @@ async fn proposer_validator() {
                 "0xdbda1821b80551c9d65939329250298aa3472ba22feea921c0cf5d620ea67b97".to_string(),
             ),
             txn_args: txn_args.clone(),
+            bonds: Default::default(),
         },
         fault_offset: 250,
         fault_parent,
@@ -255,9 +270,16 @@ async fn proposer_validator() {
             fast_forward_start: 0,
             fast_forward_target: 0,
             num_concurrent_provers: 1,
+            coordinator: Default::default(),
             enable_experimental_witness_endpoint: true,
             max_fault_proving_delay: 0,
             max_validity_proving_delay: 0,
+            proving_policy: Default::default(),
+            watchdog: Default::default(),
+            watch_only: false,
+            fault_report_dir: None,
+            export_bundle_dir: None,
+            bundle_sender: None,
             l1_head_jump_back: 0,
             validator_signer: ValidatorSignerArgs::from(
                 "0x92db14e403b83dfe3df233f83dfa3a0d7096f21ca9b0d6d6b8d88b2b4ec1564e".to_string(),
@@ -277,10 +299,14 @@ async fn proposer_validator() {
                 skip_derivation_proof: false,
                 skip_await_proof: false,
                 clear_cache_data: true,
+                proof_backend: vec![],
+                proof_backend_timeout: None,
+                proof_store: Default::default(),
                 hokulea: Default::default(),
                 hana: Default::default(),
             },
             boundless: Default::default(),
+            bonds: Default::default(),
         },
         3,
         validator_data_dir.clone(),
@@ -291,6 +317,7 @@ async fn proposer_validator() {
             bypass_chain_registry: false,
             proposer_signer: proposer_signer.clone(),
             txn_args: txn_args.clone(),
+            bonds: Default::default(),
         },
         proposer_data_dir.clone(),
     ));
@@ -314,9 +341,16 @@ async fn proposer_validator() {
             fast_forward_start: 0,
             fast_forward_target: 90, // run validity proofs until block 90 is finalized
             num_concurrent_provers: 5,
+            coordinator: Default::default(),
             enable_experimental_witness_endpoint: true,
             max_fault_proving_delay: 0,
             max_validity_proving_delay: 0,
+            proving_policy: Default::default(),
+            watchdog: Default::default(),
+            watch_only: false,
+            fault_report_dir: None,
+            export_bundle_dir: None,
+            bundle_sender: None,
             l1_head_jump_back: 0,
             validator_signer: ValidatorSignerArgs::from(
                 "0x92db14e403b83dfe3df233f83dfa3a0d7096f21ca9b0d6d6b8d88b2b4ec1564e".to_string(),
@@ -336,10 +370,14 @@ async fn proposer_validator() {
                 skip_derivation_proof: false,
                 skip_await_proof: false,
                 clear_cache_data: true,
+                proof_backend: vec![],
+                proof_backend_timeout: None,
+                proof_store: Default::default(),
                 hokulea: Default::default(),
                 hana: Default::default(),
             },
             boundless: Default::default(),
+            bonds: Default::default(),
         },
         3,
         validator_data_dir.clone(),
@@ -350,6 +388,7 @@ async fn proposer_validator() {
             bypass_chain_registry: false,
             proposer_signer: proposer_signer.clone(),
             txn_args: txn_args.clone(),
+            bonds: Default::default(),
         },
         proposer_data_dir.clone(),
     ));
@@ -386,6 +425,8 @@ async fn prover() {
             op_geth_url: "http://127.0.0.1:9545".to_string(),
             op_node_url: "http://127.0.0.1:7545".to_string(),
             op_rpc_delay: 0,
+            op_node_quorum: None,
+            verify_output_roots: false,
             beacon_rpc_url: "http://127.0.0.1:5052".to_string(),
         },
         kailua_game_implementation: None,
@@ -468,6 +509,9 @@ async fn prover() {
             skip_derivation_proof: false,
             skip_await_proof: false,
             clear_cache_data: true,
+            proof_backend: vec![],
+            proof_backend_timeout: None,
+            proof_store: Default::default(),
             hokulea: Default::default(),
             hana: Default::default(),
         },
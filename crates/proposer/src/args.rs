@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use kailua_sync::args::SyncArgs;
+use kailua_sync::bonds::BondManagerArgs;
 use kailua_sync::transact::signer::ProposerSignerArgs;
 use kailua_sync::transact::TransactArgs;
 
@@ -32,4 +33,7 @@ pub struct ProposeArgs {
     /// Transaction publication configuration
     #[clap(flatten)]
     pub txn_args: TransactArgs,
+    /// Treasury bond management configuration
+    #[clap(flatten)]
+    pub bonds: BondManagerArgs,
 }
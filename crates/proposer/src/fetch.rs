@@ -12,18 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloy::consensus::BlockHeader;
-use alloy::eips::BlockNumberOrTag;
-use alloy::network::BlockResponse;
 use alloy::primitives::{Address, U256};
 use kailua_contracts::*;
 use kailua_sync::agent::SyncAgent;
-use kailua_sync::await_tel;
 use kailua_sync::proposal::Proposal;
 use kailua_sync::stall::Stall;
-use kailua_sync::transact::rpc::get_block;
 use opentelemetry::global::tracer;
-use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use opentelemetry::trace::{TraceContextExt, Tracer};
 
 pub async fn fetch_vanguard(agent: &SyncAgent) -> Address {
     let tracer = tracer("kailua");
@@ -64,21 +59,7 @@ pub async fn fetch_paid_bond(agent: &SyncAgent, address: Address) -> U256 {
 }
 
 pub async fn fetch_current_challenger_duration(agent: &SyncAgent, proposal: &Proposal) -> u64 {
-    let tracer = tracer("kailua");
-    let context = opentelemetry::Context::current_with_span(
-        tracer.start("Proposal::fetch_current_challenger_duration"),
-    );
-
-    let chain_time = await_tel!(
-        context,
-        get_block(&agent.provider.l1_provider, BlockNumberOrTag::Latest)
-    )
-    .header()
-    .timestamp();
-
     proposal
-        .tournament_contract_instance(&agent.provider.l1_provider)
-        .getChallengerDuration(U256::from(chain_time))
-        .stall_with_context(context.clone(), "KailuaTournament::getChallengerDuration")
+        .fetch_current_challenger_duration(&agent.provider.l1_provider)
         .await
 }
@@ -27,7 +27,9 @@ use anyhow::{bail, Context};
 use kailua_contracts::*;
 use kailua_kona::blobs::hash_to_fe;
 use kailua_sync::agent::{SyncAgent, FINAL_L2_BLOCK_RESOLVED};
+use kailua_sync::bonds::claim_bonds;
 use kailua_sync::proposal::Proposal;
+use kailua_sync::provider::failover_client;
 use kailua_sync::stall::Stall;
 use kailua_sync::transact::provider::SafeProvider;
 use kailua_sync::transact::rpc::get_block;
@@ -37,7 +39,7 @@ use opentelemetry::global::{meter, tracer};
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
 use opentelemetry::KeyValue;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use tracing::{error, info, warn};
 
@@ -80,7 +82,7 @@ pub async fn propose(args: ProposeArgs, data_dir: PathBuf) -> anyhow::Result<()>
         args.txn_args
             .premium_provider::<Ethereum>()
             .wallet(&proposer_wallet)
-            .connect_http(args.sync.provider.eth_rpc_url.as_str().try_into()?),
+            .connect_client(failover_client(&args.sync.provider.eth_rpc_url)?),
     );
     info!("Proposer address: {proposer_address}");
 
@@ -92,6 +94,7 @@ pub async fn propose(args: ProposeArgs, data_dir: PathBuf) -> anyhow::Result<()>
 
     // on startup, prioritize submitting a proposal
     let mut prioritize_proposing = true;
+    let mut last_bond_claim: Option<Instant> = None;
     loop {
         // Wait for new data on every iteration
         sleep(Duration::from_secs(1)).await;
@@ -107,6 +110,22 @@ pub async fn propose(args: ProposeArgs, data_dir: PathBuf) -> anyhow::Result<()>
                 .to_string()
                 .contains(FINAL_L2_BLOCK_RESOLVED)
             {
+                // reclaim the proposer bond now that all proposals are resolved
+                if args.bonds.claim_bonds {
+                    if let Err(err) = await_tel!(
+                        context,
+                        claim_bonds(
+                            &proposer_provider,
+                            agent.deployment.treasury,
+                            proposer_address,
+                            true,
+                            &args.bonds.claim,
+                            &args.txn_args
+                        )
+                    ) {
+                        error!("Failed to claim bonds: {err:?}");
+                    }
+                }
                 return Ok(());
             }
             error!("Synchronization error: {err:?}");
@@ -152,17 +171,54 @@ pub async fn propose(args: ProposeArgs, data_dir: PathBuf) -> anyhow::Result<()>
         // Reset priority
         prioritize_proposing = false;
 
-        // Check if deployment is still valid
-        let dispute_game_factory =
-            IDisputeGameFactory::new(agent.deployment.factory, &agent.provider.l1_provider);
-        let latest_game_impl_addr = dispute_game_factory
-            .gameImpls(KAILUA_GAME_TYPE)
-            .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
-            .await;
-        if latest_game_impl_addr != agent.deployment.game {
-            warn!("Not proposing. Deployment {} outdated. Found new deployment {latest_game_impl_addr}.", agent.deployment.game);
+        // Periodically claim bonds owed by the treasury
+        if args.bonds.claim_bonds
+            && last_bond_claim.is_none_or(|instant| {
+                instant.elapsed() >= Duration::from_secs(args.bonds.bond_claim_interval)
+            })
+        {
+            last_bond_claim = Some(Instant::now());
+            // the proposer bond is only reclaimed once no more proposals will be made
+            let final_proposal_made = args.sync.final_l2_block.is_some_and(|final_l2_block| {
+                agent
+                    .canonical_tip()
+                    .is_some_and(|tip| tip.output_block_number >= final_l2_block)
+            });
+            match await_tel!(
+                context,
+                claim_bonds(
+                    &proposer_provider,
+                    agent.deployment.treasury,
+                    proposer_address,
+                    final_proposal_made,
+                    &args.bonds.claim,
+                    &args.txn_args
+                )
+            ) {
+                Ok(claimed) if !claimed.is_zero() => info!("Claimed {claimed} wei in bonds."),
+                Ok(_) => {}
+                Err(err) => error!("Failed to claim bonds: {err:?}"),
+            }
+        }
+
+        // Wait for a pending deployment upgrade to take effect
+        if let Some(upgrade_index) = agent.upgrades.first_key_value().map(|(i, _)| *i) {
+            warn!("Not proposing. Deployment upgrade at index {upgrade_index} is pending.");
             continue;
         }
+        // Check if a pinned deployment is still valid
+        if agent.upgrade_scan_block.is_none() {
+            let dispute_game_factory =
+                IDisputeGameFactory::new(agent.deployment.factory, &agent.provider.l1_provider);
+            let latest_game_impl_addr = dispute_game_factory
+                .gameImpls(KAILUA_GAME_TYPE)
+                .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
+                .await;
+            if latest_game_impl_addr != agent.deployment.game {
+                warn!("Not proposing. Deployment {} outdated. Found new deployment {latest_game_impl_addr}.", agent.deployment.game);
+                continue;
+            }
+        }
 
         // Submit proposal to extend canonical chain
         let Some(canonical_tip) = agent.canonical_tip() else {
@@ -354,10 +410,10 @@ pub async fn propose(args: ProposeArgs, data_dir: PathBuf) -> anyhow::Result<()>
             transaction = transaction.sidecar(sidecar);
         }
         match transaction
-            .timed_transact_with_context(
+            .replaceable_transact_with_context(
                 context.clone(),
                 "KailuaTreasury::propose",
-                Some(Duration::from_secs(args.txn_args.txn_timeout)),
+                &args.txn_args,
             )
             .await
             .context("KailuaTreasury::propose")
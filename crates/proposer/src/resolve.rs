@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::fetch::fetch_current_challenger_duration;
-use alloy::network::{Network, ReceiptResponse};
+use alloy::network::{Network, ReceiptResponse, TransactionBuilder4844};
 use alloy::primitives::{Address, U256};
 use alloy::providers::Provider;
 use anyhow::{bail, Context};
@@ -28,7 +28,6 @@ use opentelemetry::metrics::{Counter, Gauge};
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
 use opentelemetry::KeyValue;
 use std::future::IntoFuture;
-use std::time::Duration;
 use tracing::{debug, error, info};
 
 #[allow(clippy::too_many_arguments)]
@@ -118,10 +117,10 @@ pub async fn resolve_next_pending_proposal<P: Provider>(
             info!("Eliminating {ELIMINATIONS_LIMIT} opponents before resolution.");
             match resolved_parent_contract
                 .pruneChildren(U256::from(ELIMINATIONS_LIMIT))
-                .timed_transact_with_context(
+                .replaceable_transact_with_context(
                     context.clone(),
                     "KailuaTournament::pruneChildren",
-                    Some(Duration::from_secs(txn_args.txn_timeout)),
+                    txn_args,
                 )
                 .await
                 .context("KailuaTournament::pruneChildren transact")
@@ -258,7 +257,10 @@ pub async fn resolve_proposal<P: Provider<N>, N: Network>(
     proposal: &Proposal,
     provider: P,
     txn_args: &TransactArgs,
-) -> anyhow::Result<N::ReceiptResponse> {
+) -> anyhow::Result<N::ReceiptResponse>
+where
+    N::TransactionRequest: TransactionBuilder4844,
+{
     let tracer = tracer("kailua");
     let context = opentelemetry::Context::current_with_span(tracer.start("Proposal::resolve"));
 
@@ -291,10 +293,10 @@ pub async fn resolve_proposal<P: Provider<N>, N: Network>(
         info!("Eliminating {ELIMINATIONS_LIMIT} opponents before resolution.");
         let receipt = parent_tournament_instance
             .pruneChildren(U256::from(ELIMINATIONS_LIMIT))
-            .timed_transact_with_context(
+            .replaceable_transact_with_context(
                 context.clone(),
                 "KailuaTournament::pruneChildren",
-                Some(Duration::from_secs(txn_args.txn_timeout)),
+                txn_args,
             )
             .await
             .context("KailuaTournament::pruneChildren")?;
@@ -307,11 +309,7 @@ pub async fn resolve_proposal<P: Provider<N>, N: Network>(
     // Issue resolution call
     let receipt = contract_instance
         .resolve()
-        .timed_transact_with_context(
-            context.clone(),
-            "KailuaTournament::resolve",
-            Some(Duration::from_secs(txn_args.txn_timeout)),
-        )
+        .replaceable_transact_with_context(context.clone(), "KailuaTournament::resolve", txn_args)
         .await
         .context("KailuaTournament::resolve")?;
     info!("KailuaTournament::resolve: {} gas", receipt.gas_used());
@@ -14,7 +14,9 @@
 
 use crate::hana::args::HanaArgs;
 use crate::hokulea::args::HokuleaArgs;
+use crate::risczero::backend::ProofBackendKind;
 use crate::risczero::boundless::BoundlessArgs;
+use crate::store::ProofStoreArgs;
 use alloy_primitives::{Address, B256};
 use clap::Parser;
 use futures::FutureExt;
@@ -66,7 +68,15 @@ pub struct ProvingArgs {
     /// Whether to keep cache data after successful completion
     #[clap(long, env, default_value_t = false)]
     pub clear_cache_data: bool,
+    /// Comma-separated proving backends to try in order of preference
+    #[clap(long, env, value_delimiter = ',')]
+    pub proof_backend: Vec<ProofBackendKind>,
+    /// Seconds to await a proof from a backend before falling back to the next one
+    #[clap(long, env)]
+    pub proof_backend_timeout: Option<u64>,
 
+    #[clap(flatten)]
+    pub proof_store: ProofStoreArgs,
     #[clap(flatten)]
     pub hokulea: HokuleaArgs,
     #[clap(flatten)]
@@ -99,6 +109,22 @@ impl ProvingArgs {
             .into_iter()
             .flatten(),
         );
+        if !self.proof_backend.is_empty() {
+            proving_args.extend(vec![
+                String::from("--proof-backend"),
+                self.proof_backend
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ]);
+        }
+        if let Some(proof_backend_timeout) = self.proof_backend_timeout {
+            proving_args.extend(vec![
+                String::from("--proof-backend-timeout"),
+                proof_backend_timeout.to_string(),
+            ]);
+        }
         if let Some(payout_recipient_address) = &self.payout_recipient_address {
             proving_args.extend(vec![
                 // wallet address for payouts
@@ -106,6 +132,8 @@ impl ProvingArgs {
                 payout_recipient_address.to_string(),
             ]);
         }
+        // Proof store
+        proving_args.extend(self.proof_store.to_arg_vec());
         // Hokulea
         proving_args.extend(self.hokulea.to_arg_vec());
         // Hana
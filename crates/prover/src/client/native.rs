@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::args::ProveArgs;
+use crate::estimate::EstimateSink;
 use crate::kv::{create_disk_kv_store, create_split_kv_store, RWLKeyValueStore};
 use crate::ProvingError;
 use alloy_primitives::B256;
@@ -56,6 +57,7 @@ pub async fn run_native_client(
     prove_snark: bool,
     force_attempt: bool,
     seek_proof: bool,
+    estimate_sink: Option<EstimateSink>,
 ) -> Result<(), ProvingError> {
     // Instantiate data channels
     let hint = BidirectionalChannel::new().map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
@@ -147,6 +149,7 @@ pub async fn run_native_client(
         prove_snark,
         force_attempt,
         seek_proof,
+        estimate_sink,
     ));
     // Wait for both tasks to complete.
     info!("Starting preimage server and client program.");
@@ -14,6 +14,7 @@
 
 use crate::args::ProvingArgs;
 use crate::client::witgen;
+use crate::estimate::EstimateSink;
 use crate::risczero::boundless::BoundlessArgs;
 use crate::ProvingError;
 use alloy_primitives::B256;
@@ -61,6 +62,7 @@ pub async fn run_proving_client<P, H>(
     prove_snark: bool,
     force_attempt: bool,
     seek_proof: bool,
+    estimate_sink: Option<EstimateSink>,
 ) -> Result<(), ProvingError>
 where
     P: PreimageOracleClient + Send + Sync + Debug + Clone + 'static,
@@ -121,6 +123,7 @@ where
                     eth_rpc_url: l1_node_address.expect("Missing Hokulea L1 Node Provider"),
                     proving_args: proving.clone(),
                     boundless_args: boundless.clone(),
+                    estimate_sink: estimate_sink.clone(),
                 };
 
                 // todo: concurrency via generic prover pool
@@ -206,6 +209,7 @@ where
         witness_frames,
         [stitched_proofs, extra_proofs].concat(),
         prove_snark,
+        estimate_sink,
     )
     .await
 }
@@ -0,0 +1,84 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::args::ProveArgs;
+use crate::prove::prove_with_estimates;
+use crate::risczero::backend::ProofBackendKind;
+use crate::risczero::boundless::{BoundlessArgs, DEFAULT_CYCLE_MAX_WEI, DEFAULT_CYCLE_MIN_WEI};
+use alloy_primitives::{B256, U256};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Where the executor backend records the estimates of a proving run
+pub type EstimateSink = Arc<Mutex<Vec<ProofEstimate>>>;
+
+/// The measured cost of proving a single guest execution
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProofEstimate {
+    /// Hash of the image id and journal of the proof
+    pub proof_id: B256,
+    /// Last L2 block covered by the proof, if it is an FPVM proof
+    pub claimed_l2_block_number: Option<u64>,
+    /// Number of segments the execution was split into
+    pub segments: usize,
+    /// Number of cycles that require proving, including padding
+    pub total_cycles: u64,
+    /// Number of cycles spent executing guest code
+    pub user_cycles: u64,
+    /// Size in bytes of the guest input excluding stitched proofs
+    pub witness_size: usize,
+    /// Number of proofs verified by the guest
+    pub stitched_proofs: usize,
+    /// Whether the cycle count was recorded for reuse by Boundless requests
+    pub recorded: bool,
+}
+
+/// Runs witness generation followed by an executor-only zkVM pass for each proof that proving the
+/// given range would require, without producing any proofs.
+///
+/// The workload is split exactly as it would be when proving, and the cycle count of every
+/// execution is recorded for reuse by later Boundless requests instead of preflighting.
+/// Proofs that stitch other proofs together can not be executed without their receipts, so the
+/// derivation proof of a range split into multiple execution proofs is not estimated.
+pub async fn estimate(mut args: ProveArgs) -> anyhow::Result<Vec<ProofEstimate>> {
+    let sink = EstimateSink::default();
+    args.proving.proof_backend = vec![ProofBackendKind::Executor];
+    args.proving.skip_await_proof = true;
+    prove_with_estimates(args, Some(sink.clone())).await?;
+    let mut estimates = std::mem::take(&mut *sink.lock().await);
+    estimates.sort_by_key(|e| (e.claimed_l2_block_number, e.proof_id));
+    Ok(estimates)
+}
+
+/// Returns the starting and maximum prices (wei) of a Boundless order for the cycles, using the
+/// default per-cycle prices if no market is configured
+pub fn projected_price(cycle_count: u64, boundless: &BoundlessArgs) -> (U256, U256) {
+    let (cycle_min_wei, cycle_max_wei) = match &boundless.market {
+        Some(market) => (
+            market.boundless_cycle_min_wei,
+            market.boundless_cycle_max_wei,
+        ),
+        None => (
+            DEFAULT_CYCLE_MIN_WEI
+                .parse()
+                .expect("Invalid default cycle price"),
+            DEFAULT_CYCLE_MAX_WEI
+                .parse()
+                .expect("Invalid default cycle price"),
+        ),
+    };
+    let cycles = U256::from(cycle_count);
+    (cycle_min_wei * cycles, cycle_max_wei * cycles)
+}
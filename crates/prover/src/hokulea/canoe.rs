@@ -13,9 +13,10 @@
 // limitations under the License.
 
 use crate::args::ProvingArgs;
-use crate::proof::{proof_file_name, read_bincoded_file};
+use crate::estimate::EstimateSink;
 use crate::risczero::boundless::BoundlessArgs;
 use crate::risczero::seek_proof;
+use crate::store::ProofStore;
 use alloy::transports::http::reqwest::Url;
 use anyhow::{anyhow, bail, Context};
 use async_trait::async_trait;
@@ -43,6 +44,8 @@ pub struct KailuaCanoeSteelProvider {
     pub proving_args: ProvingArgs,
     /// Boundless arguments
     pub boundless_args: BoundlessArgs,
+    /// Where executor estimates are recorded
+    pub estimate_sink: Option<EstimateSink>,
 }
 
 #[async_trait]
@@ -116,7 +119,7 @@ impl CanoeProvider for KailuaCanoeSteelProvider {
         ));
 
         // todo: dynamic lookup of KAILUA_DA_HOKULEA_ID corresponding to KAILUA_FPVM_HOKULEA_ID
-        let file_name = proof_file_name(kailua_build::KAILUA_DA_HOKULEA_ID, journal.clone());
+        let file_name = ProofStore::key(kailua_build::KAILUA_DA_HOKULEA_ID, journal.clone());
 
         seek_proof(
             &self.proving_args,
@@ -130,11 +133,15 @@ impl CanoeProvider for KailuaCanoeSteelProvider {
             vec![],
             vec![],
             false,
+            self.estimate_sink.clone(),
         )
         .await
         .map_err(|err| anyhow!(err))?;
 
-        read_bincoded_file(&file_name)
+        ProofStore::new(&self.proving_args.proof_store)
+            .await
+            .context("ProofStore::new")?
+            .load(&file_name)
             .await
             .context(format!("Failed to read proof file {file_name} contents."))
     }
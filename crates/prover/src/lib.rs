@@ -18,6 +18,7 @@ pub mod args;
 pub mod channel;
 pub mod client;
 pub mod config;
+pub mod estimate;
 pub mod hana;
 pub mod hokulea;
 pub mod kv;
@@ -25,6 +26,7 @@ pub mod preflight;
 pub mod proof;
 pub mod prove;
 pub mod risczero;
+pub mod store;
 pub mod tasks;
 
 #[derive(Debug, thiserror::Error)]
@@ -219,6 +219,7 @@ pub async fn concurrent_execution_preflight(
             false,
             true,
             false,
+            None,
         )));
         // jobs.push(args.clone());
         // update starting block for next job
@@ -15,6 +15,7 @@
 use crate::args::ProveArgs;
 use crate::channel::AsyncChannel;
 use crate::config::generate_rollup_config_file;
+use crate::estimate::EstimateSink;
 use crate::kv::create_disk_kv_store;
 use crate::preflight::{concurrent_execution_preflight, fetch_precondition_data};
 use crate::tasks::{handle_oneshot_tasks, Cached, Oneshot, OneshotResult};
@@ -37,7 +38,16 @@ use tempfile::tempdir;
 use tokio::fs::remove_dir_all;
 use tracing::{error, info, warn};
 
-pub async fn prove(mut args: ProveArgs) -> anyhow::Result<()> {
+pub async fn prove(args: ProveArgs) -> anyhow::Result<()> {
+    prove_with_estimates(args, None).await
+}
+
+/// Proves the range like [prove], recording the cost of every proof measured by the executor
+/// backend into the sink
+pub async fn prove_with_estimates(
+    mut args: ProveArgs,
+    estimate_sink: Option<EstimateSink>,
+) -> anyhow::Result<()> {
     let tracer = tracer("kailua");
     let context = opentelemetry::Context::current_with_span(tracer.start("prove"));
 
@@ -235,6 +245,7 @@ pub async fn prove(mut args: ProveArgs) -> anyhow::Result<()> {
             let disk_kv_store = disk_kv_store.clone();
             let task_channel = task_channel.clone();
             let result_channel = result_channel.clone();
+            let estimate_sink = estimate_sink.clone();
             tokio::spawn(async move {
                 let result = crate::tasks::compute_fpvm_proof(
                     job_args.clone(),
@@ -245,6 +256,7 @@ pub async fn prove(mut args: ProveArgs) -> anyhow::Result<()> {
                     vec![],
                     vec![],
                     !have_split,
+                    estimate_sink,
                     task_channel.0.clone(),
                 )
                 .await;
@@ -283,6 +295,7 @@ pub async fn prove(mut args: ProveArgs) -> anyhow::Result<()> {
                     prove_snark: false,
                     force_attempt,
                     seek_proof: true,
+                    estimate_sink: None,
                 };
                 if proof.is_some() {
                     info!(
@@ -445,6 +458,7 @@ pub async fn prove(mut args: ProveArgs) -> anyhow::Result<()> {
                 stitched_boot_info,
                 proofs,
                 true,
+                estimate_sink,
                 task_channel.0.clone(),
             )
             .await
@@ -0,0 +1,501 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::args::ProvingArgs;
+use crate::estimate::EstimateSink;
+use crate::risczero::bonsai::{should_use_bonsai, BonsaiBackend};
+use crate::risczero::boundless::{BoundlessArgs, BoundlessBackend};
+use crate::risczero::executor::ExecutorBackend;
+use crate::risczero::zkvm::ZkvmBackend;
+use crate::ProvingError;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use boundless_market::GuestEnv;
+use risc0_zkvm::serde::to_vec;
+use risc0_zkvm::{ExecutorEnv, InnerReceipt, Journal, Receipt};
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+/// Number of times every backend is tried before giving up on a proof
+pub const PROOF_BACKEND_ROUNDS: usize = 3;
+
+/// The services that can be used to generate proofs
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofBackendKind {
+    /// The decentralized Boundless proving network
+    Boundless,
+    /// The Bonsai proving service
+    Bonsai,
+    /// The local zkVM prover
+    Zkvm,
+    /// The local zkVM executor, which measures proving costs without producing proofs
+    Executor,
+}
+
+impl Display for ProofBackendKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProofBackendKind::Boundless => write!(f, "boundless"),
+            ProofBackendKind::Bonsai => write!(f, "bonsai"),
+            ProofBackendKind::Zkvm => write!(f, "zkvm"),
+            ProofBackendKind::Executor => write!(f, "executor"),
+        }
+    }
+}
+
+/// The progress of a submitted proving job
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofStatus {
+    Pending,
+    Ready,
+}
+
+/// A service that generates proofs asynchronously
+#[async_trait]
+pub trait ProofBackend: Send {
+    /// The name of the backend for reporting
+    fn name(&self) -> &'static str;
+
+    /// The duration to wait between polls of the submitted job
+    fn poll_interval(&self) -> Duration;
+
+    /// Dispatches a proving job for the given input
+    async fn submit(&mut self, input: &ProofInput) -> Result<(), ProvingError>;
+
+    /// Checks whether the proof of the submitted job is ready to be retrieved
+    async fn poll(&mut self) -> Result<ProofStatus, ProvingError>;
+
+    /// Fetches and verifies the proof of the completed job
+    async fn retrieve(&mut self) -> Result<Receipt, ProvingError>;
+
+    /// Abandons the submitted job
+    async fn cancel(&mut self) -> Result<(), ProvingError>;
+}
+
+/// The program and guest input data of a proof
+#[derive(Clone, Debug)]
+pub struct ProofInput {
+    pub image_id: [u32; 8],
+    pub elf: &'static [u8],
+    pub journal: Journal,
+    pub witness_slices: Vec<Vec<u32>>,
+    pub witness_frames: Vec<Vec<u8>>,
+    pub stitched_proofs: Vec<Receipt>,
+    pub prove_snark: bool,
+}
+
+impl ProofInput {
+    /// Whether a stitched receipt must be verified in the guest instead of as an assumption
+    pub fn is_guest_verified(receipt: &Receipt) -> bool {
+        // Force in-guest verification (should be used for testing only)
+        if std::env::var("KAILUA_FORCE_RECURSION").is_ok() {
+            warn!("(KAILUA_FORCE_RECURSION) Forcibly loading receipt as guest input.");
+            return true;
+        }
+        matches!(receipt.inner, InnerReceipt::Groth16(_))
+    }
+
+    /// Builds the environment for local execution, optionally resolving stitched receipts as
+    /// assumptions instead of verifying them in the guest
+    #[allow(deprecated)]
+    pub fn executor_env<'a>(
+        &self,
+        segment_limit: u32,
+        add_assumptions: bool,
+    ) -> anyhow::Result<ExecutorEnv<'a>> {
+        let mut builder = ExecutorEnv::builder();
+        // Set segment po2
+        builder.segment_limit_po2(segment_limit);
+        // Pass in witness data slices
+        for slice in &self.witness_slices {
+            builder.write_slice(slice);
+        }
+        // Pass in witness data frames
+        for frame in &self.witness_frames {
+            builder.write_frame(frame);
+        }
+        // Dev-mode for recursive proofs
+        if risc0_zkvm::is_dev_mode() {
+            builder.env_var("RISC0_DEV_MODE", "1");
+        }
+        // Pass in proofs
+        for receipt in &self.stitched_proofs {
+            if add_assumptions && !Self::is_guest_verified(receipt) {
+                builder.add_assumption(receipt.clone());
+            } else {
+                builder.write(receipt)?;
+            }
+        }
+        builder.build()
+    }
+
+    /// Serializes the guest input for Bonsai, returning the stitched receipts that must be
+    /// uploaded as assumptions separately
+    pub fn bonsai_input(&self) -> anyhow::Result<(Vec<u8>, Vec<Receipt>)> {
+        let mut input: Vec<u8> = Vec::new();
+        // Load witness data slices
+        for slice in &self.witness_slices {
+            input.extend_from_slice(bytemuck::cast_slice(slice.as_slice()));
+        }
+        // Load witness data frames
+        for frame in &self.witness_frames {
+            let witness_len = frame.len() as u32;
+            input.extend_from_slice(&witness_len.to_le_bytes());
+            input.extend_from_slice(frame.as_slice());
+        }
+        // Load recursive proofs
+        let mut assumptions = vec![];
+        for receipt in &self.stitched_proofs {
+            if Self::is_guest_verified(receipt) {
+                input.extend_from_slice(bytemuck::cast_slice(&to_vec(receipt)?));
+            } else {
+                assumptions.push(receipt.clone());
+            }
+        }
+        Ok((input, assumptions))
+    }
+
+    /// Serializes the guest input for Boundless, where all stitched receipts are verified in the
+    /// guest
+    pub fn guest_env_input(&self) -> anyhow::Result<Vec<u8>> {
+        let mut guest_env_builder = GuestEnv::builder();
+        // Pass in input slices
+        for slice in &self.witness_slices {
+            guest_env_builder = guest_env_builder.write_slice(slice);
+        }
+        // Pass in input frames
+        for frame in &self.witness_frames {
+            guest_env_builder = guest_env_builder.write_frame(frame);
+        }
+        // Pass in proofs
+        for proof in &self.stitched_proofs {
+            guest_env_builder = guest_env_builder.write(proof)?;
+        }
+        // Build input vector
+        Ok(guest_env_builder.build_vec()?)
+    }
+}
+
+/// Picks the backend implied by the configured proving services
+#[allow(deprecated)]
+pub fn default_backend_kind(boundless: &BoundlessArgs) -> ProofBackendKind {
+    if boundless.market.is_some() && boundless.storage.is_some() && !risc0_zkvm::is_dev_mode() {
+        ProofBackendKind::Boundless
+    } else if should_use_bonsai() {
+        ProofBackendKind::Bonsai
+    } else {
+        ProofBackendKind::Zkvm
+    }
+}
+
+/// Instantiates the selected backends in order of preference, skipping Boundless if no market
+/// or storage provider is configured for it, and recording executor estimates into the sink
+pub fn proof_backends(
+    proving: &ProvingArgs,
+    boundless: BoundlessArgs,
+    estimate_sink: Option<EstimateSink>,
+) -> Result<Vec<Box<dyn ProofBackend>>, ProvingError> {
+    let kinds = if proving.proof_backend.is_empty() {
+        vec![default_backend_kind(&boundless)]
+    } else {
+        proving.proof_backend.clone()
+    };
+    let mut backends: Vec<Box<dyn ProofBackend>> = Vec::with_capacity(kinds.len());
+    for kind in kinds {
+        match kind {
+            ProofBackendKind::Boundless => {
+                let (Some(market), Some(storage)) =
+                    (boundless.market.clone(), boundless.storage.clone())
+                else {
+                    warn!("Skipping Boundless backend without market and storage provider configuration.");
+                    continue;
+                };
+                backends.push(Box::new(BoundlessBackend::new(
+                    market,
+                    storage,
+                    boundless.r2_domain.clone(),
+                    proving.clone(),
+                )));
+            }
+            ProofBackendKind::Bonsai => backends.push(Box::new(BonsaiBackend::new(proving)?)),
+            ProofBackendKind::Zkvm => backends.push(Box::new(ZkvmBackend::new(proving))),
+            ProofBackendKind::Executor => backends.push(Box::new(ExecutorBackend::new(
+                proving,
+                estimate_sink.clone(),
+            ))),
+        }
+    }
+    if backends.is_empty() {
+        return Err(ProvingError::OtherError(anyhow!(
+            "No configured proving backend is available."
+        )));
+    }
+    Ok(backends)
+}
+
+/// Seeks a proof from each backend in order, falling back to the next one whenever a backend
+/// fails or times out, and starting over until all backends have failed
+/// [PROOF_BACKEND_ROUNDS] times
+pub async fn prove_with_fallback(
+    backends: &mut [Box<dyn ProofBackend>],
+    input: &ProofInput,
+    timeout: Option<Duration>,
+) -> Result<Receipt, ProvingError> {
+    let mut last_error = None;
+    for round in 1..=PROOF_BACKEND_ROUNDS {
+        if round > 1 {
+            warn!("(Retrying) All proving backends failed.");
+            sleep(Duration::from_secs(1)).await;
+        }
+        for backend in backends.iter_mut() {
+            match run_backend(backend.as_mut(), input, timeout).await {
+                Ok(receipt) => return Ok(receipt),
+                // the job was dispatched without awaiting it or the input can not be proven
+                Err(err @ (ProvingError::NotAwaitingProof | ProvingError::ExecutionError(_))) => {
+                    return Err(err)
+                }
+                Err(err) => {
+                    error!("Proving backend {} failed: {err:?}", backend.name());
+                    last_error = Some(err);
+                }
+            }
+        }
+    }
+    Err(ProvingError::OtherError(anyhow!(
+        "All proving backends failed {PROOF_BACKEND_ROUNDS} times. Last error: {:?}",
+        last_error
+    )))
+}
+
+/// Submits the job to the backend and awaits its proof until the timeout elapses
+pub async fn run_backend(
+    backend: &mut dyn ProofBackend,
+    input: &ProofInput,
+    timeout: Option<Duration>,
+) -> Result<Receipt, ProvingError> {
+    info!("Seeking proof using {} backend.", backend.name());
+    let start = Instant::now();
+    backend.submit(input).await?;
+    loop {
+        let status = match backend.poll().await {
+            Ok(status) => status,
+            Err(err) => {
+                cancel_backend(backend).await;
+                return Err(err);
+            }
+        };
+        if status == ProofStatus::Ready {
+            return backend.retrieve().await;
+        }
+        if let Some(timeout) = timeout.filter(|timeout| start.elapsed() >= *timeout) {
+            cancel_backend(backend).await;
+            return Err(ProvingError::OtherError(anyhow!(
+                "{} backend timed out after {} seconds.",
+                backend.name(),
+                timeout.as_secs()
+            )));
+        }
+        sleep(backend.poll_interval()).await;
+    }
+}
+
+async fn cancel_backend(backend: &mut dyn ProofBackend) {
+    if let Err(err) = backend.cancel().await {
+        warn!("Failed to cancel {} proving job: {err:?}", backend.name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use risc0_zkvm::{FakeReceipt, ReceiptClaim};
+    use std::sync::{Arc, Mutex};
+
+    /// How a mock backend responds to a proving job
+    #[derive(Clone, Copy)]
+    enum Behavior {
+        Prove,
+        FailSubmit,
+        FailPoll,
+        Hang,
+        Dispatch,
+    }
+
+    struct MockBackend {
+        name: &'static str,
+        behavior: Behavior,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockBackend {
+        fn boxed(
+            name: &'static str,
+            behavior: Behavior,
+            log: &Arc<Mutex<Vec<String>>>,
+        ) -> Box<dyn ProofBackend> {
+            Box::new(Self {
+                name,
+                behavior,
+                log: log.clone(),
+            })
+        }
+
+        fn record(&self, event: &str) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{}:{event}", self.name));
+        }
+    }
+
+    #[async_trait]
+    impl ProofBackend for MockBackend {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn poll_interval(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        async fn submit(&mut self, _input: &ProofInput) -> Result<(), ProvingError> {
+            self.record("submit");
+            match self.behavior {
+                Behavior::FailSubmit => Err(ProvingError::OtherError(anyhow!("submit"))),
+                Behavior::Dispatch => Err(ProvingError::NotAwaitingProof),
+                _ => Ok(()),
+            }
+        }
+
+        async fn poll(&mut self) -> Result<ProofStatus, ProvingError> {
+            match self.behavior {
+                Behavior::FailPoll => Err(ProvingError::OtherError(anyhow!("poll"))),
+                Behavior::Hang => Ok(ProofStatus::Pending),
+                _ => Ok(ProofStatus::Ready),
+            }
+        }
+
+        async fn retrieve(&mut self) -> Result<Receipt, ProvingError> {
+            self.record("retrieve");
+            Ok(Receipt::new(
+                InnerReceipt::Fake(FakeReceipt::new(ReceiptClaim::ok([0u32; 8], vec![]))),
+                vec![],
+            ))
+        }
+
+        async fn cancel(&mut self) -> Result<(), ProvingError> {
+            self.record("cancel");
+            Ok(())
+        }
+    }
+
+    fn input() -> ProofInput {
+        ProofInput {
+            image_id: [0u32; 8],
+            elf: &[],
+            journal: Journal::new(vec![]),
+            witness_slices: vec![],
+            witness_frames: vec![],
+            stitched_proofs: vec![],
+            prove_snark: false,
+        }
+    }
+
+    fn events(log: &Arc<Mutex<Vec<String>>>) -> Vec<String> {
+        log.lock().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn test_prove_with_fallback_order() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut backends = vec![
+            MockBackend::boxed("a", Behavior::FailSubmit, &log),
+            MockBackend::boxed("b", Behavior::FailPoll, &log),
+            MockBackend::boxed("c", Behavior::Prove, &log),
+            MockBackend::boxed("d", Behavior::Prove, &log),
+        ];
+        prove_with_fallback(&mut backends, &input(), None)
+            .await
+            .unwrap();
+        // failed jobs are cancelled and later backends are never tried
+        assert_eq!(
+            events(&log),
+            vec!["a:submit", "b:submit", "b:cancel", "c:submit", "c:retrieve"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prove_with_fallback_timeout() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut backends = vec![
+            MockBackend::boxed("a", Behavior::Hang, &log),
+            MockBackend::boxed("b", Behavior::Prove, &log),
+        ];
+        prove_with_fallback(&mut backends, &input(), Some(Duration::ZERO))
+            .await
+            .unwrap();
+        assert_eq!(
+            events(&log),
+            vec!["a:submit", "a:cancel", "b:submit", "b:retrieve"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prove_with_fallback_terminal() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut backends = vec![
+            MockBackend::boxed("a", Behavior::Dispatch, &log),
+            MockBackend::boxed("b", Behavior::Prove, &log),
+        ];
+        let err = prove_with_fallback(&mut backends, &input(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProvingError::NotAwaitingProof));
+        assert_eq!(events(&log), vec!["a:submit"]);
+    }
+
+    #[tokio::test]
+    async fn test_prove_with_fallback_exhausted() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut backends = vec![
+            MockBackend::boxed("a", Behavior::FailSubmit, &log),
+            MockBackend::boxed("b", Behavior::FailPoll, &log),
+        ];
+        let err = prove_with_fallback(&mut backends, &input(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProvingError::OtherError(_)));
+        let submits = events(&log)
+            .into_iter()
+            .filter(|event| event.ends_with(":submit"))
+            .count();
+        assert_eq!(submits, 2 * PROOF_BACKEND_ROUNDS);
+    }
+
+    #[test]
+    fn test_proof_backends_skip_boundless() {
+        let proving = ProvingArgs::parse_from(["kailua", "--proof-backend", "boundless,zkvm"]);
+        let backends = proof_backends(&proving, BoundlessArgs::default(), None).unwrap();
+        assert_eq!(
+            backends.iter().map(|b| b.name()).collect::<Vec<_>>(),
+            vec![ZkvmBackend::new(&proving).name()]
+        );
+        // a chain of only unavailable backends is rejected
+        let proving = ProvingArgs::parse_from(["kailua", "--proof-backend", "boundless"]);
+        assert!(proof_backends(&proving, BoundlessArgs::default(), None).is_err());
+    }
+}
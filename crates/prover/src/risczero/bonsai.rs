@@ -14,61 +14,89 @@
 
 use crate::args::ProvingArgs;
 use crate::client::proving::{acquire_owned_permit, SEMAPHORE_R0VM};
+use crate::risczero::backend::{ProofBackend, ProofInput, ProofStatus};
 use crate::ProvingError;
 use anyhow::{anyhow, Context};
+use async_trait::async_trait;
 use bonsai_sdk::non_blocking::{Client, SessionId, SnarkId};
 use bonsai_sdk::responses::SessionStats;
 use bytemuck::NoUninit;
 use human_bytes::human_bytes;
 use kailua_sync::{retry_res, retry_res_timeout};
-use risc0_zkvm::serde::to_vec;
 use risc0_zkvm::sha::Digest;
-use risc0_zkvm::{InnerReceipt, Receipt};
+use risc0_zkvm::Receipt;
 use std::time::Duration;
+use tokio::sync::OwnedSemaphorePermit;
 use tokio::time::sleep;
 use tracing::log::warn;
 use tracing::{error, info};
 
-pub async fn run_bonsai_client<A: NoUninit + Into<Digest>>(
-    image: (A, &[u8]),
-    witness_slices: Vec<Vec<u32>>,
-    witness_frames: Vec<Vec<u8>>,
-    stitched_proofs: Vec<Receipt>,
+/// Delegates proving to the Bonsai proving service
+pub struct BonsaiBackend {
+    skip_await_proof: bool,
+    polling_interval: Duration,
+    job: Option<BonsaiJob>,
+}
+
+/// A proving workflow running on Bonsai
+struct BonsaiJob {
+    client: Client,
+    image_id: [u32; 8],
     prove_snark: bool,
-    proving_args: &ProvingArgs,
-) -> Result<Receipt, ProvingError> {
-    info!("Running Bonsai client.");
-    // Instantiate client
-    let client =
-        Client::from_env(risc0_zkvm::VERSION).map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
-    // Prepare input payload
-    let mut input: Vec<u8> = Vec::new();
-    // Load witness data slices
-    for slice in witness_slices {
-        input.extend_from_slice(bytemuck::cast_slice(slice.as_slice()));
+    r0vm_permit: Option<OwnedSemaphorePermit>,
+    stark_session: SessionId,
+    /// The SNARK wrapping session along with the bincoded STARK receipt being wrapped
+    snark_session: Option<(SnarkId, Vec<u8>)>,
+    receipt: Option<Receipt>,
+}
+
+impl BonsaiBackend {
+    pub fn new(proving_args: &ProvingArgs) -> Result<Self, ProvingError> {
+        let polling_interval = if let Ok(ms) = std::env::var("BONSAI_POLL_INTERVAL_MS") {
+            Duration::from_millis(
+                ms.parse()
+                    .context("invalid bonsai poll interval")
+                    .map_err(|e| ProvingError::OtherError(anyhow!(e)))?,
+            )
+        } else {
+            Duration::from_secs(1)
+        };
+        Ok(Self {
+            skip_await_proof: proving_args.skip_await_proof,
+            polling_interval,
+            job: None,
+        })
     }
-    // Load witness data frames
-    for frame in witness_frames {
-        let witness_len = frame.len() as u32;
-        input.extend_from_slice(&witness_len.to_le_bytes());
-        input.extend_from_slice(frame.as_slice());
+
+    fn job(&mut self) -> Result<&mut BonsaiJob, ProvingError> {
+        self.job
+            .as_mut()
+            .ok_or_else(|| ProvingError::OtherError(anyhow!("No Bonsai proof submitted.")))
     }
-    // Load recursive proofs and upload succinct receipts
-    let mut assumption_receipt_ids = vec![];
-    for receipt in stitched_proofs {
-        if std::env::var("KAILUA_FORCE_RECURSION").is_ok() {
-            warn!("(KAILUA_FORCE_RECURSION) Forcibly loading receipt as guest input.");
-            input.extend_from_slice(bytemuck::cast_slice(
-                &to_vec(&receipt).map_err(|e| ProvingError::OtherError(anyhow!(e)))?,
-            ));
-            continue;
-        }
+}
 
-        if matches!(receipt.inner, InnerReceipt::Groth16(_)) {
-            input.extend_from_slice(bytemuck::cast_slice(
-                &to_vec(&receipt).map_err(|e| ProvingError::OtherError(anyhow!(e)))?,
-            ));
-        } else {
+#[async_trait]
+impl ProofBackend for BonsaiBackend {
+    fn name(&self) -> &'static str {
+        "bonsai"
+    }
+
+    fn poll_interval(&self) -> Duration {
+        self.polling_interval
+    }
+
+    async fn submit(&mut self, input: &ProofInput) -> Result<(), ProvingError> {
+        info!("Running Bonsai client.");
+        // Instantiate client
+        let client = Client::from_env(risc0_zkvm::VERSION)
+            .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
+        // Prepare input payload
+        let (bonsai_input, assumptions) = input
+            .bonsai_input()
+            .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
+        // Upload succinct receipts
+        let mut assumption_receipt_ids = vec![];
+        for receipt in assumptions {
             let serialized_receipt =
                 bincode::serialize(&receipt).map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
             let receipt_id = client
@@ -77,155 +105,182 @@ pub async fn run_bonsai_client<A: NoUninit + Into<Digest>>(
                 .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
             assumption_receipt_ids.push(receipt_id);
         }
-    }
-
-    // Create a session on Bonsai
-    let r0vm_permit = acquire_owned_permit(SEMAPHORE_R0VM.clone())
-        .await
-        .map_err(ProvingError::OtherError);
-    let mut stark_session = create_stark_session(
-        image,
-        &client,
-        input.clone(),
-        assumption_receipt_ids.clone(),
-    )
-    .await;
 
-    if proving_args.skip_await_proof {
-        warn!("Skipping awaiting proof on Bonsai.");
-        return Err(ProvingError::NotAwaitingProof);
-    }
-
-    let polling_interval = if let Ok(ms) = std::env::var("BONSAI_POLL_INTERVAL_MS") {
-        Duration::from_millis(
-            ms.parse()
-                .context("invalid bonsai poll interval")
-                .map_err(|e| ProvingError::OtherError(anyhow!(e)))?,
+        // Create a session on Bonsai
+        let r0vm_permit = acquire_owned_permit(SEMAPHORE_R0VM.clone()).await.ok();
+        let stark_session = create_stark_session(
+            (input.image_id, input.elf),
+            &client,
+            bonsai_input,
+            assumption_receipt_ids,
         )
-    } else {
-        Duration::from_secs(1)
-    };
+        .await;
+        self.job = Some(BonsaiJob {
+            client,
+            image_id: input.image_id,
+            prove_snark: input.prove_snark,
+            r0vm_permit,
+            stark_session,
+            snark_session: None,
+            receipt: None,
+        });
 
-    let stark_receipt = loop {
-        // The session has already been started in the executor. Poll bonsai to check if
-        // the proof request succeeded.
-        let res = retry_res!(stark_session.status(&client).await).await;
-
-        match res.status.as_str() {
-            "RUNNING" => tokio::time::sleep(polling_interval).await,
-            "SUCCEEDED" => {
-                // Download the receipt, containing the output
-                let Some(receipt_url) = res.receipt_url else {
-                    error!("API error, missing receipt on completed session");
-                    continue;
-                };
+        if self.skip_await_proof {
+            warn!("Skipping awaiting proof on Bonsai.");
+            return Err(ProvingError::NotAwaitingProof);
+        }
+        Ok(())
+    }
 
-                let stats = res.stats.unwrap_or_else(|| {
-                    error!("Missing stats object on Bonsai response.");
-                    SessionStats {
-                        segments: 0,
-                        total_cycles: 0,
-                        cycles: 0,
-                    }
-                });
+    async fn poll(&mut self) -> Result<ProofStatus, ProvingError> {
+        let job = self.job()?;
+        if job.receipt.is_some() {
+            Ok(ProofStatus::Ready)
+        } else if job.snark_session.is_some() {
+            poll_snark_session(job).await
+        } else {
+            poll_stark_session(job).await
+        }
+    }
 
-                info!(
-                    "Bonsai usage: user_cycles: {} total_cycles: {}",
-                    stats.cycles, stats.total_cycles
-                );
+    async fn retrieve(&mut self) -> Result<Receipt, ProvingError> {
+        self.job()?
+            .receipt
+            .take()
+            .ok_or_else(|| ProvingError::OtherError(anyhow!("Bonsai proof not yet available.")))
+    }
 
-                info!("Downloading Bonsai receipt from {receipt_url}.");
-                let Ok(receipt_buf) = client.download(&receipt_url).await else {
-                    error!("Failed to download STARK receipt at {receipt_url}");
-                    continue;
-                };
+    async fn cancel(&mut self) -> Result<(), ProvingError> {
+        let Some(job) = self.job.take() else {
+            return Ok(());
+        };
+        // SNARK wrapping sessions can not be stopped
+        if job.snark_session.is_none() && job.receipt.is_none() {
+            info!("Stopping Bonsai session {}.", job.stark_session.uuid);
+            job.stark_session
+                .stop(&job.client)
+                .await
+                .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
+        }
+        Ok(())
+    }
+}
 
-                info!("Verifying receipt received from Bonsai.");
-                let Ok(receipt) = bincode::deserialize::<Receipt>(&receipt_buf) else {
-                    error!("Failed to deserialize receipt at {receipt_url}");
-                    continue;
-                };
-                let Ok(()) = receipt.verify(image.0) else {
-                    error!("Failed to verify receipt at {receipt_url}.");
-                    continue;
-                };
+/// Polls the STARK proving session, requesting SNARK wrapping once it completes if needed
+async fn poll_stark_session(job: &mut BonsaiJob) -> Result<ProofStatus, ProvingError> {
+    // The session has already been started in the executor. Poll bonsai to check if
+    // the proof request succeeded.
+    let res = retry_res!(job.stark_session.status(&job.client).await).await;
+    match res.status.as_str() {
+        "RUNNING" => return Ok(ProofStatus::Pending),
+        "SUCCEEDED" => {}
+        _ => {
+            return Err(ProvingError::OtherError(anyhow!(
+                "Bonsai prover session [{}] exited: {} err: {:?}",
+                job.stark_session.uuid,
+                res.status,
+                res.error_msg
+            )));
+        }
+    }
 
-                break receipt;
-            }
-            _ => {
-                error!(
-                    "Bonsai prover session [{}] exited: {} err: {:?}. Retrying.",
-                    stark_session.uuid, res.status, res.error_msg
-                );
-                // Retry and create another session
-                stark_session = create_stark_session(
-                    image,
-                    &client,
-                    input.clone(),
-                    assumption_receipt_ids.clone(),
-                )
-                .await;
-            }
+    // Download the receipt, containing the output
+    let Some(receipt_url) = res.receipt_url else {
+        error!("API error, missing receipt on completed session");
+        return Ok(ProofStatus::Pending);
+    };
+    let stats = res.stats.unwrap_or_else(|| {
+        error!("Missing stats object on Bonsai response.");
+        SessionStats {
+            segments: 0,
+            total_cycles: 0,
+            cycles: 0,
         }
+    });
+    info!(
+        "Bonsai usage: user_cycles: {} total_cycles: {}",
+        stats.cycles, stats.total_cycles
+    );
+    let Some(receipt) = download_receipt(&job.client, &receipt_url, job.image_id).await else {
+        return Ok(ProofStatus::Pending);
     };
-
-    drop(r0vm_permit);
-    if !prove_snark {
-        return Ok(stark_receipt);
+    job.r0vm_permit.take();
+    if !job.prove_snark {
+        job.receipt = Some(receipt);
+        return Ok(ProofStatus::Ready);
     }
-    info!("Wrapping STARK as SNARK on Bonsai.");
-    let stark_receipt_bincoded =
-        bincode::serialize(&stark_receipt).map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
 
     // Request that Bonsai compress further, to Groth16.
-    let mut snark_session = create_snark_session(
-        &client,
+    info!("Wrapping STARK as SNARK on Bonsai.");
+    let stark_receipt_bincoded =
+        bincode::serialize(&receipt).map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
+    let snark_session = create_snark_session(
+        &job.client,
         stark_receipt_bincoded.clone(),
-        Some(stark_session.uuid),
+        Some(job.stark_session.uuid.clone()),
     )
     .await;
+    job.snark_session = Some((snark_session, stark_receipt_bincoded));
+    Ok(ProofStatus::Pending)
+}
 
-    let groth16_receipt = loop {
-        let res = retry_res!(snark_session.status(&client).await).await;
-
-        match res.status.as_str() {
-            "RUNNING" => sleep(polling_interval).await,
-            "SUCCEEDED" => {
-                let Some(receipt_url) = res.output else {
-                    error!("SNARK API error, missing output url.");
-                    continue;
-                };
-
-                info!("Downloading Groth16 receipt from Bonsai.");
-                let Ok(receipt_buf) = client.download(&receipt_url).await else {
-                    error!("Failed to download SNARK receipt at {receipt_url}");
-                    continue;
-                };
+/// Polls the SNARK wrapping session, restarting it if it fails
+async fn poll_snark_session(job: &mut BonsaiJob) -> Result<ProofStatus, ProvingError> {
+    let Some((snark_session, stark_receipt)) = &mut job.snark_session else {
+        return Err(ProvingError::OtherError(anyhow!(
+            "No Bonsai SNARK session started."
+        )));
+    };
+    let res = retry_res!(snark_session.status(&job.client).await).await;
+    match res.status.as_str() {
+        "RUNNING" => Ok(ProofStatus::Pending),
+        "SUCCEEDED" => {
+            let Some(receipt_url) = res.output else {
+                error!("SNARK API error, missing output url.");
+                return Ok(ProofStatus::Pending);
+            };
+            let Some(receipt) = download_receipt(&job.client, &receipt_url, job.image_id).await
+            else {
+                return Ok(ProofStatus::Pending);
+            };
+            job.receipt = Some(receipt);
+            Ok(ProofStatus::Ready)
+        }
+        _ => {
+            error!(
+                "Bonsai prover workflow [{}] exited: {} err: {:?}",
+                snark_session.uuid, res.status, res.error_msg
+            );
+            // Retry wrapping the STARK receipt
+            *snark_session = create_snark_session(&job.client, stark_receipt.clone(), None).await;
+            Ok(ProofStatus::Pending)
+        }
+    }
+}
 
-                info!("Verifying receipt received from Bonsai.");
-                let Ok(receipt) = bincode::deserialize::<Receipt>(&receipt_buf) else {
-                    error!("Failed to deserialize SNARK receipt at {receipt_url}");
-                    continue;
-                };
-                let Ok(()) = receipt.verify(image.0) else {
-                    error!("Failed to verify SNARK receipt at {receipt_url}.");
-                    continue;
-                };
+/// Downloads and verifies a receipt, logging any failures
+pub async fn download_receipt(
+    client: &Client,
+    receipt_url: &str,
+    image_id: [u32; 8],
+) -> Option<Receipt> {
+    info!("Downloading Bonsai receipt from {receipt_url}.");
+    let Ok(receipt_buf) = client.download(receipt_url).await else {
+        error!("Failed to download receipt at {receipt_url}");
+        return None;
+    };
 
-                break receipt;
-            }
-            _ => {
-                error!(
-                    "Bonsai prover workflow [{}] exited: {} err: {:?}",
-                    snark_session.uuid, res.status, res.error_msg
-                );
-                snark_session =
-                    create_snark_session(&client, stark_receipt_bincoded.clone(), None).await;
-            }
-        }
+    info!("Verifying receipt received from Bonsai.");
+    let Ok(receipt) = bincode::deserialize::<Receipt>(&receipt_buf) else {
+        error!("Failed to deserialize receipt at {receipt_url}");
+        return None;
+    };
+    let Ok(()) = receipt.verify(image_id) else {
+        error!("Failed to verify receipt at {receipt_url}.");
+        return None;
     };
 
-    Ok(groth16_receipt)
+    Some(receipt)
 }
 
 pub async fn create_snark_session(
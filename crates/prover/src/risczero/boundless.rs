@@ -16,12 +16,15 @@ use crate::args::ProvingArgs;
 use crate::client::proving::{acquire_owned_permit, SEMAPHORE_R0VM};
 use crate::proof::save_to_bincoded_file;
 use crate::proof::{proof_id, read_bincoded_file};
+use crate::risczero::backend::{ProofBackend, ProofInput, ProofStatus};
+use crate::store::ProofStoreArgs;
 use crate::ProvingError;
 use alloy::eips::BlockNumberOrTag;
 use alloy::signers::k256::sha2::{Digest as _, Sha256};
 use alloy::transports::http::reqwest::Url;
 use alloy_primitives::{Address, B256, U256};
 use anyhow::{anyhow, Context};
+use async_trait::async_trait;
 use aws_config::{BehaviorVersion, Region};
 use aws_sdk_s3::config::Credentials;
 use aws_sdk_s3::primitives::ByteStream;
@@ -35,7 +38,7 @@ use boundless_market::contracts::{
 };
 use boundless_market::request_builder::OfferParams;
 use boundless_market::storage::{StorageProviderConfig, StorageProviderType};
-use boundless_market::{Deployment, GuestEnv, ProofRequest, StandardStorageProvider};
+use boundless_market::{Deployment, ProofRequest, StandardStorageProvider};
 use bytemuck::NoUninit;
 use clap::Parser;
 use human_bytes::human_bytes;
@@ -43,7 +46,7 @@ use kailua_sync::{retry_res, retry_res_timeout};
 use lazy_static::lazy_static;
 use risc0_ethereum_contracts::selector::Selector;
 use risc0_zkvm::sha::Digestible;
-use risc0_zkvm::{default_executor, Digest, ExecutorEnv, Journal, Receipt};
+use risc0_zkvm::{default_executor, Digest, Journal, Receipt};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::sync::Arc;
@@ -68,6 +71,12 @@ pub struct BoundlessArgs {
     pub r2_domain: Option<String>,
 }
 
+/// Default starting price (wei) per cycle of proving orders
+pub const DEFAULT_CYCLE_MIN_WEI: &str = "0";
+
+/// Default maximum price (wei) per cycle of proving orders
+pub const DEFAULT_CYCLE_MAX_WEI: &str = "200000000";
+
 #[derive(Parser, Debug, Clone)]
 #[group(requires_all = ["boundless_rpc_url", "boundless_wallet_key"])]
 pub struct MarketProviderConfig {
@@ -131,10 +140,10 @@ pub struct MarketProviderConfig {
     #[clap(long, env, required = false)]
     pub boundless_assume_cycle_count: Option<u64>,
     /// Starting price (wei) per cycle of the proving order
-    #[clap(long, env, required = false, default_value = "0")]
+    #[clap(long, env, required = false, default_value = DEFAULT_CYCLE_MIN_WEI)]
     pub boundless_cycle_min_wei: U256,
     /// Maximum price (wei) per cycle of the proving order
-    #[clap(long, env, required = false, default_value = "200000000")]
+    #[clap(long, env, required = false, default_value = DEFAULT_CYCLE_MAX_WEI)]
     pub boundless_cycle_max_wei: U256,
     /// Stake (USDC) per gigacycle of the proving order
     #[clap(long, env, required = false, default_value = "1000")]
@@ -305,113 +314,203 @@ lazy_static! {
     static ref BOUNDLESS_NET: Arc<Mutex<()>> = Default::default();
 }
 
-#[allow(clippy::too_many_arguments)]
-pub async fn run_boundless_client<A: NoUninit + Into<Digest>>(
+/// Delegates proving to the decentralized Boundless proving network
+pub struct BoundlessBackend {
     market: MarketProviderConfig,
     storage: StorageProviderConfig,
     r2_domain: Option<String>,
-    image: (A, &[u8]),
-    journal: Journal,
-    witness_slices: Vec<Vec<u32>>,
-    witness_frames: Vec<Vec<u8>>,
-    stitched_proofs: Vec<Receipt>,
-    proving_args: &ProvingArgs,
-) -> Result<Receipt, ProvingError> {
-    info!("Running boundless client.");
+    proving_args: ProvingArgs,
+    job: Option<BoundlessJob>,
+}
 
-    // Create R2 storage if configured
-    let r2_storage = if let Some(ref domain) = r2_domain {
-        Some(
-            R2Storage::new(&storage, domain)
-                .await
-                .context("Failed to create R2 storage")
-                .map_err(|e| ProvingError::OtherError(anyhow!(e)))?,
-        )
-    } else {
-        None
-    };
+/// A proof request submitted to the Boundless market
+struct BoundlessJob {
+    client: Client,
+    image_id: [u32; 8],
+    request_id: U256,
+    expires_at: u64,
+}
 
-    // Instantiate storage provider (used when R2 is not configured)
-    let storage_provider = StandardStorageProvider::from_config(&storage)
-        .context("StandardStorageProvider::from_config")
-        .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
-
-    // Override deployment configuration if set
-    let market_deployment = market
-        .boundless_chain_id
-        .and_then(Deployment::from_chain_id)
-        .or_else(|| {
-            let mut builder = Deployment::builder();
-            if let Some(boundless_market_address) = market.boundless_market_address {
-                builder.boundless_market_address(boundless_market_address);
-            };
-            if let Some(boundless_verifier_router_address) =
-                market.boundless_verifier_router_address
-            {
-                builder.verifier_router_address(boundless_verifier_router_address);
-            };
-            if let Some(boundless_set_verifier_address) = market.boundless_set_verifier_address {
-                builder.set_verifier_address(boundless_set_verifier_address);
-            };
-            if let Some(boundless_stake_token_address) = market.boundless_stake_token_address {
-                builder.stake_token_address(boundless_stake_token_address);
-            };
-            if let Some(boundless_order_stream_url) = market.boundless_order_stream_url.clone() {
-                builder.order_stream_url(boundless_order_stream_url);
-            };
-            builder.build().ok()
-        });
+impl BoundlessBackend {
+    pub fn new(
+        market: MarketProviderConfig,
+        storage: StorageProviderConfig,
+        r2_domain: Option<String>,
+        proving_args: ProvingArgs,
+    ) -> Self {
+        Self {
+            market,
+            storage,
+            r2_domain,
+            proving_args,
+            job: None,
+        }
+    }
 
-    // Instantiate client
-    let boundless_client = retry_res_timeout!(
-        15,
-        Client::builder()
-            .with_private_key(market.boundless_wallet_key.clone())
-            .with_rpc_url(market.boundless_rpc_url.clone())
-            .with_deployment(market_deployment.clone())
-            .with_storage_provider(Some(storage_provider.clone()))
-            .build()
-            .await
-            .context("ClientBuilder::build()")
-    )
-    .await;
+    fn job(&self) -> Result<&BoundlessJob, ProvingError> {
+        self.job
+            .as_ref()
+            .ok_or_else(|| ProvingError::OtherError(anyhow!("No Boundless request submitted.")))
+    }
+}
 
-    // Report boundless deployment info
-    info!(
-        "Using BoundlessMarket at {}",
-        boundless_client.deployment.boundless_market_address,
-    );
-    debug!("Deployment: {:?}", boundless_client.deployment);
+#[async_trait]
+impl ProofBackend for BoundlessBackend {
+    fn name(&self) -> &'static str {
+        "boundless"
+    }
 
-    // Set the proof request requirements
-    let requirements = Requirements::new(image.0, Predicate::digest_match(journal.digest()))
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.market.boundless_order_check_interval)
+    }
+
+    async fn submit(&mut self, input: &ProofInput) -> Result<(), ProvingError> {
+        info!("Running boundless client.");
+
+        // Create R2 storage if configured
+        let r2_storage = if let Some(domain) = &self.r2_domain {
+            Some(
+                R2Storage::new(&self.storage, domain)
+                    .await
+                    .context("Failed to create R2 storage")
+                    .map_err(|e| ProvingError::OtherError(anyhow!(e)))?,
+            )
+        } else {
+            None
+        };
+
+        // Instantiate storage provider (used when R2 is not configured)
+        let storage_provider = StandardStorageProvider::from_config(&self.storage)
+            .context("StandardStorageProvider::from_config")
+            .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
+
+        // Override deployment configuration if set
+        let market_deployment = self
+            .market
+            .boundless_chain_id
+            .and_then(Deployment::from_chain_id)
+            .or_else(|| {
+                let mut builder = Deployment::builder();
+                if let Some(boundless_market_address) = self.market.boundless_market_address {
+                    builder.boundless_market_address(boundless_market_address);
+                };
+                if let Some(boundless_verifier_router_address) =
+                    self.market.boundless_verifier_router_address
+                {
+                    builder.verifier_router_address(boundless_verifier_router_address);
+                };
+                if let Some(boundless_set_verifier_address) =
+                    self.market.boundless_set_verifier_address
+                {
+                    builder.set_verifier_address(boundless_set_verifier_address);
+                };
+                if let Some(boundless_stake_token_address) =
+                    self.market.boundless_stake_token_address
+                {
+                    builder.stake_token_address(boundless_stake_token_address);
+                };
+                if let Some(boundless_order_stream_url) =
+                    self.market.boundless_order_stream_url.clone()
+                {
+                    builder.order_stream_url(boundless_order_stream_url);
+                };
+                builder.build().ok()
+            });
+
+        // Instantiate client
+        let boundless_client = retry_res_timeout!(
+            15,
+            Client::builder()
+                .with_private_key(self.market.boundless_wallet_key.clone())
+                .with_rpc_url(self.market.boundless_rpc_url.clone())
+                .with_deployment(market_deployment.clone())
+                .with_storage_provider(Some(storage_provider.clone()))
+                .build()
+                .await
+                .context("ClientBuilder::build()")
+        )
+        .await;
+
+        // Report boundless deployment info
+        info!(
+            "Using BoundlessMarket at {}",
+            boundless_client.deployment.boundless_market_address,
+        );
+        debug!("Deployment: {:?}", boundless_client.deployment);
+
+        // Set the proof request requirements
+        let requirements = Requirements::new(
+            input.image_id,
+            Predicate::digest_match(input.journal.digest()),
+        )
         // manually choose latest Groth16 receipt selector
         .with_selector((Selector::groth16_latest() as u32).into());
 
-    // Wait for a market request to be fulfilled
-    loop {
-        // todo: price increase over time ?
-        match request_proof(
-            &market,
+        // Find or submit a market request
+        let (request_id, expires_at) = request_proof(
+            &self.market,
             &boundless_client,
             r2_storage.as_ref(),
-            image,
-            journal.clone(),
-            &witness_slices,
-            &witness_frames,
-            &stitched_proofs,
-            proving_args,
+            input,
+            &self.proving_args,
             &requirements,
         )
+        .await?;
+        self.job = Some(BoundlessJob {
+            client: boundless_client,
+            image_id: input.image_id,
+            request_id,
+            expires_at,
+        });
+
+        if self.proving_args.skip_await_proof {
+            warn!("Skipping awaiting proof on Boundless.");
+            return Err(ProvingError::NotAwaitingProof);
+        }
+        Ok(())
+    }
+
+    async fn poll(&mut self) -> Result<ProofStatus, ProvingError> {
+        let job = self.job()?;
+        let request_status = retry_res_timeout!(job
+            .client
+            .boundless_market
+            .get_status(job.request_id, Some(job.expires_at))
+            .await
+            .context("get_status"))
+        .await;
+        match request_status {
+            RequestStatus::Fulfilled => Ok(ProofStatus::Ready),
+            RequestStatus::Expired => Err(ProvingError::OtherError(anyhow!(
+                "Boundless request 0x{:x} expired.",
+                job.request_id
+            ))),
+            _ => Ok(ProofStatus::Pending),
+        }
+    }
+
+    async fn retrieve(&mut self) -> Result<Receipt, ProvingError> {
+        let job = self.job()?;
+        retrieve_proof(
+            &job.client,
+            job.request_id,
+            job.image_id,
+            self.market.boundless_order_check_interval,
+            job.expires_at,
+        )
         .await
-        {
-            Err(ProvingError::OtherError(e)) => {
-                error!("(Retrying) Boundless request failed: {e:?}");
-                sleep(Duration::from_secs(1)).await;
-            }
-            // this will return successful results or propagatable errors
-            result => break result,
+        .context("retrieve_proof")
+        .map_err(|e| ProvingError::OtherError(anyhow!(e)))
+    }
+
+    async fn cancel(&mut self) -> Result<(), ProvingError> {
+        if let Some(job) = self.job.take() {
+            warn!(
+                "Boundless request 0x{:x} can not be withdrawn and remains open until it expires.",
+                job.request_id
+            );
         }
+        Ok(())
     }
 }
 
@@ -508,13 +607,13 @@ pub async fn get_next_fresh_nonce(
     }
 }
 
+/// Finds a pending or fulfilled request matching the requirements that was previously submitted
 pub async fn look_back(
     market: &MarketProviderConfig,
     boundless_client: &Client,
     requirements: &Requirements,
-    proving_args: &ProvingArgs,
     previous_nonce: &mut Option<u32>,
-) -> Result<Option<Receipt>, ProvingError> {
+) -> Option<(U256, u64)> {
     let boundless_wallet_address = boundless_client.signer.as_ref().unwrap().address();
     loop {
         let nonce = next_nonce(requirements, *previous_nonce);
@@ -522,10 +621,7 @@ pub async fn look_back(
         let _ = previous_nonce.insert(nonce);
         info!("Looking back at request {request_id:x}");
         // Get request behind id
-        let Some(request) = get_proof_request(market, boundless_client, request_id).await else {
-            // we hit a fresh nonce
-            break Ok(None);
-        };
+        let request = get_proof_request(market, boundless_client, request_id).await?;
         // Check if not expired
         let request_status = retry_res_timeout!(boundless_client
             .boundless_market
@@ -545,30 +641,7 @@ pub async fn look_back(
         }
 
         info!("Found matching request already submitted!");
-
-        if proving_args.skip_await_proof {
-            warn!("Skipping awaiting proof on Boundless.");
-            return Err(ProvingError::NotAwaitingProof);
-        }
-
-        // Return result if okay
-        match retrieve_proof(
-            boundless_client,
-            request_id,
-            requirements.imageId.0,
-            market.boundless_order_check_interval,
-            request.expires_at(),
-        )
-        .await
-        {
-            Ok(proof) => {
-                break Ok(Some(proof));
-            }
-            Err(err) => {
-                error!("Failed to retrieve proof: {err:?}");
-                // continue to next nonce
-            }
-        }
+        break Some((request_id, request.expires_at()));
     }
 }
 
@@ -610,33 +683,23 @@ pub async fn retrieve_proof(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-pub async fn request_proof<A: NoUninit + Into<Digest>>(
+/// Submits a new proof request unless a matching one was already submitted, returning the id and
+/// expiry of the request
+pub async fn request_proof(
     market: &MarketProviderConfig,
     boundless_client: &Client,
     r2_storage: Option<&R2Storage>,
-    image: (A, &[u8]),
-    journal: Journal,
-    witness_slices: &Vec<Vec<u32>>,
-    witness_frames: &Vec<Vec<u8>>,
-    stitched_proofs: &Vec<Receipt>,
+    input: &ProofInput,
     proving_args: &ProvingArgs,
     requirements: &Requirements,
-) -> Result<Receipt, ProvingError> {
+) -> Result<(U256, u64), ProvingError> {
     // Check prior requests
     let fresh_nonce = if market.boundless_look_back {
         let mut nonce_target = None;
-        // note: look_back only returns the NotAwaitingProof error
-        if let Some(proof) = look_back(
-            market,
-            boundless_client,
-            requirements,
-            proving_args,
-            &mut nonce_target,
-        )
-        .await?
+        if let Some(request) =
+            look_back(market, boundless_client, requirements, &mut nonce_target).await
         {
-            return Ok(proof);
+            return Ok(request);
         }
         nonce_target.unwrap()
     } else {
@@ -644,7 +707,7 @@ pub async fn request_proof<A: NoUninit + Into<Digest>>(
     };
 
     // Upload program
-    let bin_file_name = binary_file_name(image.0);
+    let bin_file_name = binary_file_name(input.image_id);
     let program_url = loop {
         match (
             market.boundless_enable_upload_caching,
@@ -665,17 +728,17 @@ pub async fn request_proof<A: NoUninit + Into<Digest>>(
 
                 info!(
                     "Uploading {} Kailua ELF.",
-                    human_bytes(image.1.len() as f64)
+                    human_bytes(input.elf.len() as f64)
                 );
                 let program_url = if let Some(r2) = r2_storage {
                     retry_res!(r2
-                        .upload_program(image.1)
+                        .upload_program(input.elf)
                         .await
                         .context("R2Storage::upload_program"))
                     .await
                 } else {
                     retry_res!(boundless_client
-                        .upload_program(image.1)
+                        .upload_program(input.elf)
                         .await
                         .context("Client::upload_program"))
                     .await
@@ -692,7 +755,11 @@ pub async fn request_proof<A: NoUninit + Into<Digest>>(
     };
 
     // Preflight execution to get cycle count
-    let req_file_name = request_file_name(image.0, journal.clone());
+    let req_file_name = request_file_path(
+        &proving_args.proof_store,
+        input.image_id,
+        input.journal.clone(),
+    );
     let cycle_count = match (
         market.boundless_assume_cycle_count,
         read_bincoded_file::<BoundlessRequest>(&req_file_name).await,
@@ -709,32 +776,15 @@ pub async fn request_proof<A: NoUninit + Into<Digest>>(
         }
         (None, Err(err)) => {
             warn!("Preflighting execution: {err:?}");
-            let preflight_witness_slices = witness_slices.clone();
-            let preflight_witness_frames = witness_frames.clone();
-            let preflight_stitched_proofs = stitched_proofs.clone();
+            let preflight_input = input.clone();
             let segment_limit = proving_args.segment_limit;
-            let elf = image.1.to_vec();
             let r0vm_permit = acquire_owned_permit(SEMAPHORE_R0VM.clone())
                 .await
                 .map_err(ProvingError::OtherError);
             let session_info = tokio::task::spawn_blocking(move || {
-                let mut builder = ExecutorEnv::builder();
-                // Set segment po2
-                builder.segment_limit_po2(segment_limit);
-                // Pass in witness data slices
-                for slice in &preflight_witness_slices {
-                    builder.write_slice(slice);
-                }
-                // Pass in witness data frames
-                for frame in &preflight_witness_frames {
-                    builder.write_frame(frame);
-                }
-                // Pass in proofs
-                for proof in &preflight_stitched_proofs {
-                    builder.write(proof)?;
-                }
-                let env = builder.build()?;
-                let session_info = default_executor().execute(env, &elf)?;
+                // Pass in proofs as guest input
+                let env = preflight_input.executor_env(segment_limit, false)?;
+                let session_info = default_executor().execute(env, preflight_input.elf)?;
                 Ok::<_, anyhow::Error>(session_info)
             })
             .await
@@ -756,7 +806,7 @@ pub async fn request_proof<A: NoUninit + Into<Digest>>(
     };
 
     // Pass in input frames
-    let inp_file_name = input_file_name(image.0, journal.clone());
+    let inp_file_name = input_file_name(input.image_id, input.journal.clone());
     let input_url = loop {
         match (
             market.boundless_enable_upload_caching,
@@ -775,26 +825,10 @@ pub async fn request_proof<A: NoUninit + Into<Digest>>(
                     continue;
                 };
 
-                let mut guest_env_builder = GuestEnv::builder();
-                // Pass in input slices
-                for slice in witness_slices {
-                    guest_env_builder = guest_env_builder.write_slice(slice);
-                }
-                // Pass in input frames
-                for frame in witness_frames {
-                    guest_env_builder = guest_env_builder.write_frame(frame);
-                }
-                // Pass in proofs
-                for proof in stitched_proofs {
-                    guest_env_builder = guest_env_builder
-                        .write(proof)
-                        .context("GuestEnvBuilder::write")
-                        .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
-                }
                 // Build input vector
-                let input = guest_env_builder
-                    .build_vec()
-                    .context("GuestEnvBuilder::build_vec")
+                let input = input
+                    .guest_env_input()
+                    .context("ProofInput::guest_env_input")
                     .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
 
                 // Upload input
@@ -851,7 +885,7 @@ pub async fn request_proof<A: NoUninit + Into<Digest>>(
         corrected_lock_timeout_factor + market.boundless_order_expiry_factor;
     let request = boundless_client
         .new_request()
-        .with_journal(journal)
+        .with_journal(input.journal.clone())
         .with_cycles(cycle_count)
         .with_program_url(program_url)
         .context("RequestParams::with_program_url")
@@ -901,27 +935,26 @@ pub async fn request_proof<A: NoUninit + Into<Digest>>(
     .await;
     drop(boundless_req_lock);
 
-    if proving_args.skip_await_proof {
-        warn!("Skipping awaiting proof on Boundless.");
-        return Err(ProvingError::NotAwaitingProof);
-    }
-
-    retrieve_proof(
-        boundless_client,
-        request_id,
-        image.0,
-        market.boundless_order_check_interval,
-        expires_at,
-    )
-    .await
-    .context("retrieve_proof")
-    .map_err(|e| ProvingError::OtherError(anyhow!(e)))
+    Ok((request_id, expires_at))
 }
 
 pub fn request_file_name<A: NoUninit>(image_id: A, journal: impl Into<Journal>) -> String {
     format!("boundless-{}.req", proof_id(image_id, journal))
 }
 
+/// Returns the path of the cached request data of the proof under the proof store directory
+pub fn request_file_path<A: NoUninit>(
+    proof_store: &ProofStoreArgs,
+    image_id: A,
+    journal: impl Into<Journal>,
+) -> String {
+    proof_store
+        .dir()
+        .join(request_file_name(image_id, journal))
+        .display()
+        .to_string()
+}
+
 pub fn binary_file_name<A: NoUninit>(image_id: A) -> String {
     format!(
         "boundless-{}.req",
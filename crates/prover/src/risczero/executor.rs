@@ -0,0 +1,145 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::args::ProvingArgs;
+use crate::client::proving::{acquire_owned_permit, SEMAPHORE_R0VM};
+use crate::estimate::{EstimateSink, ProofEstimate};
+use crate::proof::{proof_id, save_to_bincoded_file};
+use crate::risczero::backend::{ProofBackend, ProofInput, ProofStatus};
+use crate::risczero::boundless::{request_file_path, BoundlessRequest};
+use crate::store::ProofStoreArgs;
+use crate::ProvingError;
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use kailua_kona::journal::ProofJournal;
+use risc0_zkvm::{default_executor, Receipt};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Executes the guest locally without proving to measure the cost of its proof
+pub struct ExecutorBackend {
+    segment_limit: u32,
+    fpvm_image_id: [u32; 8],
+    proof_store: ProofStoreArgs,
+    estimate_sink: Option<EstimateSink>,
+}
+
+impl ExecutorBackend {
+    pub fn new(proving_args: &ProvingArgs, estimate_sink: Option<EstimateSink>) -> Self {
+        Self {
+            segment_limit: proving_args.segment_limit,
+            fpvm_image_id: proving_args.image_id(),
+            proof_store: proving_args.proof_store.clone(),
+            estimate_sink,
+        }
+    }
+}
+
+#[async_trait]
+impl ProofBackend for ExecutorBackend {
+    fn name(&self) -> &'static str {
+        "executor"
+    }
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    async fn submit(&mut self, input: &ProofInput) -> Result<(), ProvingError> {
+        info!("Running zkvm executor.");
+        let execution_input = input.clone();
+        let segment_limit = self.segment_limit;
+        let r0vm_permit = acquire_owned_permit(SEMAPHORE_R0VM.clone())
+            .await
+            .map_err(ProvingError::OtherError);
+        let session_info = tokio::task::spawn_blocking(move || {
+            // Pass in proofs as guest input like the Boundless preflight does
+            let env = execution_input.executor_env(segment_limit, false)?;
+            let session_info = default_executor().execute(env, execution_input.elf)?;
+            Ok::<_, anyhow::Error>(session_info)
+        })
+        .await
+        .context("spawn_blocking")
+        .map_err(|e| ProvingError::OtherError(anyhow!(e)))?
+        .map_err(|e| ProvingError::ExecutionError(anyhow!(e)))?;
+        drop(r0vm_permit);
+
+        // Summarize the session
+        let total_cycles = session_info
+            .segments
+            .iter()
+            .map(|segment| 1 << segment.po2)
+            .sum::<u64>();
+        let user_cycles = session_info
+            .segments
+            .iter()
+            .map(|segment| segment.cycles as u64)
+            .sum::<u64>();
+        let witness_size = input.witness_frames.iter().map(|f| f.len()).sum::<usize>()
+            + input
+                .witness_slices
+                .iter()
+                .map(|s| s.len() * size_of::<u32>())
+                .sum::<usize>();
+        let claimed_l2_block_number = (input.image_id == self.fpvm_image_id)
+            .then(|| ProofJournal::decode_packed(input.journal.as_ref()).claimed_l2_block_number);
+        let mut estimate = ProofEstimate {
+            proof_id: proof_id(input.image_id, input.journal.clone()),
+            claimed_l2_block_number,
+            segments: session_info.segments.len(),
+            total_cycles,
+            user_cycles,
+            witness_size,
+            stitched_proofs: input.stitched_proofs.len(),
+            recorded: false,
+        };
+        info!(
+            "Executed {} total cycles ({} user cycles) over {} segments.",
+            estimate.total_cycles, estimate.user_cycles, estimate.segments
+        );
+
+        // Record the cycle count so that Boundless requests for this proof skip preflighting
+        let req_file_name =
+            request_file_path(&self.proof_store, input.image_id, input.journal.clone());
+        let cached_data = BoundlessRequest {
+            cycle_count: total_cycles,
+        };
+        match save_to_bincoded_file(&cached_data, &req_file_name).await {
+            Ok(()) => estimate.recorded = true,
+            Err(err) => warn!("Failed to cache cycle count data: {err:?}"),
+        }
+        if let Some(estimate_sink) = &self.estimate_sink {
+            estimate_sink.lock().await.push(estimate);
+        }
+
+        // No proof is ever produced
+        Err(ProvingError::NotAwaitingProof)
+    }
+
+    async fn poll(&mut self) -> Result<ProofStatus, ProvingError> {
+        Err(ProvingError::OtherError(anyhow!(
+            "The executor backend does not produce proofs."
+        )))
+    }
+
+    async fn retrieve(&mut self) -> Result<Receipt, ProvingError> {
+        Err(ProvingError::OtherError(anyhow!(
+            "The executor backend does not produce proofs."
+        )))
+    }
+
+    async fn cancel(&mut self) -> Result<(), ProvingError> {
+        Ok(())
+    }
+}
@@ -13,17 +13,20 @@
 // limitations under the License.
 
 use crate::args::ProvingArgs;
-use crate::proof::proof_file_name;
+use crate::estimate::EstimateSink;
+use crate::risczero::backend::ProofInput;
 use crate::risczero::boundless::BoundlessArgs;
-use crate::{proof, ProvingError};
+use crate::store::ProofStore;
+use crate::ProvingError;
 use anyhow::Context;
 use risc0_zkvm::{Journal, Receipt};
-use std::convert::identity;
-use std::path::Path;
+use std::time::Duration;
 use tracing::{error, info};
 
+pub mod backend;
 pub mod bonsai;
 pub mod boundless;
+pub mod executor;
 pub mod zkvm;
 
 /// Use our own version of SessionStats to avoid non-exhaustive issues (risc0_zkvm::SessionStats)
@@ -44,7 +47,6 @@ pub struct KailuaProveInfo {
 }
 
 #[allow(clippy::too_many_arguments)]
-#[allow(deprecated)]
 pub async fn seek_proof(
     proving: &ProvingArgs,
     boundless: BoundlessArgs,
@@ -53,55 +55,37 @@ pub async fn seek_proof(
     witness_frames: Vec<Vec<u8>>,
     stitched_proofs: Vec<Receipt>,
     prove_snark: bool,
+    estimate_sink: Option<EstimateSink>,
 ) -> Result<(), ProvingError> {
     // Check proof cache
-    let file_name = proof_file_name(proving.image_id(), journal.clone());
-    if Path::new(&file_name).try_exists().is_ok_and(identity) {
+    let store = ProofStore::new(&proving.proof_store)
+        .await
+        .context("ProofStore::new")
+        .map_err(ProvingError::OtherError)?;
+    let file_name = ProofStore::key(proving.image_id(), journal.clone());
+    if store.contains(&file_name).await {
         info!("Proving skipped. Proof file {file_name} already exists.");
+        return Ok(());
     }
 
     // compute the zkvm proof
-    let proof = match (boundless.market, boundless.storage) {
-        (Some(marked_provider_config), Some(storage_provider_config))
-            if !risc0_zkvm::is_dev_mode() =>
-        {
-            boundless::run_boundless_client(
-                marked_provider_config,
-                storage_provider_config,
-                boundless.r2_domain,
-                proving.image(),
-                journal.clone(),
-                witness_slices,
-                witness_frames,
-                stitched_proofs,
-                proving,
-            )
-            .await?
-        }
-        _ => {
-            if bonsai::should_use_bonsai() {
-                bonsai::run_bonsai_client(
-                    proving.image(),
-                    witness_slices,
-                    witness_frames,
-                    stitched_proofs,
-                    prove_snark,
-                    proving,
-                )
-                .await?
-            } else {
-                zkvm::run_zkvm_client(
-                    proving.image(),
-                    witness_slices,
-                    witness_frames,
-                    stitched_proofs,
-                    prove_snark,
-                    proving,
-                )
-                .await?
-            }
-        }
+    let (image_id, elf) = proving.image();
+    let input = ProofInput {
+        image_id,
+        elf,
+        journal: journal.clone(),
+        witness_slices,
+        witness_frames,
+        stitched_proofs,
+        prove_snark,
     };
+    let mut backends = backend::proof_backends(proving, boundless, estimate_sink)?;
+    let proof = backend::prove_with_fallback(
+        &mut backends,
+        &input,
+        proving.proof_backend_timeout.map(Duration::from_secs),
+    )
+    .await?;
 
     // Save proof file to disk
     if journal != proof.journal {
@@ -111,12 +95,12 @@ pub async fn seek_proof(
             hex::encode(&proof.journal)
         );
     }
-    let file_name = proof_file_name(proving.image_id(), proof.journal.clone());
-    proof::save_to_bincoded_file(&proof, &file_name)
+    let file_name = store
+        .save(proving.image_id(), &proof)
         .await
-        .context("save_to_bincoded_file")
+        .context("ProofStore::save")
         .map_err(ProvingError::OtherError)?;
-    info!("Saved proof to file {file_name}");
+    info!("Saved proof {file_name} to {}", store.dir.display());
 
     Ok(())
 }
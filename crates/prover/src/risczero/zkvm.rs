@@ -14,121 +14,133 @@
 
 use crate::args::ProvingArgs;
 use crate::client::proving::{acquire_owned_permit, SEMAPHORE_R0VM};
+use crate::risczero::backend::{ProofBackend, ProofInput, ProofStatus};
 use crate::risczero::{KailuaProveInfo, KailuaSessionStats};
 use crate::ProvingError;
 use anyhow::{anyhow, Context};
-use bytemuck::NoUninit;
-use risc0_zkvm::{default_prover, Digest, ExecutorEnv, InnerReceipt, ProverOpts, Receipt};
+use async_trait::async_trait;
+use risc0_zkvm::{default_prover, ProverOpts, Receipt};
+use std::time::Duration;
+use tokio::task::JoinHandle;
 use tracing::info;
 use tracing::log::warn;
 
-pub async fn run_zkvm_client<A: NoUninit + Into<Digest>>(
-    image: (A, &[u8]),
-    witness_slices: Vec<Vec<u32>>,
-    witness_frames: Vec<Vec<u8>>,
-    stitched_proofs: Vec<Receipt>,
-    prove_snark: bool,
-    proving_args: &ProvingArgs,
-) -> Result<Receipt, ProvingError> {
-    info!("Running zkvm client.");
-    if proving_args.skip_await_proof {
-        warn!("Skipping awaiting proof locally.");
-        return Err(ProvingError::NotAwaitingProof);
+/// Proves locally using the default zkvm prover
+pub struct ZkvmBackend {
+    segment_limit: u32,
+    skip_await_proof: bool,
+    image_id: [u32; 8],
+    task: Option<JoinHandle<Result<KailuaProveInfo, ProvingError>>>,
+}
+
+impl ZkvmBackend {
+    pub fn new(proving_args: &ProvingArgs) -> Self {
+        Self {
+            segment_limit: proving_args.segment_limit,
+            skip_await_proof: proving_args.skip_await_proof,
+            image_id: proving_args.image_id(),
+            task: None,
+        }
     }
+}
 
-    let segment_limit = proving_args.segment_limit;
-    let elf = image.1.to_vec();
-    let r0vm_permit = acquire_owned_permit(SEMAPHORE_R0VM.clone())
-        .await
-        .map_err(ProvingError::OtherError);
-    let prove_info = tokio::task::spawn_blocking(move || {
-        let env = build_zkvm_env(
-            witness_slices,
-            witness_frames,
-            stitched_proofs,
-            segment_limit,
-        )?;
-        let prover = default_prover();
-        let prover_opts = if prove_snark {
-            ProverOpts::groth16()
-        } else {
-            ProverOpts::succinct()
-        };
-        let risc0_prove_info = prover
-            .prove_with_opts(env, &elf, &prover_opts)
-            .context("prove_with_opts")?;
+#[async_trait]
+impl ProofBackend for ZkvmBackend {
+    fn name(&self) -> &'static str {
+        "zkvm"
+    }
 
-        // Convert to our own KailuaProveInfo
-        let kailua_prove_info = KailuaProveInfo {
-            receipt: risc0_prove_info.receipt,
-            stats: KailuaSessionStats {
-                segments: risc0_prove_info.stats.segments,
-                total_cycles: risc0_prove_info.stats.total_cycles,
-                user_cycles: risc0_prove_info.stats.user_cycles,
-                paging_cycles: risc0_prove_info.stats.paging_cycles,
-                reserved_cycles: risc0_prove_info.stats.reserved_cycles,
-            },
-        };
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
 
-        Ok::<_, anyhow::Error>(kailua_prove_info)
-    })
-    .await
-    .map_err(|e| ProvingError::OtherError(anyhow!(e)))?
-    .map_err(|e| ProvingError::ExecutionError(anyhow!(e)))?;
-    drop(r0vm_permit);
+    async fn submit(&mut self, input: &ProofInput) -> Result<(), ProvingError> {
+        info!("Running zkvm client.");
+        if self.skip_await_proof {
+            warn!("Skipping awaiting proof locally.");
+            return Err(ProvingError::NotAwaitingProof);
+        }
 
-    info!(
-        "Proof of {} total cycles ({} user cycles) computed.",
-        prove_info.stats.total_cycles, prove_info.stats.user_cycles
-    );
-    prove_info
-        .receipt
-        .verify(image.0)
-        .context("receipt verification")
-        .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
-    info!("Receipt verified.");
+        self.image_id = input.image_id;
+        let segment_limit = self.segment_limit;
+        let input = input.clone();
+        self.task = Some(tokio::spawn(async move {
+            let r0vm_permit = acquire_owned_permit(SEMAPHORE_R0VM.clone())
+                .await
+                .map_err(ProvingError::OtherError);
+            tokio::task::spawn_blocking(move || {
+                // The permit is held until proving completes even if this job is abandoned
+                let _r0vm_permit = r0vm_permit;
+                let env = input.executor_env(segment_limit, true)?;
+                let prover = default_prover();
+                let prover_opts = if input.prove_snark {
+                    ProverOpts::groth16()
+                } else {
+                    ProverOpts::succinct()
+                };
+                let risc0_prove_info = prover
+                    .prove_with_opts(env, input.elf, &prover_opts)
+                    .context("prove_with_opts")?;
 
-    Ok(prove_info.receipt)
-}
+                // Convert to our own KailuaProveInfo
+                let kailua_prove_info = KailuaProveInfo {
+                    receipt: risc0_prove_info.receipt,
+                    stats: KailuaSessionStats {
+                        segments: risc0_prove_info.stats.segments,
+                        total_cycles: risc0_prove_info.stats.total_cycles,
+                        user_cycles: risc0_prove_info.stats.user_cycles,
+                        paging_cycles: risc0_prove_info.stats.paging_cycles,
+                        reserved_cycles: risc0_prove_info.stats.reserved_cycles,
+                    },
+                };
 
-#[allow(deprecated)]
-pub fn build_zkvm_env<'a>(
-    witness_slices: Vec<Vec<u32>>,
-    witness_frames: Vec<Vec<u8>>,
-    stitched_proofs: Vec<Receipt>,
-    segment_limit: u32,
-) -> anyhow::Result<ExecutorEnv<'a>> {
-    // Execution environment
-    let mut builder = ExecutorEnv::builder();
-    // Set segment po2
-    builder.segment_limit_po2(segment_limit);
-    // Pass in witness data slices
-    for slice in &witness_slices {
-        builder.write_slice(slice);
+                Ok::<_, anyhow::Error>(kailua_prove_info)
+            })
+            .await
+            .map_err(|e| ProvingError::OtherError(anyhow!(e)))?
+            .map_err(|e| ProvingError::ExecutionError(anyhow!(e)))
+        }));
+        Ok(())
     }
-    // Pass in witness data frames
-    for frame in &witness_frames {
-        builder.write_frame(frame);
+
+    async fn poll(&mut self) -> Result<ProofStatus, ProvingError> {
+        match &self.task {
+            Some(task) if task.is_finished() => Ok(ProofStatus::Ready),
+            Some(_) => Ok(ProofStatus::Pending),
+            None => Err(ProvingError::OtherError(anyhow!(
+                "No zkvm proof submitted."
+            ))),
+        }
     }
-    // Dev-mode for recursive proofs
-    if risc0_zkvm::is_dev_mode() {
-        builder.env_var("RISC0_DEV_MODE", "1");
+
+    async fn retrieve(&mut self) -> Result<Receipt, ProvingError> {
+        let task = self
+            .task
+            .take()
+            .ok_or_else(|| ProvingError::OtherError(anyhow!("No zkvm proof submitted.")))?;
+        let prove_info = task
+            .await
+            .map_err(|e| ProvingError::OtherError(anyhow!(e)))??;
+
+        info!(
+            "Proof of {} total cycles ({} user cycles) computed.",
+            prove_info.stats.total_cycles, prove_info.stats.user_cycles
+        );
+        prove_info
+            .receipt
+            .verify(self.image_id)
+            .context("receipt verification")
+            .map_err(|e| ProvingError::OtherError(anyhow!(e)))?;
+        info!("Receipt verified.");
+
+        Ok(prove_info.receipt)
     }
-    // Pass in proofs
-    for receipt in stitched_proofs {
-        // Force in-guest verification (should be used for testing only)
-        if std::env::var("KAILUA_FORCE_RECURSION").is_ok() {
-            warn!("(KAILUA_FORCE_RECURSION) Forcibly loading receipt as guest input.");
-            builder.write(&receipt)?;
-            continue;
-        }
 
-        if matches!(receipt.inner, InnerReceipt::Groth16(_)) {
-            builder.write(&receipt)?;
-        } else {
-            builder.add_assumption(receipt);
+    async fn cancel(&mut self) -> Result<(), ProvingError> {
+        // The local prover can not be interrupted, so its result is discarded
+        if let Some(task) = self.task.take() {
+            task.abort();
         }
+        Ok(())
     }
-
-    builder.build()
 }
@@ -0,0 +1,517 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::proof::{proof_file_name, proof_id};
+use anyhow::{anyhow, bail, Context};
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_s3::config::Credentials;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client as S3Client;
+use bytemuck::NoUninit;
+use clap::Parser;
+use risc0_zkvm::sha::Digestible;
+use risc0_zkvm::{Journal, Receipt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Name of the file under the store directory listing the stored proofs
+pub const PROOF_INDEX_FILE_NAME: &str = "kailua-proofs.jsonl";
+
+/// Default key prefix of proofs stored in S3
+pub const DEFAULT_S3_PREFIX: &str = "kailua/proofs";
+
+/// Environment variable through which the S3 secret key is handed to prover processes
+pub const S3_SECRET_KEY_ENV: &str = "PROOF_STORE_S3_SECRET_KEY";
+
+/// Where computed proofs are stored and shared
+#[derive(Parser, Clone, Debug, Default)]
+pub struct ProofStoreArgs {
+    /// Directory to store proofs under (defaults to the working directory)
+    #[clap(long, env)]
+    pub proof_store_dir: Option<PathBuf>,
+    /// S3-compatible bucket to share stored proofs through
+    #[clap(long, env)]
+    pub proof_store_s3_bucket: Option<String>,
+    /// Key prefix of proofs stored in the bucket (defaults to `kailua/proofs`)
+    #[clap(long, env)]
+    pub proof_store_s3_prefix: Option<String>,
+    /// Endpoint url of the S3-compatible service
+    #[clap(long, env)]
+    pub proof_store_s3_url: Option<String>,
+    /// Region of the bucket
+    #[clap(long, env)]
+    pub proof_store_s3_region: Option<String>,
+    /// Access key of the bucket (defaults to the ambient AWS credentials)
+    #[clap(long, env)]
+    pub proof_store_s3_access_key: Option<String>,
+    /// Secret key of the bucket
+    #[clap(long, env)]
+    pub proof_store_s3_secret_key: Option<String>,
+}
+
+impl ProofStoreArgs {
+    /// Returns the local directory of the store
+    pub fn dir(&self) -> PathBuf {
+        self.proof_store_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Returns the command line arguments of the store, excluding the S3 secret key which is
+    /// passed through [ProofStoreArgs::to_env_vec] instead
+    pub fn to_arg_vec(&self) -> Vec<String> {
+        [
+            (
+                "--proof-store-dir",
+                self.proof_store_dir
+                    .as_ref()
+                    .map(|d| d.display().to_string()),
+            ),
+            (
+                "--proof-store-s3-bucket",
+                self.proof_store_s3_bucket.clone(),
+            ),
+            (
+                "--proof-store-s3-prefix",
+                self.proof_store_s3_prefix.clone(),
+            ),
+            ("--proof-store-s3-url", self.proof_store_s3_url.clone()),
+            (
+                "--proof-store-s3-region",
+                self.proof_store_s3_region.clone(),
+            ),
+            (
+                "--proof-store-s3-access-key",
+                self.proof_store_s3_access_key.clone(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(flag, value)| value.map(|value| [flag.to_string(), value]))
+        .flatten()
+        .collect()
+    }
+
+    /// Returns the environment variables of the store that are kept off the command line
+    pub fn to_env_vec(&self) -> Vec<(&'static str, String)> {
+        self.proof_store_s3_secret_key
+            .clone()
+            .map(|secret_key| (S3_SECRET_KEY_ENV, secret_key))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// An entry of the index of stored proofs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProofIndexEntry {
+    /// Hex-encoded hash of the image id and journal
+    pub proof_id: String,
+    /// Hex-encoded image id of the proven program
+    pub image_id: String,
+    /// Hex-encoded journal of the proof
+    pub journal: String,
+    /// Name of the stored proof file
+    pub file_name: String,
+    /// Unix time at which the proof was stored
+    pub stored_at: u64,
+}
+
+/// Content-addressed storage of proofs in a local directory, optionally mirrored to S3
+pub struct ProofStore {
+    /// Local directory holding proof files and the index
+    pub dir: PathBuf,
+    /// Remote bucket to share proofs through
+    pub s3: Option<S3ProofStore>,
+    /// Serializes index updates by this process
+    index_lock: Mutex<()>,
+}
+
+/// An S3-compatible bucket holding proof files
+pub struct S3ProofStore {
+    client: S3Client,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3ProofStore {
+    pub async fn new(args: &ProofStoreArgs, bucket: String) -> anyhow::Result<Self> {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let (Some(access_key), Some(secret_key)) = (
+            &args.proof_store_s3_access_key,
+            &args.proof_store_s3_secret_key,
+        ) {
+            loader = loader.credentials_provider(Credentials::new(
+                access_key,
+                secret_key,
+                None,
+                None,
+                "ProofStore",
+            ));
+        }
+        if let Some(endpoint) = &args.proof_store_s3_url {
+            loader = loader.endpoint_url(endpoint);
+        }
+        if let Some(region) = &args.proof_store_s3_region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        let client = S3Client::new(&loader.load().await);
+        let prefix = args
+            .proof_store_s3_prefix
+            .clone()
+            .unwrap_or_else(|| DEFAULT_S3_PREFIX.to_string());
+        Ok(Self {
+            client,
+            bucket,
+            prefix: prefix.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn key(&self, file_name: &str) -> String {
+        format!("{}/{file_name}", self.prefix)
+    }
+
+    pub async fn get(&self, file_name: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let response = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.key(file_name))
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(err) if err.as_service_error().is_some_and(|e| e.is_no_such_key()) => {
+                return Ok(None)
+            }
+            Err(err) => return Err(anyhow!(err).context("Failed to download from S3")),
+        };
+        let data = response
+            .body
+            .collect()
+            .await
+            .context("Failed to read S3 object body")?;
+        Ok(Some(data.into_bytes().to_vec()))
+    }
+
+    pub async fn exists(&self, file_name: &str) -> anyhow::Result<bool> {
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(self.key(file_name))
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(err) if err.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(err) => Err(anyhow!(err).context("Failed to query S3")),
+        }
+    }
+
+    pub async fn put(&self, file_name: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.key(file_name))
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .context("Failed to upload to S3")?;
+        Ok(())
+    }
+}
+
+impl ProofStore {
+    pub async fn new(args: &ProofStoreArgs) -> anyhow::Result<Self> {
+        let dir = args.dir();
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("Failed to create proof store directory {}", dir.display()))?;
+        let s3 = match &args.proof_store_s3_bucket {
+            Some(bucket) => Some(
+                S3ProofStore::new(args, bucket.clone())
+                    .await
+                    .context("S3ProofStore::new")?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            dir,
+            s3,
+            index_lock: Default::default(),
+        })
+    }
+
+    /// Returns the name under which the proof of the journal is stored
+    pub fn key<A: NoUninit>(image_id: A, journal: impl Into<Journal>) -> String {
+        proof_file_name(image_id, journal)
+    }
+
+    /// Returns the local path of the stored proof file
+    pub fn path(&self, file_name: &str) -> PathBuf {
+        self.dir.join(file_name)
+    }
+
+    /// Returns the name under which the receipt belongs, derived from its own claim
+    pub fn receipt_key(receipt: &Receipt) -> anyhow::Result<String> {
+        let claim = receipt
+            .claim()
+            .map_err(|err| anyhow!("Receipt::claim: {err:?}"))?;
+        let image_id = claim
+            .as_value()
+            .map_err(|err| anyhow!("MaybePruned::as_value: {err:?}"))?
+            .pre
+            .digest();
+        Ok(Self::key(image_id, receipt.journal.clone()))
+    }
+
+    /// Returns true if the proof is stored locally or remotely
+    pub async fn contains(&self, file_name: &str) -> bool {
+        if self.path(file_name).exists() {
+            return true;
+        }
+        let Some(s3) = &self.s3 else {
+            return false;
+        };
+        s3.exists(file_name).await.unwrap_or_else(|err| {
+            warn!("Failed to look up proof {file_name} in S3: {err:?}");
+            false
+        })
+    }
+
+    /// Reads a stored proof, caching proofs fetched from S3 in the local directory once they
+    /// are confirmed to be stored under their own name
+    pub async fn load(&self, file_name: &str) -> anyhow::Result<Receipt> {
+        let path = self.path(file_name);
+        if path.exists() {
+            let data = tokio::fs::read(&path)
+                .await
+                .with_context(|| format!("Failed to read proof file {}.", path.display()))?;
+            return deserialize_receipt(file_name, &data);
+        }
+        let Some(s3) = &self.s3 else {
+            bail!("Proof {file_name} not found.");
+        };
+        let data = s3
+            .get(file_name)
+            .await
+            .context("S3ProofStore::get")?
+            .ok_or_else(|| anyhow!("Proof {file_name} not found."))?;
+        let receipt = deserialize_receipt(file_name, &data)?;
+        let receipt_key = Self::receipt_key(&receipt).context("ProofStore::receipt_key")?;
+        if receipt_key != file_name {
+            bail!("S3 object {file_name} holds proof {receipt_key} instead.");
+        }
+        info!("Fetched proof {file_name} from S3.");
+        if let Err(err) = write_atomically(&path, &data).await {
+            warn!("Failed to cache proof {file_name} locally: {err:?}");
+        }
+        Ok(receipt)
+    }
+
+    /// Stores the proof under its content address, returning its file name
+    pub async fn save<A: NoUninit>(
+        &self,
+        image_id: A,
+        receipt: &Receipt,
+    ) -> anyhow::Result<String> {
+        let file_name = Self::key(image_id, receipt.journal.clone());
+        let data = bincode::serialize(receipt).context("Could not serialize proof.")?;
+        write_atomically(&self.path(&file_name), &data).await?;
+        if let Some(s3) = &self.s3 {
+            s3.put(&file_name, data)
+                .await
+                .context("S3ProofStore::put")?;
+            info!("Uploaded proof {file_name} to S3.");
+        }
+        self.append_index(ProofIndexEntry {
+            proof_id: proof_id(image_id, receipt.journal.clone()).to_string(),
+            image_id: hex::encode(bytemuck::cast::<A, [u8; 32]>(image_id)),
+            journal: hex::encode(&receipt.journal.bytes),
+            file_name: file_name.clone(),
+            stored_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        })
+        .await?;
+        Ok(file_name)
+    }
+
+    async fn append_index(&self, entry: ProofIndexEntry) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(&entry).context("serde_json::to_string")?;
+        line.push('\n');
+        let _index_lock = self.index_lock.lock().await;
+        // appends of single lines do not interleave across processes
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(PROOF_INDEX_FILE_NAME))
+            .await
+            .context("Failed to open proof index.")?;
+        file.write_all(line.as_bytes())
+            .await
+            .context("Failed to append to proof index.")?;
+        file.flush().await.context("Failed to flush proof index.")
+    }
+
+    /// Lists the latest index entry of each proof stored in the local directory
+    pub async fn index(&self) -> anyhow::Result<Vec<ProofIndexEntry>> {
+        let path = self.dir.join(PROOF_INDEX_FILE_NAME);
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        let contents = tokio::fs::read_to_string(&path)
+            .await
+            .context("Failed to read proof index.")?;
+        let mut entries = BTreeMap::new();
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str::<ProofIndexEntry>(line) {
+                Ok(entry) => {
+                    entries.insert(entry.file_name.clone(), entry);
+                }
+                Err(err) => warn!("Skipping malformed proof index entry: {err:?}"),
+            }
+        }
+        Ok(entries.into_values().collect())
+    }
+}
+
+fn deserialize_receipt(file_name: &str, data: &[u8]) -> anyhow::Result<Receipt> {
+    bincode::deserialize::<Receipt>(data)
+        .with_context(|| format!("Failed to deserialize proof {file_name} with bincode."))
+}
+
+/// Writes the data to a temporary file before moving it into place so readers never observe
+/// partially written proofs
+async fn write_atomically(path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let tmp_path = path.with_extension(format!("tmp-{}", std::process::id()));
+    let mut file = tokio::fs::File::create(&tmp_path)
+        .await
+        .with_context(|| format!("Failed to create {}.", tmp_path.display()))?;
+    file.write_all(data)
+        .await
+        .with_context(|| format!("Failed to write {}.", tmp_path.display()))?;
+    file.sync_all()
+        .await
+        .with_context(|| format!("Failed to sync {}.", tmp_path.display()))?;
+    tokio::fs::rename(&tmp_path, path)
+        .await
+        .with_context(|| format!("Failed to move proof into {}.", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use risc0_zkvm::{FakeReceipt, InnerReceipt, ReceiptClaim};
+
+    fn receipt(image_id: [u32; 8], journal: Vec<u8>) -> Receipt {
+        Receipt::new(
+            InnerReceipt::Fake(FakeReceipt::new(ReceiptClaim::ok(
+                image_id,
+                journal.clone(),
+            ))),
+            journal,
+        )
+    }
+
+    fn args(dir: &Path) -> ProofStoreArgs {
+        ProofStoreArgs {
+            proof_store_dir: Some(dir.to_path_buf()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ProofStore::new(&args(dir.path())).await.unwrap();
+        let image_id = [7u32; 8];
+        let proof = receipt(image_id, vec![1, 2, 3]);
+        let file_name = ProofStore::key(image_id, proof.journal.clone());
+
+        assert!(!store.contains(&file_name).await);
+        assert!(store.load(&file_name).await.is_err());
+        assert_eq!(store.save(image_id, &proof).await.unwrap(), file_name);
+        assert!(store.contains(&file_name).await);
+        assert_eq!(store.load(&file_name).await.unwrap().journal, proof.journal);
+        // no temporary files are left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+        // stored proofs are named after their own claim
+        assert_eq!(ProofStore::receipt_key(&proof).unwrap(), file_name);
+        assert_ne!(
+            ProofStore::receipt_key(&receipt([8u32; 8], vec![1, 2, 3])).unwrap(),
+            file_name
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ProofStore::new(&args(dir.path())).await.unwrap();
+        assert!(store.index().await.unwrap().is_empty());
+
+        let image_id = [7u32; 8];
+        let proofs = [receipt(image_id, vec![1]), receipt(image_id, vec![2])];
+        for proof in &proofs {
+            store.save(image_id, proof).await.unwrap();
+        }
+        // saving a proof again does not duplicate its entry
+        store.save(image_id, &proofs[0]).await.unwrap();
+        // malformed lines are skipped
+        let mut contents = std::fs::read_to_string(dir.path().join(PROOF_INDEX_FILE_NAME)).unwrap();
+        contents.push_str("not json\n");
+        std::fs::write(dir.path().join(PROOF_INDEX_FILE_NAME), contents).unwrap();
+
+        let index = store.index().await.unwrap();
+        assert_eq!(index.len(), 2);
+        for proof in &proofs {
+            let entry = index
+                .iter()
+                .find(|e| e.journal == hex::encode(&proof.journal.bytes))
+                .unwrap();
+            assert_eq!(
+                entry.file_name,
+                ProofStore::key(image_id, proof.journal.clone())
+            );
+            assert_eq!(
+                entry.proof_id,
+                proof_id(image_id, proof.journal.clone()).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_s3_secret_key_env() {
+        let args = ProofStoreArgs {
+            proof_store_s3_bucket: Some(String::from("bucket")),
+            proof_store_s3_access_key: Some(String::from("access")),
+            proof_store_s3_secret_key: Some(String::from("secret")),
+            ..Default::default()
+        };
+        let arg_vec = args.to_arg_vec();
+        assert!(arg_vec.contains(&String::from("--proof-store-s3-access-key")));
+        assert!(!arg_vec.iter().any(|arg| arg.contains("secret")));
+        assert_eq!(
+            args.to_env_vec(),
+            vec![(S3_SECRET_KEY_ENV, String::from("secret"))]
+        );
+        assert!(ProofStoreArgs::default().to_env_vec().is_empty());
+    }
+}
@@ -13,8 +13,9 @@
 // limitations under the License.
 
 use crate::args::ProveArgs;
+use crate::estimate::EstimateSink;
 use crate::kv::RWLKeyValueStore;
-use crate::proof::{proof_file_name, read_bincoded_file};
+use crate::store::ProofStore;
 use crate::ProvingError;
 use alloy::providers::RootProvider;
 use alloy_primitives::B256;
@@ -30,8 +31,6 @@ use kona_proof::BootInfo;
 use risc0_zkvm::Receipt;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
-use std::convert::identity;
-use std::path::Path;
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
@@ -48,6 +47,7 @@ pub struct Cached {
     pub prove_snark: bool,
     pub force_attempt: bool,
     pub seek_proof: bool,
+    pub estimate_sink: Option<EstimateSink>,
 }
 
 impl PartialEq for Cached {
@@ -127,6 +127,7 @@ pub async fn handle_oneshot_tasks(task_receiver: Receiver<Oneshot>) -> anyhow::R
                     cached_task.prove_snark,
                     cached_task.force_attempt,
                     cached_task.seek_proof,
+                    cached_task.estimate_sink,
                 )
                 .await,
             })
@@ -150,6 +151,7 @@ pub async fn compute_oneshot_task(
     prove_snark: bool,
     force_attempt: bool,
     seek_proof: bool,
+    estimate_sink: Option<EstimateSink>,
     task_sender: Sender<Oneshot>,
 ) -> Result<Receipt, ProvingError> {
     // create proving task
@@ -165,6 +167,7 @@ pub async fn compute_oneshot_task(
         prove_snark,
         force_attempt,
         seek_proof,
+        estimate_sink,
     };
     // create onshot channel
     let oneshot_channel = async_channel::bounded(1);
@@ -196,6 +199,7 @@ pub async fn compute_fpvm_proof(
     stitched_boot_info: Vec<StitchedBootInfo>,
     stitched_proofs: Vec<Receipt>,
     prove_snark: bool,
+    estimate_sink: Option<EstimateSink>,
     task_sender: Sender<Oneshot>,
 ) -> Result<Option<Receipt>, ProvingError> {
     // report transaction count
@@ -228,6 +232,7 @@ pub async fn compute_fpvm_proof(
         stitching_only,
         // skip seeking a complete proof if skipping derivation
         !args.proving.skip_derivation_proof,
+        estimate_sink.clone(),
         task_sender.clone(),
     )
     .await;
@@ -265,6 +270,7 @@ pub async fn compute_fpvm_proof(
             false,
             false,
             false,
+            estimate_sink.clone(),
             task_sender.clone(),
         )
         .await;
@@ -321,6 +327,7 @@ pub async fn compute_fpvm_proof(
                     rollup_config.clone(),
                     disk_kv_store.clone(),
                     &execution_cache,
+                    estimate_sink.clone(),
                 ),
                 result_sender: result_channel.0.clone(),
             })
@@ -412,6 +419,7 @@ pub async fn compute_fpvm_proof(
                     rollup_config.clone(),
                     disk_kv_store.clone(),
                     &execution_cache,
+                    estimate_sink.clone(),
                 ),
                 result_sender: result_channel.0.clone(),
             })
@@ -429,6 +437,7 @@ pub async fn compute_fpvm_proof(
                     rollup_config.clone(),
                     disk_kv_store.clone(),
                     &execution_cache,
+                    estimate_sink.clone(),
                 ),
                 result_sender: result_channel.0.clone(),
             })
@@ -477,6 +486,7 @@ pub async fn compute_fpvm_proof(
             prove_snark,
             true,
             true,
+            estimate_sink,
             task_sender.clone(),
         )
         .await?,
@@ -488,6 +498,7 @@ pub fn create_cached_execution_task(
     rollup_config: RollupConfig,
     disk_kv_store: Option<RWLKeyValueStore>,
     execution_cache: &[Arc<Execution>],
+    estimate_sink: Option<EstimateSink>,
 ) -> Cached {
     let starting_block = execution_cache
         .iter()
@@ -534,6 +545,7 @@ pub fn create_cached_execution_task(
         prove_snark: false,
         force_attempt,
         seek_proof: true,
+        estimate_sink,
     }
 }
 
@@ -550,6 +562,7 @@ pub async fn compute_cached_proof(
     prove_snark: bool,
     force_attempt: bool,
     seek_proof: bool,
+    estimate_sink: Option<EstimateSink>,
 ) -> Result<Receipt, ProvingError> {
     // extract single chain kona config
     let boot = BootInfo {
@@ -571,9 +584,13 @@ pub async fn compute_cached_proof(
         stitched_boot_info.clone(),
     );
     let skip_await_proof = args.proving.skip_await_proof;
-    // Skip computation if previously saved to disk
-    let file_name = proof_file_name(image_id, &proof_journal);
-    if Path::new(&file_name).try_exists().is_ok_and(identity) && seek_proof {
+    // Skip computation if previously stored
+    let store = ProofStore::new(&args.proving.proof_store)
+        .await
+        .context("ProofStore::new")
+        .map_err(ProvingError::OtherError)?;
+    let file_name = ProofStore::key(image_id, &proof_journal);
+    if seek_proof && store.contains(&file_name).await {
         info!("Proving skipped. Proof file {file_name} already exists.");
     } else {
         if seek_proof {
@@ -636,6 +653,7 @@ pub async fn compute_cached_proof(
             prove_snark,
             force_attempt,
             seek_proof,
+            estimate_sink,
         )
         .await?;
     }
@@ -645,7 +663,8 @@ pub async fn compute_cached_proof(
         return Err(ProvingError::NotAwaitingProof);
     }
 
-    read_bincoded_file(&file_name)
+    store
+        .load(&file_name)
         .await
         .context(format!("Failed to read proof file {file_name} contents."))
         .map_err(|e| ProvingError::OtherError(anyhow!(e)))
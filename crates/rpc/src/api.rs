@@ -12,25 +12,63 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::events::{KailuaEvent, KailuaServerEvents, KailuaTopic};
+use crate::state::{
+    KailuaServerState, ProposalData, ProposalId, SyncStatus, WithdrawalProofContext,
+};
 use alloy::primitives::Address;
-use jsonrpsee::core::{async_trait, RpcResult};
+use anyhow::anyhow;
+use jsonrpsee::core::{async_trait, RpcResult, SubscriptionResult};
 use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::INTERNAL_ERROR_CODE;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use jsonrpsee::PendingSubscriptionSink;
+use kailua_sync::provider::optimism::fetch_output_root_proof;
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::RwLock;
-use tracing::trace;
+use tracing::{trace, warn};
 
 #[rpc(client, server, namespace = "kailua")]
 pub trait KailuaApi {
     #[method(name = "gameAddressForBlockByNumber")]
     async fn game_address_for_block_by_number(&self, number: u64) -> RpcResult<Option<Address>>;
+
+    #[method(name = "getProposal")]
+    async fn get_proposal(&self, id: ProposalId) -> RpcResult<Option<ProposalData>>;
+
+    #[method(name = "getCanonicalTip")]
+    async fn get_canonical_tip(&self) -> RpcResult<Option<ProposalData>>;
+
+    #[method(name = "getLastResolved")]
+    async fn get_last_resolved(&self) -> RpcResult<Option<ProposalData>>;
+
+    #[method(name = "getProposalChildren")]
+    async fn get_proposal_children(&self, id: ProposalId) -> RpcResult<Option<Vec<ProposalData>>>;
+
+    #[method(name = "getEliminations")]
+    async fn get_eliminations(&self) -> RpcResult<BTreeMap<Address, u64>>;
+
+    #[method(name = "getSyncStatus")]
+    async fn get_sync_status(&self) -> RpcResult<SyncStatus>;
+
+    #[method(name = "getWithdrawalProofContext")]
+    async fn get_withdrawal_proof_context(
+        &self,
+        l2_block: u64,
+    ) -> RpcResult<Option<WithdrawalProofContext>>;
+
+    #[subscription(name = "subscribe" => "subscription", unsubscribe = "unsubscribe", item = KailuaEvent)]
+    async fn subscribe(&self, topic: KailuaTopic) -> SubscriptionResult;
 }
 
-pub type KailuaServerCache = Arc<RwLock<BTreeMap<u64, Address>>>;
+pub type KailuaServerCache = Arc<RwLock<KailuaServerState>>;
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Debug)]
 pub struct KailuaApiHandler {
     pub cache: KailuaServerCache,
+    pub events: KailuaServerEvents,
 }
 
 #[async_trait]
@@ -41,8 +79,146 @@ impl KailuaApiServer for KailuaApiHandler {
             .cache
             .read()
             .await
+            .canonical
             .range(number..)
             .next()
             .map(|(_, addr)| *addr))
     }
+
+    async fn get_proposal(&self, id: ProposalId) -> RpcResult<Option<ProposalData>> {
+        trace!(target: "rpc::kailua", "Serving kailua_getProposal");
+        Ok(self.cache.read().await.proposal(id).cloned())
+    }
+
+    async fn get_canonical_tip(&self) -> RpcResult<Option<ProposalData>> {
+        trace!(target: "rpc::kailua", "Serving kailua_getCanonicalTip");
+        let cache = self.cache.read().await;
+        Ok(cache
+            .proposals
+            .get(&cache.status.canonical_proposal_tip)
+            .cloned())
+    }
+
+    async fn get_last_resolved(&self) -> RpcResult<Option<ProposalData>> {
+        trace!(target: "rpc::kailua", "Serving kailua_getLastResolved");
+        let cache = self.cache.read().await;
+        Ok(cache
+            .proposals
+            .get(&cache.status.last_resolved_game)
+            .cloned())
+    }
+
+    async fn get_proposal_children(&self, id: ProposalId) -> RpcResult<Option<Vec<ProposalData>>> {
+        trace!(target: "rpc::kailua", "Serving kailua_getProposalChildren");
+        let cache = self.cache.read().await;
+        Ok(cache.proposal(id).map(|proposal| {
+            proposal
+                .children
+                .iter()
+                .filter_map(|child| cache.proposals.get(child).cloned())
+                .collect()
+        }))
+    }
+
+    async fn get_eliminations(&self) -> RpcResult<BTreeMap<Address, u64>> {
+        trace!(target: "rpc::kailua", "Serving kailua_getEliminations");
+        Ok(self.cache.read().await.eliminations.clone())
+    }
+
+    async fn get_sync_status(&self) -> RpcResult<SyncStatus> {
+        trace!(target: "rpc::kailua", "Serving kailua_getSyncStatus");
+        Ok(self.cache.read().await.status.clone())
+    }
+
+    async fn get_withdrawal_proof_context(
+        &self,
+        l2_block: u64,
+    ) -> RpcResult<Option<WithdrawalProofContext>> {
+        trace!(target: "rpc::kailua", "Serving kailua_getWithdrawalProofContext");
+        // Look up earliest canonical game covering the block
+        let (address, game, history, l2_provider) = {
+            let cache = self.cache.read().await;
+            let Some(address) = cache.canonical.range(l2_block..).next().map(|(_, a)| *a) else {
+                return Ok(None);
+            };
+            let game = cache
+                .addresses
+                .get(&address)
+                .and_then(|index| cache.proposals.get(index))
+                .cloned();
+            (
+                address,
+                game,
+                cache.history.clone(),
+                cache.l2_provider.clone(),
+            )
+        };
+        // Fall back to the persisted data of games no longer held in memory
+        let game = match (game, history) {
+            (Some(game), _) => game,
+            (None, Some(history)) => match history.proposal(address).map_err(internal_error)? {
+                Some(game) => game,
+                None => return Ok(None),
+            },
+            (None, None) => return Ok(None),
+        };
+        let Some(l2_provider) = l2_provider else {
+            return Err(internal_error(anyhow!("L2 provider is not yet available.")));
+        };
+        // Fetch output root preimage from op-geth
+        let output_root_proof = fetch_output_root_proof(&l2_provider, game.output_block_number)
+            .await
+            .map_err(internal_error)?;
+        if output_root_proof.output_root() != game.output_root {
+            return Err(internal_error(anyhow!(
+                "Output root preimage for block {} does not match game {} output root {}.",
+                game.output_block_number,
+                game.contract,
+                game.output_root
+            )));
+        }
+        Ok(Some(WithdrawalProofContext {
+            l2_block,
+            game_index: game.index,
+            game_address: game.contract,
+            game_l2_block: game.output_block_number,
+            output_root: game.output_root,
+            output_root_proof,
+        }))
+    }
+
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        topic: KailuaTopic,
+    ) -> SubscriptionResult {
+        trace!(target: "rpc::kailua", "Serving kailua_subscribe");
+        let mut events = self.events.subscribe();
+        let sink = pending.accept().await?;
+        loop {
+            tokio::select! {
+                _ = sink.closed() => break,
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        if event.topic() != topic {
+                            continue;
+                        }
+                        let message = serde_json::value::to_raw_value(&event)?;
+                        if sink.send(message).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Subscription to {topic:?} skipped {skipped} events.");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn internal_error(err: anyhow::Error) -> ErrorObjectOwned {
+    ErrorObject::owned(INTERNAL_ERROR_CODE, format!("{err:?}"), None::<()>)
 }
@@ -32,4 +32,12 @@ pub struct RpcArgs {
     /// Disables listening for RPC requests over WS
     #[clap(long, env, default_value_t = false)]
     pub disable_ws: bool,
+    /// Whether to synchronize the full canonical game history starting from the KailuaTreasury
+    #[clap(
+        long,
+        env,
+        default_value_t = false,
+        conflicts_with = "kailua_anchor_address"
+    )]
+    pub backfill: bool,
 }
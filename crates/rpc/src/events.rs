@@ -0,0 +1,66 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::state::ProposalData;
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Maximum number of events buffered for slow subscribers
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+pub type KailuaServerEvents = broadcast::Sender<KailuaEvent>;
+
+/// Topics that can be subscribed to through `kailua_subscribe`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KailuaTopic {
+    NewProposal,
+    ProposalResolved,
+    CanonicalTipChanged,
+    ProposerEliminated,
+    FaultDetected,
+}
+
+/// A proposal lifecycle event observed while synchronizing
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "topic", rename_all = "camelCase")]
+pub enum KailuaEvent {
+    /// A new proposal was processed
+    NewProposal(ProposalData),
+    /// A proposal was resolved on chain
+    ProposalResolved(ProposalData),
+    /// A new proposal became the canonical chain tip
+    CanonicalTipChanged(ProposalData),
+    /// A proposer was eliminated at the given proposal index
+    ProposerEliminated { proposer: Address, index: u64 },
+    /// A proposal was found to contain a faulty output or trail element
+    FaultDetected {
+        proposal: ProposalData,
+        divergence_point: usize,
+        trail: bool,
+    },
+}
+
+impl KailuaEvent {
+    pub fn topic(&self) -> KailuaTopic {
+        match self {
+            KailuaEvent::NewProposal(_) => KailuaTopic::NewProposal,
+            KailuaEvent::ProposalResolved(_) => KailuaTopic::ProposalResolved,
+            KailuaEvent::CanonicalTipChanged(_) => KailuaTopic::CanonicalTipChanged,
+            KailuaEvent::ProposerEliminated { .. } => KailuaTopic::ProposerEliminated,
+            KailuaEvent::FaultDetected { .. } => KailuaTopic::FaultDetected,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::state::ProposalData;
+use alloy::primitives::Address;
+use anyhow::{bail, Context};
+use rocksdb::{Direction, IteratorMode, WriteBatch};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Key prefix of (l2 block -> canonical game) entries
+const CANONICAL_PREFIX: &[u8] = b"canonical";
+/// Key prefix of (game address -> canonical proposal data) entries
+const PROPOSAL_PREFIX: &[u8] = b"proposal";
+/// Key of the last resolved canonical game whose history is complete
+const RESUME_KEY: &[u8] = b"resume";
+
+/// Persistent record of the canonical games tracked by the RPC
+#[derive(Debug)]
+pub struct CanonicalHistory {
+    /// Local persistent key-value store
+    pub db: rocksdb::DB,
+}
+
+impl CanonicalHistory {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let mut options = rocksdb::Options::default();
+        options.create_if_missing(true);
+        let db = rocksdb::DB::open(&options, path).context("rocksdb::DB::open")?;
+        Ok(Self { db })
+    }
+
+    fn canonical_key(l2_block: u64) -> Vec<u8> {
+        [CANONICAL_PREFIX, l2_block.to_be_bytes().as_slice()].concat()
+    }
+
+    fn proposal_key(address: Address) -> Vec<u8> {
+        [PROPOSAL_PREFIX, address.as_slice()].concat()
+    }
+
+    /// Loads all persisted (l2 block -> canonical game) entries
+    pub fn load_canonical(&self) -> anyhow::Result<BTreeMap<u64, Address>> {
+        let mut canonical = BTreeMap::new();
+        for entry in self
+            .db
+            .iterator(IteratorMode::From(CANONICAL_PREFIX, Direction::Forward))
+        {
+            let (key, value) = entry.context("rocksdb::DB::iterator")?;
+            let Some(l2_block) = key.strip_prefix(CANONICAL_PREFIX) else {
+                break;
+            };
+            if l2_block.len() != 8 || value.len() != 20 {
+                bail!("Malformed canonical game entry in storage.");
+            }
+            canonical.insert(
+                u64::from_be_bytes(l2_block.try_into()?),
+                Address::from_slice(&value),
+            );
+        }
+        Ok(canonical)
+    }
+
+    /// Persists new canonical games along with their data
+    pub fn put_canonical(&self, proposals: &[ProposalData]) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        for proposal in proposals {
+            batch.put(
+                Self::canonical_key(proposal.output_block_number),
+                proposal.contract.as_slice(),
+            );
+            batch.put(
+                Self::proposal_key(proposal.contract),
+                serde_json::to_vec(proposal).context("serde_json::to_vec")?,
+            );
+        }
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+
+    /// Loads the persisted data of a canonical game
+    pub fn proposal(&self, address: Address) -> anyhow::Result<Option<ProposalData>> {
+        let Some(value) = self
+            .db
+            .get(Self::proposal_key(address))
+            .context("rocksdb::DB::get")?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            serde_json::from_slice(&value).context("serde_json::from_slice")?,
+        ))
+    }
+
+    /// Returns the last resolved canonical game up to which the history is complete
+    pub fn resume_point(&self) -> anyhow::Result<Option<Address>> {
+        Ok(self
+            .db
+            .get(RESUME_KEY)
+            .context("rocksdb::DB::get")?
+            .map(|value| Address::from_slice(&value)))
+    }
+
+    /// Records the last resolved canonical game up to which the history is complete
+    pub fn set_resume_point(&self, address: Address) -> anyhow::Result<()> {
+        self.db
+            .put(RESUME_KEY, address.as_slice())
+            .context("rocksdb::DB::put")
+    }
+}
@@ -14,6 +14,9 @@
 
 pub mod api;
 pub mod args;
+pub mod events;
+pub mod history;
 pub mod requests;
 pub mod rpc;
+pub mod state;
 pub mod sync;
@@ -14,12 +14,17 @@
 
 use crate::api::{KailuaApiHandler, KailuaApiServer, KailuaServerCache};
 use crate::args::RpcArgs;
+use crate::events::KailuaServerEvents;
 use jsonrpsee::server::ServerConfig;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 
-pub async fn handle_rpc_requests(args: RpcArgs, cache: KailuaServerCache) -> anyhow::Result<()> {
+pub async fn handle_rpc_requests(
+    args: RpcArgs,
+    cache: KailuaServerCache,
+    events: KailuaServerEvents,
+) -> anyhow::Result<()> {
     // Actual handler for requests
-    let kailua_api_handler = KailuaApiHandler { cache }.into_rpc();
+    let kailua_api_handler = KailuaApiHandler { cache, events }.into_rpc();
 
     // Bind address
     let socket_addr = args
@@ -14,11 +14,13 @@
 
 use crate::api::KailuaServerCache;
 use crate::args::RpcArgs;
+use crate::events::{KailuaServerEvents, EVENT_CHANNEL_CAPACITY};
 use crate::{requests, sync};
 use anyhow::Context;
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
 use std::path::PathBuf;
+use tokio::sync::broadcast;
 use tokio::{spawn, try_join};
 
 pub async fn rpc(args: RpcArgs, data_dir: PathBuf) -> anyhow::Result<()> {
@@ -26,13 +28,19 @@ pub async fn rpc(args: RpcArgs, data_dir: PathBuf) -> anyhow::Result<()> {
     let context = opentelemetry::Context::current_with_span(tracer.start("rpc"));
 
     let server_cache: KailuaServerCache = Default::default();
+    let (server_events, _): (KailuaServerEvents, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
 
     let handle_sync = spawn(
-        sync::handle_sync(args.clone(), data_dir.clone(), server_cache.clone())
-            .with_context(context.clone()),
+        sync::handle_sync(
+            args.clone(),
+            data_dir.clone(),
+            server_cache.clone(),
+            server_events.clone(),
+        )
+        .with_context(context.clone()),
     );
     let handle_requests = spawn(
-        requests::handle_rpc_requests(args.clone(), server_cache.clone())
+        requests::handle_rpc_requests(args.clone(), server_cache.clone(), server_events.clone())
             .with_context(context.clone()),
     );
 
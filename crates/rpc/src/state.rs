@@ -0,0 +1,256 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::events::KailuaEvent;
+use crate::history::CanonicalHistory;
+use alloy::primitives::{Address, B256};
+use alloy::providers::RootProvider;
+use kailua_sync::agent::{SyncAgent, SyncChanges};
+use kailua_sync::proposal::Proposal;
+use kailua_sync::provider::optimism::OutputRootProof;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+/// A read-only view of the [SyncAgent] state shared with the RPC request handler
+#[derive(Clone, Debug, Default)]
+pub struct KailuaServerState {
+    /// Canonical proposal addresses keyed by their output block number
+    pub canonical: BTreeMap<u64, Address>,
+    /// Data of all proposals processed so far keyed by their factory index
+    pub proposals: BTreeMap<u64, ProposalData>,
+    /// Factory index of each processed proposal keyed by its contract address
+    pub addresses: BTreeMap<Address, u64>,
+    /// Proposer elimination rounds
+    pub eliminations: BTreeMap<Address, u64>,
+    /// Synchronization progress
+    pub status: SyncStatus,
+    /// Provider for L2 chain data of the [SyncAgent]
+    pub l2_provider: Option<RootProvider>,
+    /// Persistent record of canonical games, including those pruned by the [SyncAgent]
+    pub history: Option<Arc<CanonicalHistory>>,
+    /// Whether the view was populated by a first refresh
+    pub seeded: bool,
+}
+
+impl KailuaServerState {
+    /// Refreshes the view using the proposals changed in the agent since the last refresh and
+    /// returns the lifecycle events observed in between.
+    ///
+    /// The first refresh only seeds the view with the restored or loaded proposals, which are
+    /// historical, and returns no events.
+    pub fn update(&mut self, agent: &mut SyncAgent) -> Vec<KailuaEvent> {
+        let mut events = vec![];
+        let changes = match agent.changes.as_mut() {
+            Some(changes) => std::mem::take(changes),
+            None => SyncChanges {
+                updated: agent.proposals.keys().copied().collect(),
+                pruned: Default::default(),
+            },
+        };
+        for index in changes.updated {
+            let Some(proposal) = agent.proposals.get(&index) else {
+                continue;
+            };
+            let data = ProposalData::from(proposal);
+            self.addresses.insert(proposal.contract, proposal.index);
+            let previous = self.proposals.insert(proposal.index, data.clone());
+            let was_resolved = previous.as_ref().is_some_and(|p| p.resolved_at != 0);
+            if previous.is_none() {
+                events.push(KailuaEvent::NewProposal(data.clone()));
+                if let Some(fault) = proposal.fault() {
+                    events.push(KailuaEvent::FaultDetected {
+                        proposal: data.clone(),
+                        divergence_point: fault.divergence_point(),
+                        trail: fault.is_trail(),
+                    });
+                }
+            }
+            if !was_resolved && data.resolved_at != 0 {
+                events.push(KailuaEvent::ProposalResolved(data));
+            }
+        }
+        // Drop the proposals freed by the agent
+        for index in changes.pruned {
+            if let Some(data) = self.proposals.remove(&index) {
+                self.addresses.remove(&data.contract);
+            }
+        }
+        for (proposer, index) in &agent.eliminations {
+            if !self.eliminations.contains_key(proposer) {
+                events.push(KailuaEvent::ProposerEliminated {
+                    proposer: *proposer,
+                    index: *index,
+                });
+            }
+        }
+        self.eliminations = agent.eliminations.clone();
+        let status = SyncStatus::from(agent);
+        if status.canonical_proposal_tip != self.status.canonical_proposal_tip {
+            if let Some(tip) = self.proposals.get(&status.canonical_proposal_tip) {
+                events.push(KailuaEvent::CanonicalTipChanged(tip.clone()));
+            }
+        }
+        self.status = status;
+        if !self.seeded {
+            self.seeded = true;
+            return vec![];
+        }
+        events
+    }
+
+    /// Looks up a proposal by its factory index or contract address
+    pub fn proposal(&self, id: ProposalId) -> Option<&ProposalData> {
+        let index = match id {
+            ProposalId::Index(index) => index,
+            ProposalId::Address(address) => *self.addresses.get(&address)?,
+        };
+        self.proposals.get(&index)
+    }
+}
+
+/// Identifies a proposal either by its factory index or its contract address
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProposalId {
+    Index(u64),
+    Address(Address),
+}
+
+/// The RPC representation of a [Proposal] without its blob data
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProposalData {
+    /// Address of the contract instance
+    pub contract: Address,
+    /// Address of the tied treasury
+    pub treasury: Address,
+    /// DGF Index of the game
+    pub index: u64,
+    /// DGF Index of the game's parent
+    pub parent: u64,
+    /// Address of the proposer
+    pub proposer: Address,
+    /// Contract creation timestamp
+    pub created_at: u64,
+    /// Versioned hashes of the intermediate output blobs
+    pub io_blob_hashes: Vec<B256>,
+    /// Claimed output root
+    pub output_root: B256,
+    /// Claimed output root block number
+    pub output_block_number: u64,
+    /// Proposal L1 head
+    pub l1_head: B256,
+    /// Proposal IO/Claim signature
+    pub signature: B256,
+    /// List of child proposals
+    pub children: BTreeSet<u64>,
+    /// Index of successor proposal
+    pub successor: Option<u64>,
+    /// Correctness of each intermediate output in proposal
+    pub correct_io: Vec<Option<bool>>,
+    /// Correctness of each trailing data element in proposal
+    pub correct_trail: Vec<Option<bool>>,
+    /// Correctness of claimed output root
+    pub correct_claim: Option<bool>,
+    /// Correctness of parent proposal
+    pub correct_parent: Option<bool>,
+    /// Overall correctness of the proposal
+    pub correct: Option<bool>,
+    /// Whether the proposal is canonical
+    pub canonical: Option<bool>,
+    /// When the proposal was resolved
+    pub resolved_at: u64,
+}
+
+impl From<&Proposal> for ProposalData {
+    fn from(proposal: &Proposal) -> Self {
+        Self {
+            contract: proposal.contract,
+            treasury: proposal.treasury,
+            index: proposal.index,
+            parent: proposal.parent,
+            proposer: proposal.proposer,
+            created_at: proposal.created_at,
+            io_blob_hashes: proposal.io_blobs.iter().map(|(hash, _)| *hash).collect(),
+            output_root: proposal.output_root,
+            output_block_number: proposal.output_block_number,
+            l1_head: proposal.l1_head,
+            signature: proposal.signature,
+            children: proposal.children.clone(),
+            successor: proposal.successor,
+            correct_io: proposal.correct_io.clone(),
+            correct_trail: proposal.correct_trail.clone(),
+            correct_claim: proposal.correct_claim,
+            correct_parent: proposal.correct_parent,
+            correct: proposal.is_correct(),
+            canonical: proposal.canonical,
+            resolved_at: proposal.resolved_at,
+        }
+    }
+}
+
+/// A snapshot of the synchronization progress of the [SyncAgent]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SyncStatus {
+    /// Address of the KailuaTreasury contract
+    pub treasury: Address,
+    /// Address of the KailuaGame contract
+    pub game: Address,
+    /// Factory game index of the latest canonical proposal
+    pub canonical_proposal_tip: u64,
+    /// Index of the next proposal to query
+    pub next_factory_index: u64,
+    /// Proposal indices whose processing is delayed
+    pub delayed_factory_indices: Vec<u64>,
+    /// Index of the last L2 block height whose output is known
+    pub last_output_index: u64,
+    /// Index of the last proposal resolved on chain
+    pub last_resolved_game: u64,
+}
+
+impl From<&SyncAgent> for SyncStatus {
+    fn from(agent: &SyncAgent) -> Self {
+        Self {
+            treasury: agent.deployment.treasury,
+            game: agent.deployment.game,
+            canonical_proposal_tip: agent.cursor.canonical_proposal_tip,
+            next_factory_index: agent.cursor.next_factory_index,
+            delayed_factory_indices: agent
+                .cursor
+                .delayed_factory_indices
+                .iter()
+                .copied()
+                .collect(),
+            last_output_index: agent.cursor.last_output_index,
+            last_resolved_game: agent.cursor.last_resolved_game,
+        }
+    }
+}
+
+/// The data required to prove a withdrawal against a canonical Kailua game
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WithdrawalProofContext {
+    /// The queried L2 block number
+    pub l2_block: u64,
+    /// DGF Index of the game
+    pub game_index: u64,
+    /// Address of the game contract
+    pub game_address: Address,
+    /// Output root block number of the game
+    pub game_l2_block: u64,
+    /// Output root claimed by the game
+    pub output_root: B256,
+    /// Preimage of the output root claimed by the game
+    pub output_root_proof: OutputRootProof,
+}
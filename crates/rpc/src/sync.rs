@@ -14,15 +14,17 @@
 
 use crate::api::KailuaServerCache;
 use crate::args::RpcArgs;
+use crate::events::KailuaServerEvents;
+use crate::history::CanonicalHistory;
+use crate::state::ProposalData;
 use anyhow::Context;
-use kailua_contracts::*;
-use kailua_sync::agent::{SyncAgent, FINAL_L2_BLOCK_RESOLVED};
-use kailua_sync::stall::Stall;
-use kailua_sync::{await_tel, KAILUA_GAME_TYPE};
+use kailua_sync::agent::{SyncAgent, SyncChanges, FINAL_L2_BLOCK_RESOLVED};
+use kailua_sync::{await_tel, await_tel_res};
 use opentelemetry::global::tracer;
 use opentelemetry::trace::FutureExt;
 use opentelemetry::trace::{TraceContextExt, Tracer};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{error, info, warn};
@@ -31,39 +33,65 @@ pub async fn handle_sync(
     args: RpcArgs,
     data_dir: PathBuf,
     server_cache: KailuaServerCache,
+    server_events: KailuaServerEvents,
 ) -> anyhow::Result<()> {
     let tracer = tracer("kailua");
     let context = opentelemetry::Context::current_with_span(tracer.start("handle_sync"));
 
-    // todo: auto update deployment
-    // check dgf for set implementation events
-    // order by their game index
-
-    // todo: init from beginning instead of last resolved game
-
     // initialize sync agent
     let mut agent = SyncAgent::new(
         &args.sync.provider,
-        data_dir,
+        data_dir.clone(),
         args.sync.kailua_game_implementation,
         args.sync.kailua_anchor_address,
         args.bypass_chain_registry,
     )
     .await?;
     info!("KailuaTreasury({:?})", agent.deployment.treasury);
+    // Track proposal changes so that the server state is refreshed incrementally
+    agent.changes = Some(SyncChanges {
+        updated: agent.proposals.keys().copied().collect(),
+        pruned: Default::default(),
+    });
 
-    // Check if deployment is still valid
-    let dispute_game_factory =
-        IDisputeGameFactory::new(agent.deployment.factory, &agent.provider.l1_provider);
-    let latest_game_impl_addr = dispute_game_factory
-        .gameImpls(KAILUA_GAME_TYPE)
-        .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
-        .await;
-    if latest_game_impl_addr != agent.deployment.game {
-        warn!(
-            "Deployment {} outdated. Found new deployment {latest_game_impl_addr}.",
-            agent.deployment.game
-        );
+    // Load persisted canonical game history
+    let history = Arc::new(CanonicalHistory::open(
+        &data_dir
+            .join("rpc")
+            .join(agent.deployment.cfg_hash.to_string())
+            .join(agent.deployment.treasury.to_string()),
+    )?);
+    let canonical = history.load_canonical()?;
+    info!("Loaded {} canonical games from storage.", canonical.len());
+    {
+        let mut cache = server_cache.write().await;
+        cache.canonical.extend(canonical);
+        cache.history = Some(history.clone());
+        cache.l2_provider = Some(agent.provider.l2_provider.clone());
+    }
+
+    // Synchronize from the end of the persisted history or the treasury if backfilling
+    let backfill = args.backfill;
+    let mut resume_point = history.resume_point()?;
+    if backfill {
+        let anchor = resume_point.unwrap_or(agent.deployment.treasury);
+        // Restored proposals are replayed from the last resolved game onward, so the history
+        // only has a gap if it ends before that game
+        let restored_from = agent
+            .proposals
+            .get(&agent.cursor.last_resolved_game)
+            .map(|proposal| (proposal.index, proposal.contract));
+        let resume_index = match resume_point {
+            Some(resume_point) => history.proposal(resume_point)?.map(|p| p.index),
+            None => None,
+        };
+        let is_complete = restored_from.is_some_and(|(index, contract)| {
+            contract == anchor || resume_index.is_some_and(|resume_index| resume_index >= index)
+        });
+        if !is_complete {
+            info!("Backfilling canonical games starting from {anchor}.");
+            await_tel_res!(context, agent.reset_to(anchor), "SyncAgent::reset_to")?;
+        }
     }
 
     loop {
@@ -122,12 +150,35 @@ pub async fn handle_sync(
                 proposal.contract, proposal.output_block_number, proposal.output_root
             );
             // Queue proposal for submission to rpc cache
-            new_entries.push((proposal.output_block_number, proposal.contract));
+            new_entries.push(ProposalData::from(proposal));
         }
 
-        // Send new entries to cache
-        if !new_entries.is_empty() {
-            server_cache.write().await.extend(new_entries);
+        // Persist new entries and the point up to which history is complete
+        if let Err(err) = history.put_canonical(&new_entries) {
+            error!("Failed to persist canonical games: {err:?}");
+        } else if let Some(last_resolved) = agent
+            .proposals
+            .get(&agent.cursor.last_resolved_game)
+            .filter(|_| backfill)
+        {
+            if resume_point != Some(last_resolved.contract) {
+                match history.set_resume_point(last_resolved.contract) {
+                    Ok(()) => resume_point = Some(last_resolved.contract),
+                    Err(err) => error!("Failed to persist resume point: {err:?}"),
+                }
+            }
+        }
+
+        // Send new entries and refreshed agent state to cache
+        let mut cache = server_cache.write().await;
+        cache.canonical.extend(
+            new_entries
+                .iter()
+                .map(|proposal| (proposal.output_block_number, proposal.contract)),
+        );
+        for event in cache.update(&mut agent) {
+            // Sending only fails when there are no active subscribers
+            let _ = server_events.send(event);
         }
     }
 }
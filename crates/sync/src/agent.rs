@@ -16,13 +16,17 @@ use crate::cursor::SyncCursor;
 use crate::deployment::SyncDeployment;
 use crate::proposal::{Proposal, ProposalSync};
 use crate::provider::optimism::fetch_rollup_config;
-use crate::provider::{ProviderArgs, SyncProvider};
+use crate::provider::{OutputRootMismatch, ProviderArgs, SyncProvider};
 use crate::stall::Stall;
+use crate::store::{SyncState, SyncStore};
 use crate::telemetry::SyncTelemetry;
-use crate::{await_tel, await_tel_res, retry_res_ctx_timeout, retry_res_timeout, KAILUA_GAME_TYPE};
+use crate::{await_tel, await_tel_res, retry_res_ctx_timeout, KAILUA_GAME_TYPE};
+use alloy::eips::BlockId;
 use alloy::network::Network;
 use alloy::primitives::{Address, B256, U256};
 use alloy::providers::Provider;
+use alloy::rpc::types::Filter;
+use alloy::sol_types::SolEvent;
 use anyhow::{anyhow, bail, Context};
 use futures::future::join_all;
 use itertools::Itertools;
@@ -39,12 +43,15 @@ use opentelemetry::trace::{TraceContextExt, Tracer};
 use opentelemetry::KeyValue;
 use std::collections::btree_map::Entry;
 use std::collections::{BTreeMap, BTreeSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
 pub const FINAL_L2_BLOCK_RESOLVED: &str = "Last resolved proposal l2 block reached final l2 block.";
 
+/// Maximum number of L1 blocks to query for factory events at once
+pub const UPGRADE_SCAN_BLOCK_RANGE: u64 = 1000;
+
 /// A stateful agent object for synchronizing with an on-chain Kailua deployment.
 pub struct SyncAgent {
     /// RPC providers to use for querying chain data
@@ -55,8 +62,10 @@ pub struct SyncAgent {
     pub config: RollupConfig,
     /// Kailua deployment configuration for instance being synchronized
     pub deployment: SyncDeployment,
-    /// Local persistent key-value store
-    pub db: Arc<rocksdb::DB>,
+    /// Base directory of the local persistent key-value store
+    pub data_dir: PathBuf,
+    /// Local persistent storage of outputs and agent state
+    pub db: Arc<SyncStore>,
     /// Pointers to the latest synchronized items
     pub cursor: SyncCursor,
     /// In-memory cache of op-node query results
@@ -69,12 +78,33 @@ pub struct SyncAgent {
     pub l1_heads: BTreeMap<u64, (Address, B256)>,
     /// In-memory cache of available l1-heads for derivation (inverse map)
     pub l1_heads_inv: BTreeMap<B256, (Address, u64)>,
+    /// Next L1 block to scan for KailuaGame implementation upgrades (None if pinned)
+    pub upgrade_scan_block: Option<u64>,
+    /// Queue of KailuaGame implementation upgrades keyed by the factory index of their first game
+    pub upgrades: BTreeMap<u64, SyncDeployment>,
+    /// Indices of proposals modified since the state was last persisted
+    pub unsaved_proposals: BTreeSet<u64>,
+    /// Indices of proposals pruned since the state was last persisted
+    pub pruned_proposals: BTreeSet<u64>,
+    /// Index from which restored proposals are reported as newly processed by the next sync
+    pub replay_index: Option<u64>,
+    /// Proposal changes not yet drained by an external consumer of the state (None if untracked)
+    pub changes: Option<SyncChanges>,
+}
+
+/// Proposals modified or pruned by the [SyncAgent] since they were last drained
+#[derive(Clone, Debug, Default)]
+pub struct SyncChanges {
+    /// Indices of proposals that were added or modified
+    pub updated: BTreeSet<u64>,
+    /// Indices of proposals freed from memory
+    pub pruned: BTreeSet<u64>,
 }
 
 impl SyncAgent {
     pub async fn new(
         provider_args: &ProviderArgs,
-        mut data_dir: PathBuf,
+        data_dir: PathBuf,
         game_impl_address: Option<Address>,
         anchor_address: Option<Address>,
         bypass_chain_registry: bool,
@@ -106,63 +136,225 @@ impl SyncAgent {
         let rollup_config_hash = config_hash(&config).expect("Configuration hash derivation error");
         info!("RollupConfigHash({})", hex::encode(rollup_config_hash));
 
+        // Follow implementation upgrades made after startup unless one was pinned
+        let upgrade_scan_block = match game_impl_address {
+            Some(_) => None,
+            None => Some(
+                await_tel!(
+                    context,
+                    tracer,
+                    "get_block_number",
+                    retry_res_ctx_timeout!(provider
+                        .l1_provider
+                        .get_block_number()
+                        .await
+                        .context("get_block_number"))
+                ) + 1,
+            ),
+        };
+
         // Load target deployment data
         let deployment = await_tel_res!(
             context,
             SyncDeployment::load(&provider, &config, game_impl_address),
             "Deployment::load"
         )?;
-        #[cfg(not(feature = "devnet"))]
-        {
-            let known_image_ids = [
-                B256::from(bytemuck::cast::<[u32; 8], [u8; 32]>(
-                    kailua_build::KAILUA_FPVM_KONA_ID,
-                )),
-                B256::from(bytemuck::cast::<[u32; 8], [u8; 32]>(
-                    kailua_build::KAILUA_FPVM_HOKULEA_ID,
-                )),
-                B256::from(bytemuck::cast::<[u32; 8], [u8; 32]>(
-                    kailua_build::KAILUA_FPVM_HANA_ID,
-                )),
-            ];
-            if !known_image_ids.contains(&deployment.image_id) {
-                bail!("Deployment image ID {:?} unknown.", deployment.image_id);
-            }
-        }
+        deployment.check_image_id()?;
 
         // Initialize persistent DB
-        data_dir.push(deployment.cfg_hash.to_string());
-        data_dir.push(deployment.treasury.to_string());
-        let db = Arc::new(
-            rocksdb::DB::open(&Self::db_options(), &data_dir).context("rocksdb::DB::open")?,
-        );
+        let db = Self::open_db(&data_dir, &deployment)?;
+
+        // Restore persisted state unless an explicit anchor is requested
+        let state = match anchor_address {
+            Some(_) => None,
+            None => await_tel!(
+                context,
+                tracer,
+                "restore_state",
+                Self::restore_state(&provider, &deployment, &db)
+            ),
+        };
+        let restored = state.is_some();
+        if !restored {
+            db.clear_state().context("SyncStore::clear_state")?;
+        }
 
         // Create cursor
-        let cursor = await_tel_res!(
-            context,
-            SyncCursor::load(&deployment, &provider, anchor_address),
-            "SyncCursor::load"
-        )?;
+        let (cursor, proposals, eliminations, l1_heads) = match state {
+            Some(state) => (
+                state.cursor,
+                state.proposals,
+                state.eliminations,
+                state.l1_heads,
+            ),
+            None => (
+                await_tel_res!(
+                    context,
+                    SyncCursor::load(&deployment, &provider, anchor_address),
+                    "SyncCursor::load"
+                )?,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            ),
+        };
+        let l1_heads_inv = l1_heads
+            .iter()
+            .map(|(block_no, (proposal, l1_head))| (*l1_head, (*proposal, *block_no)))
+            .collect();
 
-        Ok(Self {
+        let mut agent = Self {
             provider,
             telemetry,
             config,
             deployment,
+            data_dir,
             db,
             cursor,
             outputs: Default::default(),
-            proposals: Default::default(),
-            eliminations: Default::default(),
-            l1_heads: Default::default(),
-            l1_heads_inv: Default::default(),
-        })
+            proposals,
+            eliminations,
+            l1_heads,
+            l1_heads_inv,
+            upgrade_scan_block,
+            upgrades: Default::default(),
+            unsaved_proposals: Default::default(),
+            pruned_proposals: Default::default(),
+            replay_index: None,
+            changes: None,
+        };
+
+        // Reload the output commitments required by the restored proposals
+        if restored {
+            info!(
+                "Restored {} proposals from storage (next index {}).",
+                agent.proposals.len(),
+                agent.cursor.next_factory_index
+            );
+            agent.replay_index = Some(agent.cursor.last_resolved_game);
+            if let Some(last_resolved) = agent.proposals.get(&agent.cursor.last_resolved_game) {
+                let start = last_resolved
+                    .output_block_number
+                    .saturating_sub(agent.deployment.blocks_per_proposal());
+                await_tel_res!(
+                    context,
+                    tracer,
+                    "sync_outputs",
+                    agent.sync_outputs(
+                        start,
+                        agent.cursor.last_output_index,
+                        agent.deployment.output_block_span
+                    )
+                )?;
+            }
+        }
+
+        Ok(agent)
     }
 
-    fn db_options() -> rocksdb::Options {
-        let mut options = rocksdb::Options::default();
-        options.create_if_missing(true);
-        options
+    /// Discards the synchronized state, including any restored from storage, and restarts
+    /// synchronization from the given anchor
+    pub async fn reset_to(&mut self, anchor: Address) -> anyhow::Result<()> {
+        let tracer = tracer("kailua");
+        let context =
+            opentelemetry::Context::current_with_span(tracer.start("SyncAgent::reset_to"));
+
+        self.cursor = await_tel_res!(
+            context,
+            SyncCursor::load(&self.deployment, &self.provider, Some(anchor)),
+            "SyncCursor::load"
+        )?;
+        self.db.clear_state().context("SyncStore::clear_state")?;
+        if let Some(changes) = self.changes.as_mut() {
+            changes.updated.clear();
+            changes.pruned.extend(self.proposals.keys());
+        }
+        self.proposals.clear();
+        self.eliminations.clear();
+        self.l1_heads.clear();
+        self.l1_heads_inv.clear();
+        self.unsaved_proposals.clear();
+        self.pruned_proposals.clear();
+        self.replay_index = None;
+        Ok(())
+    }
+
+    fn open_db(data_dir: &Path, deployment: &SyncDeployment) -> anyhow::Result<Arc<SyncStore>> {
+        let path = data_dir
+            .join(deployment.cfg_hash.to_string())
+            .join(deployment.treasury.to_string());
+        Ok(Arc::new(SyncStore::open(&path)?))
+    }
+
+    /// Loads the persisted agent state if it passes all integrity checks
+    pub async fn restore_state(
+        provider: &SyncProvider,
+        deployment: &SyncDeployment,
+        db: &SyncStore,
+    ) -> Option<SyncState> {
+        let tracer = tracer("kailua");
+        let context =
+            opentelemetry::Context::current_with_span(tracer.start("SyncAgent::restore_state"));
+
+        let state = match db.load_state() {
+            Ok(Some(state)) => state,
+            Ok(None) => return None,
+            Err(err) => {
+                warn!("Discarding unreadable persisted state: {err:?}");
+                return None;
+            }
+        };
+        if let Err(err) = state.validate(deployment.treasury) {
+            warn!("Discarding inconsistent persisted state: {err:?}");
+            return None;
+        }
+        // Check that the persisted anchor and tip still match the chain
+        let dispute_game_factory =
+            IDisputeGameFactory::new(deployment.factory, &provider.l1_provider);
+        for index in [
+            state.cursor.last_resolved_game,
+            state.cursor.canonical_proposal_tip,
+        ] {
+            let proposal = state.proposals.get(&index)?;
+            let gameAtIndexReturn {
+                proxy_: game_address,
+                ..
+            } = dispute_game_factory
+                .gameAtIndex(U256::from(index))
+                .stall_with_context(context.clone(), "DisputeGameFactory::gameAtIndex")
+                .await;
+            if game_address != proposal.contract {
+                warn!(
+                    "Discarding persisted state. Proposal {index} at {} does not match on-chain game {game_address}.",
+                    proposal.contract
+                );
+                return None;
+            }
+        }
+        Some(state)
+    }
+
+    /// Persists all agent state modified since the last call
+    pub fn persist_state(&mut self) -> anyhow::Result<()> {
+        self.db.put_state(
+            &self.cursor,
+            self.unsaved_proposals
+                .iter()
+                .filter_map(|index| self.proposals.get(index)),
+            self.pruned_proposals.iter().copied(),
+            &self.eliminations,
+            &self.l1_heads,
+        )?;
+        if let Some(changes) = &mut self.changes {
+            changes.updated.extend(&self.unsaved_proposals);
+            for index in &self.pruned_proposals {
+                changes.updated.remove(index);
+                changes.pruned.insert(*index);
+            }
+        }
+        self.unsaved_proposals.clear();
+        self.pruned_proposals.clear();
+        Ok(())
     }
 
     pub fn prune_data(&mut self) -> anyhow::Result<BTreeSet<u64>> {
@@ -175,6 +367,8 @@ impl SyncAgent {
             if self.proposals.remove(&i).is_some() {
                 info!("Freed proposal {i} from memory.");
                 proposals.insert(i);
+                self.unsaved_proposals.remove(&i);
+                self.pruned_proposals.insert(i);
             }
         }
         // delete all delayed proposals prior to last resolved proposal
@@ -198,7 +392,7 @@ impl SyncAgent {
                 // abort early once we hit an output commitment we never stored during this run
                 break;
             }
-            if let Err(err) = self.db.delete(output_number.to_be_bytes()) {
+            if let Err(err) = self.db.delete_output(output_number) {
                 error!("Failed to delete output commitment {output_number} from storage: {err:?}.");
             }
             commitments += 1;
@@ -253,7 +447,7 @@ impl SyncAgent {
                 "Syncing with op-node from block {} until block {output_block_number}",
                 self.cursor.last_output_index
             );
-            await_tel!(
+            await_tel_res!(
                 context,
                 tracer,
                 "sync_outputs",
@@ -262,21 +456,58 @@ impl SyncAgent {
                     output_block_number,
                     self.deployment.output_block_span
                 )
-            );
+            )?;
         }
 
+        // check for deployment upgrades
+        let upgrade_scan_block =
+            await_tel_res!(context, tracer, "sync_upgrades", self.sync_upgrades())?;
+
         // load new proposals
         let dispute_game_factory =
             IDisputeGameFactory::new(self.deployment.factory, self.provider.l1_provider.clone());
         let game_count: u64 = dispute_game_factory
             .gameCount()
+            .block(
+                upgrade_scan_block
+                    .map(BlockId::number)
+                    .unwrap_or(BlockId::latest()),
+            )
             .stall_with_context(context.clone(), "DisputeGameFactory::gameCount")
             .await
             .to();
-        let first_factory_index = self.cursor.next_factory_index;
+        let mut first_factory_index = self
+            .replay_index
+            .take()
+            .unwrap_or(self.cursor.next_factory_index);
         let mut delayed_indices = Vec::new();
-        while self.cursor.has_next(game_count) {
+        loop {
+            // Switch deployments once all games created before the next upgrade are processed
+            if let Some(upgrade_index) = self.ready_upgrade(&delayed_indices) {
+                if let Err(err) = self
+                    .apply_upgrade(upgrade_index)
+                    .with_context(context.clone())
+                    .await
+                {
+                    warn!("Failed to apply deployment upgrade at index {upgrade_index}: {err:?}");
+                    break;
+                }
+                first_factory_index = first_factory_index.min(self.cursor.next_factory_index);
+                continue;
+            }
+            if !self.cursor.has_next(game_count) {
+                break;
+            }
             let proposal_index = self.cursor.next_index();
+            // Games created under an upgrade must be processed using the upgraded deployment
+            if self
+                .upgrades
+                .first_key_value()
+                .is_some_and(|(upgrade_index, _)| proposal_index >= *upgrade_index)
+            {
+                info!("Delaying deployment upgrade until preceding proposals are processed.");
+                break;
+            }
 
             match self
                 .sync_proposal(&dispute_game_factory, proposal_index)
@@ -382,6 +613,8 @@ impl SyncAgent {
             }
             // update resolved status
             last_unresolved_proposal.resolved_at = resolved_at;
+            self.unsaved_proposals
+                .insert(last_unresolved_proposal_index);
             // move cursor forward
             self.cursor.last_resolved_game = last_unresolved_proposal_index;
             // Prune memory and storage
@@ -390,6 +623,11 @@ impl SyncAgent {
             }
         }
 
+        // Persist updated state
+        if let Err(err) = self.persist_state() {
+            error!("Failed to persist agent state: {err:?}.");
+        }
+
         // Update sync telemetry
         if let Some(canonical_tip) = self.proposals.get(&self.cursor.canonical_proposal_tip) {
             self.telemetry.sync_canonical.record(
@@ -438,6 +676,196 @@ impl SyncAgent {
         Ok(proposals)
     }
 
+    /// Queues any KailuaGame implementation upgrades made since the last scan and returns the
+    /// last scanned L1 block number, if upgrades are being followed.
+    pub async fn sync_upgrades(&mut self) -> anyhow::Result<Option<u64>> {
+        let tracer = tracer("kailua");
+        let context =
+            opentelemetry::Context::current_with_span(tracer.start("SyncAgent::sync_upgrades"));
+
+        let Some(mut from_block) = self.upgrade_scan_block else {
+            return Ok(None);
+        };
+        let latest_block = await_tel!(
+            context,
+            tracer,
+            "get_block_number",
+            retry_res_ctx_timeout!(self
+                .provider
+                .l1_provider
+                .get_block_number()
+                .await
+                .context("get_block_number"))
+        );
+        let dispute_game_factory =
+            IDisputeGameFactory::new(self.deployment.factory, self.provider.l1_provider.clone());
+        while from_block <= latest_block {
+            let to_block = latest_block.min(from_block + UPGRADE_SCAN_BLOCK_RANGE - 1);
+            let filter = Filter::new()
+                .address(self.deployment.factory)
+                .event_signature(IDisputeGameFactory::ImplementationSet::SIGNATURE_HASH)
+                .topic2(B256::from(U256::from(KAILUA_GAME_TYPE)))
+                .from_block(from_block)
+                .to_block(to_block);
+            let logs = await_tel!(
+                context,
+                tracer,
+                "get_logs",
+                retry_res_ctx_timeout!(self
+                    .provider
+                    .l1_provider
+                    .get_logs(&filter)
+                    .await
+                    .context("get_logs"))
+            );
+            // Only the implementation in effect at the end of each block matters, which was
+            // installed by the last event of the block
+            let mut upgrade_blocks = BTreeMap::new();
+            for log in &logs {
+                if let (Some(block_number), Some(log_index)) = (log.block_number, log.log_index) {
+                    let last_index = upgrade_blocks.entry(block_number).or_insert(log_index);
+                    *last_index = (*last_index).max(log_index);
+                }
+            }
+            for (block_number, log_index) in upgrade_blocks {
+                self.queue_upgrade(&dispute_game_factory, block_number, log_index)
+                    .with_context(context.clone())
+                    .await
+                    .context("queue_upgrade")?;
+            }
+            from_block = to_block + 1;
+            self.upgrade_scan_block = Some(from_block);
+        }
+        Ok(Some(from_block - 1))
+    }
+
+    /// Queues the KailuaGame implementation installed in the factory by the event at the given
+    /// log index of an L1 block
+    pub async fn queue_upgrade<P: Provider<N>, N: Network>(
+        &mut self,
+        dispute_game_factory: &IDisputeGameFactoryInstance<P, N>,
+        block_number: u64,
+        log_index: u64,
+    ) -> anyhow::Result<()> {
+        let tracer = tracer("kailua");
+        let context =
+            opentelemetry::Context::current_with_span(tracer.start("SyncAgent::queue_upgrade"));
+
+        let game_impl_address = dispute_game_factory
+            .gameImpls(KAILUA_GAME_TYPE)
+            .block(BlockId::number(block_number))
+            .stall_with_context(context.clone(), "DisputeGameFactory::gameImpls")
+            .await;
+        // Skip implementations that are already followed or uninstalled
+        let latest_game_impl_address = self
+            .upgrades
+            .last_key_value()
+            .map(|(_, deployment)| deployment.game)
+            .unwrap_or(self.deployment.game);
+        if game_impl_address == latest_game_impl_address || game_impl_address.is_zero() {
+            return Ok(());
+        }
+        // Skip treasuries temporarily installed to create their initial instance
+        let treasury = KailuaTournament::new(game_impl_address, &self.provider.l1_provider)
+            .KAILUA_TREASURY()
+            .stall_with_context(context.clone(), "KailuaTournament::KAILUA_TREASURY")
+            .await;
+        if treasury == game_impl_address {
+            info!("Skipping installation of KailuaTreasury implementation {game_impl_address}.");
+            return Ok(());
+        }
+        // Load upgraded deployment parameters
+        let deployment = SyncDeployment::load_implementation(&self.provider, game_impl_address)
+            .with_context(context.clone())
+            .await?;
+        // Games created within the upgrade block before the implementation was set use the
+        // previous implementation
+        let game_count: u64 = dispute_game_factory
+            .gameCount()
+            .block(BlockId::number(block_number.saturating_sub(1)))
+            .stall_with_context(context.clone(), "DisputeGameFactory::gameCount")
+            .await
+            .to();
+        let filter = Filter::new()
+            .address(self.deployment.factory)
+            .event_signature(IDisputeGameFactory::DisputeGameCreated::SIGNATURE_HASH)
+            .from_block(block_number)
+            .to_block(block_number);
+        let created_logs = await_tel!(
+            context,
+            tracer,
+            "get_logs",
+            retry_res_ctx_timeout!(self
+                .provider
+                .l1_provider
+                .get_logs(&filter)
+                .await
+                .context("get_logs"))
+        );
+        let created_before = created_logs
+            .iter()
+            .filter(|log| log.log_index.is_some_and(|index| index < log_index))
+            .count() as u64;
+        let first_game_index = game_count + created_before;
+        info!(
+            "Found KailuaGame implementation upgrade to {game_impl_address} \
+            (treasury {}, image id {}) effective from factory index {first_game_index}.",
+            deployment.treasury, deployment.image_id
+        );
+        self.upgrades.insert(first_game_index, deployment);
+        Ok(())
+    }
+
+    /// Returns the index of the next queued upgrade if all games created before it were processed
+    pub fn ready_upgrade(&self, delayed_indices: &[u64]) -> Option<u64> {
+        let upgrade_index = *self.upgrades.first_key_value()?.0;
+        let is_ready = self.cursor.next_factory_index >= upgrade_index
+            && !delayed_indices
+                .iter()
+                .chain(self.cursor.delayed_factory_indices.iter())
+                .any(|i| *i < upgrade_index);
+        is_ready.then_some(upgrade_index)
+    }
+
+    /// Switches over to the deployment upgrade queued at the given factory index
+    pub async fn apply_upgrade(&mut self, first_game_index: u64) -> anyhow::Result<()> {
+        let tracer = tracer("kailua");
+        let context =
+            opentelemetry::Context::current_with_span(tracer.start("SyncAgent::apply_upgrade"));
+
+        let Some(deployment) = self.upgrades.get(&first_game_index).cloned() else {
+            bail!("No deployment upgrade queued at index {first_game_index}.");
+        };
+        deployment.check_image_id()?;
+        // A new treasury starts a new proposal tree from its last resolved instance
+        if deployment.treasury != self.deployment.treasury {
+            let cursor = await_tel_res!(
+                context,
+                SyncCursor::load(&deployment, &self.provider, None),
+                "SyncCursor::load"
+            )?;
+            let db = Self::open_db(&self.data_dir, &deployment)?;
+            db.clear_state().context("SyncStore::clear_state")?;
+            self.db = db;
+            self.cursor = cursor;
+            if let Some(changes) = &mut self.changes {
+                changes.updated.clear();
+                changes.pruned.extend(self.proposals.keys());
+            }
+            self.proposals.clear();
+            self.eliminations.clear();
+            self.unsaved_proposals.clear();
+            self.pruned_proposals.clear();
+        }
+        warn!(
+            "Upgraded deployment from KailuaGame {} to {} at factory index {first_game_index}.",
+            self.deployment.game, deployment.game
+        );
+        self.deployment = deployment;
+        self.upgrades.remove(&first_game_index);
+        Ok(())
+    }
+
     pub async fn sync_l1_head(&mut self, proposal: Address, l1_head: B256) {
         let tracer = tracer("kailua");
         let context = opentelemetry::Context::current_with_span(
@@ -565,11 +993,13 @@ impl SyncAgent {
         if is_proposal_canonical {
             if let Some(parent) = self.proposals.get_mut(&proposal.parent) {
                 parent.successor = Some(proposal.index);
+                self.unsaved_proposals.insert(proposal.parent);
             }
         }
 
         // Store proposal and return inclusion
         let result = proposal.as_success();
+        self.unsaved_proposals.insert(proposal.index);
         self.proposals.insert(proposal.index, proposal);
         Ok(result)
     }
@@ -696,6 +1126,7 @@ impl SyncAgent {
                 return Ok(false);
             };
             // Append child to parent tournament children list
+            self.unsaved_proposals.insert(parent.index);
             if !parent.append_child(proposal.index) {
                 warn!(
                     "Attempted to append duplicate child {} to parent {}.",
@@ -733,15 +1164,19 @@ impl SyncAgent {
         self.outputs.get(&block_number).cloned()
     }
 
-    pub async fn sync_outputs(&mut self, mut start: u64, end: u64, step: u64) {
+    pub async fn sync_outputs(
+        &mut self,
+        mut start: u64,
+        end: u64,
+        step: u64,
+    ) -> anyhow::Result<()> {
         while start <= end {
             // perform at most 1024 tasks at a time
             let end = end.min(start + 128 * step);
 
             // check persisted data
             for i in (start..=end).step_by(step as usize) {
-                if let Ok(Some(output)) = self.db.get(i.to_be_bytes()) {
-                    let output = B256::from_slice(&output);
+                if let Ok(Some(output)) = self.db.get_output(i) {
                     self.outputs.insert(i, output);
                     if self.cursor.last_output_index < i {
                         self.cursor.last_output_index = i;
@@ -753,13 +1188,8 @@ impl SyncAgent {
                 .step_by(step as usize)
                 .filter(|i| !self.outputs.contains_key(i))
                 .map(|i| {
-                    let provider = self.provider.op_provider.clone();
-                    Box::pin(async move {
-                        (
-                            i,
-                            retry_res_timeout!(provider.output_at_block(i).await).await,
-                        )
-                    })
+                    let provider = &self.provider;
+                    Box::pin(async move { (i, provider.output_at_block(i).await) })
                 })
                 .collect_vec();
             let outputs = join_all(outputs).await;
@@ -769,10 +1199,17 @@ impl SyncAgent {
             }
             // Store outputs in memory and database
             for (i, output) in outputs.into_iter() {
+                let output = match output {
+                    Ok(output) => output,
+                    Err(err) => {
+                        if err.downcast_ref::<OutputRootMismatch>().is_some() {
+                            self.telemetry.sync_output_mismatch.add(1, &[]);
+                        }
+                        return Err(err.context(format!("output_at_block {i}")));
+                    }
+                };
                 self.outputs.insert(i, output);
-                self.db
-                    .put(i.to_be_bytes(), output.0)
-                    .expect("Database error");
+                self.db.put_output(i, output).expect("Database error");
                 if self.cursor.last_output_index < i {
                     self.cursor.last_output_index = i;
                 }
@@ -781,5 +1218,6 @@ impl SyncAgent {
             // jump forward
             start = end + step;
         }
+        Ok(())
     }
 }
@@ -0,0 +1,260 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::proposal::Proposal;
+use crate::stall::Stall;
+use crate::transact::{Transact, TransactArgs};
+use crate::{await_tel, await_tel_res};
+use alloy::network::{Network, TransactionBuilder4844};
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use anyhow::Context;
+use kailua_contracts::*;
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use std::future::IntoFuture;
+use tracing::{info, warn};
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct BondClaimArgs {
+    /// Minimum claimable amount (wei) required before submitting a bond claim
+    #[clap(long, env, required = false, default_value = "0")]
+    pub bond_claim_threshold: U256,
+    /// Maximum number of elimination bonds to claim in a single transaction
+    #[clap(long, env, required = false, default_value_t = 64)]
+    pub bond_claim_batch_size: u64,
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct BondManagerArgs {
+    /// Whether to periodically claim the bonds owed by the treasury to this agent
+    #[clap(long, env, default_value_t = false)]
+    pub claim_bonds: bool,
+    /// Seconds to wait between bond claim attempts
+    #[clap(long, env, required = false, default_value_t = 3600)]
+    pub bond_claim_interval: u64,
+
+    #[clap(flatten)]
+    pub claim: BondClaimArgs,
+}
+
+/// The bonds held by a treasury on behalf of an account
+#[derive(Clone, Debug, Default)]
+pub struct BondStatus {
+    /// Address of the KailuaTreasury contract
+    pub treasury: Address,
+    /// Address of the bond owner
+    pub account: Address,
+    /// Proposer bond currently held by the treasury
+    pub paid_bond: U256,
+    /// Index of the proposal at which the account was eliminated as a proposer
+    pub elimination_round: u64,
+    /// Last proposal made by the account
+    pub last_proposal: Address,
+    /// Whether the last proposal made by the account has been finalized
+    pub last_proposal_resolved: bool,
+    /// Eliminated proposers and their bonds yet to be claimed by the account
+    pub unclaimed_eliminations: Vec<(Address, U256)>,
+    /// Sum of the bonds yet to be claimed for the account's eliminations
+    pub unclaimed_elimination_bonds: U256,
+}
+
+impl BondStatus {
+    /// Returns the proposer bond amount that can currently be claimed back
+    pub fn claimable_proposer_bond(&self) -> U256 {
+        if self.elimination_round == 0 && self.last_proposal_resolved {
+            self.paid_bond
+        } else {
+            U256::ZERO
+        }
+    }
+}
+
+/// Queries the treasury for the proposer bond and elimination rewards owed to an account
+pub async fn fetch_bond_status<P: Provider<N>, N: Network>(
+    provider: P,
+    treasury: Address,
+    account: Address,
+) -> anyhow::Result<BondStatus> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("fetch_bond_status"));
+
+    let treasury_instance = KailuaTreasury::new(treasury, &provider);
+    let paid_bond = treasury_instance
+        .paidBonds(account)
+        .stall_with_context(context.clone(), "KailuaTreasury::paidBonds")
+        .await;
+    let elimination_round: u64 = treasury_instance
+        .eliminationRound(account)
+        .stall_with_context(context.clone(), "KailuaTreasury::eliminationRound")
+        .await
+        .to();
+    let last_proposal = treasury_instance
+        .lastProposal(account)
+        .stall_with_context(context.clone(), "KailuaTreasury::lastProposal")
+        .await;
+
+    // The proposer bond is locked until the tournament of its last proposal is won
+    let last_proposal_resolved = if last_proposal.is_zero() {
+        true
+    } else {
+        let parent_address = KailuaTournament::new(last_proposal, &provider)
+            .parentGame()
+            .stall_with_context(context.clone(), "KailuaTournament::parentGame")
+            .await;
+        let parent_instance = KailuaTournament::new(parent_address, &provider);
+        let contender_index = parent_instance
+            .contenderIndex()
+            .stall_with_context(context.clone(), "KailuaTournament::contenderIndex")
+            .await;
+        let contender_address = parent_instance
+            .children(U256::from(contender_index))
+            .stall_with_context(context.clone(), "KailuaTournament::children")
+            .await;
+        let contender_status = KailuaTournament::new(contender_address, &provider)
+            .status()
+            .stall_with_context(context.clone(), "KailuaTournament::status")
+            .await;
+        Proposal::parse_finality(contender_status)? == Some(true)
+    };
+
+    // Collect the bonds of all eliminated proposers not yet paid out
+    let eliminations_paid: u64 = treasury_instance
+        .eliminationsPaid(account)
+        .stall_with_context(context.clone(), "KailuaTreasury::eliminationsPaid")
+        .await
+        .to();
+    let mut unclaimed_eliminations = vec![];
+    let mut unclaimed_elimination_bonds = U256::ZERO;
+    for i in eliminations_paid.. {
+        let eliminated = match await_tel!(
+            context,
+            tracer,
+            "KailuaTreasury::eliminations",
+            treasury_instance
+                .eliminations(account, U256::from(i))
+                .call()
+                .into_future()
+        ) {
+            Ok(eliminated) => eliminated,
+            // reading past the end of the eliminations list reverts
+            Err(err) if err.as_revert_data().is_some() => break,
+            Err(err) => return Err(err).context("KailuaTreasury::eliminations"),
+        };
+        let bond = treasury_instance
+            .paidBonds(eliminated)
+            .stall_with_context(context.clone(), "KailuaTreasury::paidBonds")
+            .await;
+        unclaimed_elimination_bonds += bond;
+        unclaimed_eliminations.push((eliminated, bond));
+    }
+
+    Ok(BondStatus {
+        treasury,
+        account,
+        paid_bond,
+        elimination_round,
+        last_proposal,
+        last_proposal_resolved,
+        unclaimed_eliminations,
+        unclaimed_elimination_bonds,
+    })
+}
+
+/// Claims the bonds owed by the treasury to the provider's signer whenever they exceed the
+/// configured threshold, returning the total amount claimed
+pub async fn claim_bonds<P: Provider<N>, N: Network>(
+    provider: P,
+    treasury: Address,
+    account: Address,
+    claim_proposer_bond: bool,
+    claim_args: &BondClaimArgs,
+    txn_args: &TransactArgs,
+) -> anyhow::Result<U256>
+where
+    N::TransactionRequest: TransactionBuilder4844,
+{
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("claim_bonds"));
+
+    let status = await_tel_res!(
+        context,
+        fetch_bond_status(&provider, treasury, account),
+        "fetch_bond_status"
+    )?;
+    let treasury_instance = KailuaTreasury::new(treasury, &provider);
+    let mut claimed = U256::ZERO;
+
+    // Claim elimination rewards
+    let claimable = status.unclaimed_elimination_bonds;
+    if claimable.is_zero() || claimable < claim_args.bond_claim_threshold {
+        info!(
+            "Not claiming {claimable} wei in elimination bonds from {} eliminations.",
+            status.unclaimed_eliminations.len()
+        );
+    } else {
+        let claims = claim_args
+            .bond_claim_batch_size
+            .min(status.unclaimed_eliminations.len() as u64);
+        let payout = status
+            .unclaimed_eliminations
+            .iter()
+            .take(claims as usize)
+            .map(|(_, bond)| *bond)
+            .sum::<U256>();
+        info!("Claiming {payout} of {claimable} wei in elimination bonds.");
+        treasury_instance
+            .claimEliminationBonds(U256::from(claims))
+            .replaceable_transact_with_context(
+                context.clone(),
+                "KailuaTreasury::claimEliminationBonds",
+                txn_args,
+            )
+            .await
+            .context("KailuaTreasury::claimEliminationBonds")?;
+        claimed += payout;
+    }
+
+    // Claim back the proposer bond
+    if claim_proposer_bond {
+        let claimable = status.claimable_proposer_bond();
+        if status.elimination_round != 0 {
+            warn!(
+                "Proposer bond of {account} forfeited at proposal {}.",
+                status.elimination_round
+            );
+        } else if !status.last_proposal_resolved {
+            info!(
+                "Proposer bond of {} wei locked until {} is resolved.",
+                status.paid_bond, status.last_proposal
+            );
+        } else if claimable.is_zero() || claimable < claim_args.bond_claim_threshold {
+            info!("Not claiming {claimable} wei in proposer bond.");
+        } else {
+            info!("Claiming {claimable} wei in proposer bond.");
+            treasury_instance
+                .claimProposerBond()
+                .replaceable_transact_with_context(
+                    context.clone(),
+                    "KailuaTreasury::claimProposerBond",
+                    txn_args,
+                )
+                .await
+                .context("KailuaTreasury::claimProposerBond")?;
+            claimed += claimable;
+        }
+    }
+
+    Ok(claimed)
+}
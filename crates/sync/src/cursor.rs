@@ -22,9 +22,11 @@ use kailua_contracts::*;
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{TraceContextExt, Tracer};
 use opentelemetry::Context;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
 /// A collection of pointers to statefully track synchrony information
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SyncCursor {
     /// Factory game index of the latest canonical proposal
     pub canonical_proposal_tip: u64,
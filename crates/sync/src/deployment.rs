@@ -16,10 +16,11 @@ use crate::provider::SyncProvider;
 use crate::stall::Stall;
 use crate::KAILUA_GAME_TYPE;
 use alloy::primitives::{Address, B256};
+use alloy::providers::Provider;
 use kailua_contracts::*;
 use kona_genesis::RollupConfig;
 use opentelemetry::global::tracer;
-use opentelemetry::trace::{TraceContextExt, Tracer};
+use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
 use opentelemetry::Context;
 use std::process::exit;
 use tracing::{error, info, warn};
@@ -94,14 +95,36 @@ impl SyncDeployment {
             info!("Using the latest KailuaGame implementation {kailua_game_implementation_address} from DisputeGameFactory.");
         }
 
-        let kailua_game_implementation =
-            KailuaGame::new(kailua_game_implementation_address, &provider.l1_provider);
-        info!("KailuaGame({:?})", kailua_game_implementation.address());
+        info!("KailuaGame({kailua_game_implementation_address:?})");
         if kailua_game_implementation_address.is_zero() {
             error!("Fault proof game is not installed!");
             exit(1);
         }
 
+        Self::load_implementation(provider, kailua_game_implementation_address)
+            .with_context(context.clone())
+            .await
+    }
+
+    /// Loads the parameters of the given KailuaGame implementation contract
+    pub async fn load_implementation(
+        provider: &SyncProvider,
+        game_impl_address: Address,
+    ) -> anyhow::Result<Self> {
+        Self::load_from_l1(&provider.l1_provider, game_impl_address).await
+    }
+
+    /// Loads the parameters of the given KailuaGame implementation contract using only an L1
+    /// provider
+    pub async fn load_from_l1<P: Provider>(
+        l1_provider: &P,
+        game_impl_address: Address,
+    ) -> anyhow::Result<Self> {
+        let tracer = tracer("kailua");
+        let context =
+            Context::current_with_span(tracer.start("SyncDeployment::load_implementation"));
+
+        let kailua_game_implementation = KailuaGame::new(game_impl_address, l1_provider);
         let treasury = kailua_game_implementation
             .KAILUA_TREASURY()
             .stall_with_context(context.clone(), "KailuaGame::KAILUA_TREASURY")
@@ -170,6 +193,28 @@ impl SyncDeployment {
         })
     }
 
+    /// Rejects deployments whose FPVM image ID is not supported by this build
+    pub fn check_image_id(&self) -> anyhow::Result<()> {
+        #[cfg(not(feature = "devnet"))]
+        {
+            let known_image_ids = [
+                B256::from(bytemuck::cast::<[u32; 8], [u8; 32]>(
+                    kailua_build::KAILUA_FPVM_KONA_ID,
+                )),
+                B256::from(bytemuck::cast::<[u32; 8], [u8; 32]>(
+                    kailua_build::KAILUA_FPVM_HOKULEA_ID,
+                )),
+                B256::from(bytemuck::cast::<[u32; 8], [u8; 32]>(
+                    kailua_build::KAILUA_FPVM_HANA_ID,
+                )),
+            ];
+            if !known_image_ids.contains(&self.image_id) {
+                anyhow::bail!("Deployment image ID {:?} unknown.", self.image_id);
+            }
+        }
+        Ok(())
+    }
+
     pub fn min_proposal_time(&self, proposal_block_number: u64) -> u64 {
         self.genesis_time + proposal_block_number * self.block_time + 1
     }
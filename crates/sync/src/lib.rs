@@ -15,6 +15,7 @@
 pub mod agent;
 pub mod args;
 pub mod blobs;
+pub mod bonds;
 pub mod cursor;
 pub mod deployment;
 pub mod fault;
@@ -22,6 +23,7 @@ pub mod proposal;
 pub mod provider;
 pub mod retry;
 pub mod stall;
+pub mod store;
 pub mod telemetry;
 pub mod transact;
 
@@ -17,10 +17,12 @@ use crate::fault::Fault;
 use crate::provider::beacon::blob_sidecar;
 use crate::provider::SyncProvider;
 use crate::stall::Stall;
+use crate::transact::rpc::get_block;
 use crate::{await_tel, await_tel_res};
-use alloy::consensus::{Blob, BlobTransactionSidecar};
+use alloy::consensus::{Blob, BlobTransactionSidecar, BlockHeader};
 use alloy::eips::eip4844::FIELD_ELEMENTS_PER_BLOB;
-use alloy::network::Network;
+use alloy::eips::BlockNumberOrTag;
+use alloy::network::{BlockResponse, Network};
 use alloy::primitives::{Address, Bytes, B256, U256};
 use alloy::providers::Provider;
 use alloy_rpc_types_beacon::sidecar::BlobData;
@@ -89,6 +91,36 @@ pub struct Proposal {
     pub resolved_at: u64,
 }
 
+#[cfg(any(test, feature = "test-utils"))]
+impl Proposal {
+    /// Creates an unresolved proposal without blob data whose fields are derived from its index
+    pub fn mock(index: u64, parent: u64) -> Self {
+        Self {
+            contract: Address::repeat_byte(index as u8),
+            treasury: Address::ZERO,
+            index,
+            parent,
+            proposer: Address::repeat_byte(0xA0 | index as u8),
+            created_at: index,
+            io_blobs: vec![],
+            io_field_elements: vec![],
+            trail_field_elements: vec![],
+            output_root: B256::repeat_byte(index as u8),
+            output_block_number: index * 10,
+            l1_head: B256::repeat_byte(0xF0 | index as u8),
+            signature: B256::repeat_byte(index as u8),
+            children: BTreeSet::new(),
+            successor: None,
+            correct_io: vec![],
+            correct_trail: vec![],
+            correct_claim: None,
+            correct_parent: None,
+            canonical: None,
+            resolved_at: 0,
+        }
+    }
+}
+
 pub enum ProposalSync {
     SUCCESS(Address, B256),
     DELAYED(u64),
@@ -684,4 +716,24 @@ impl Proposal {
             .await
             .is_zero()
     }
+
+    /// Returns the seconds left in the challenge window of the proposal as of the latest block
+    pub async fn fetch_current_challenger_duration<P: Provider<N>, N: Network>(
+        &self,
+        provider: P,
+    ) -> u64 {
+        let tracer = tracer("kailua");
+        let context = opentelemetry::Context::current_with_span(
+            tracer.start("Proposal::fetch_current_challenger_duration"),
+        );
+
+        let chain_time = await_tel!(context, get_block(&provider, BlockNumberOrTag::Latest))
+            .header()
+            .timestamp();
+
+        self.tournament_contract_instance(&provider)
+            .getChallengerDuration(U256::from(chain_time))
+            .stall_with_context(context.clone(), "KailuaTournament::getChallengerDuration")
+            .await
+    }
 }
@@ -12,73 +12,85 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::provider::endpoint_urls;
+use crate::provider::health::EndpointHealth;
 use crate::{await_tel, retry_res_timeout};
 use alloy::consensus::{Blob, BlobTransactionSidecar};
 use alloy::eips::eip4844::kzg_to_versioned_hash;
 use alloy::primitives::B256;
 use alloy_rpc_types_beacon::sidecar::{BeaconBlobBundle, BlobData};
-use anyhow::{bail, Context};
+use anyhow::{anyhow, bail, Context};
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
-use tracing::debug;
+use tracing::{debug, warn};
 
 #[derive(Clone, Debug)]
 pub struct BlobProvider {
-    pub cl_node_endpoint: String,
+    pub cl_node_endpoints: Vec<String>,
+    pub health: EndpointHealth,
     pub client: Client,
     pub genesis_time: u64,
     pub seconds_per_slot: u64,
 }
 
 impl BlobProvider {
-    pub async fn new(cl_node_endpoint: String) -> anyhow::Result<Self> {
+    pub async fn new(cl_node_endpoints: String) -> anyhow::Result<Self> {
         let tracer = tracer("kailua");
         let context = opentelemetry::Context::current_with_span(tracer.start("BlobProvider::new"));
 
-        let cl_node_endpoint = cl_node_endpoint.trim_end_matches('/').to_owned();
-        let client = Client::new();
+        let cl_node_endpoints = endpoint_urls(&cl_node_endpoints)
+            .into_iter()
+            .map(|endpoint| endpoint.trim_end_matches('/').to_owned())
+            .collect::<Vec<_>>();
+        if cl_node_endpoints.is_empty() {
+            bail!("No beacon endpoints provided.");
+        }
+        let mut provider = Self {
+            health: EndpointHealth::new(cl_node_endpoints.len()),
+            cl_node_endpoints,
+            client: Client::new(),
+            genesis_time: 0,
+            seconds_per_slot: 0,
+        };
 
         let genesis = await_tel!(
             context,
             tracer,
-            "BlobProvider::client_get (genesis)",
+            "BlobProvider::get (genesis)",
             retry_res_timeout!(
                 10,
-                Self::client_get::<Value>(&client, &cl_node_endpoint, "eth/v1/beacon/genesis")
+                provider
+                    .get::<Value>("eth/v1/beacon/genesis")
                     .with_context(context.clone())
                     .await
             )
         );
         debug!("genesis {:?}", &genesis);
-        let genesis_time = genesis["data"]["genesis_time"]
+        provider.genesis_time = genesis["data"]["genesis_time"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("genesis time is None"))?
             .parse::<u64>()?;
         let spec = await_tel!(
             context,
             tracer,
-            "BlobProvider::client_get (spec)",
+            "BlobProvider::get (spec)",
             retry_res_timeout!(
                 10,
-                Self::client_get::<Value>(&client, &cl_node_endpoint, "eth/v1/config/spec")
+                provider
+                    .get::<Value>("eth/v1/config/spec")
                     .with_context(context.clone())
                     .await
             )
         );
         debug!("spec {:?}", &spec);
-        let seconds_per_slot = spec["data"]["SECONDS_PER_SLOT"]
+        provider.seconds_per_slot = spec["data"]["SECONDS_PER_SLOT"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("seconds per slot is None"))?
             .parse::<u64>()?;
-        Ok(Self {
-            cl_node_endpoint,
-            client,
-            genesis_time,
-            seconds_per_slot,
-        })
+        Ok(provider)
     }
 
     pub fn slot(&self, timestamp: u64) -> u64 {
@@ -106,8 +118,27 @@ impl BlobProvider {
             .context("json")
     }
 
+    /// Queries the healthiest endpoints first and fails over to the rest upon errors
     pub async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
-        Self::client_get(&self.client, &self.cl_node_endpoint, path).await
+        let mut last_err = None;
+        for i in self.health.ranking() {
+            let endpoint = &self.cl_node_endpoints[i];
+            match Self::client_get(&self.client, endpoint, path).await {
+                Ok(response) => {
+                    self.health.record(i, true);
+                    return Ok(response);
+                }
+                Err(err) => {
+                    self.health.record(i, false);
+                    warn!(
+                        "Beacon endpoint {i} failed (health {:.2}): {err:?}",
+                        self.health.score(i)
+                    );
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow!("No beacon endpoints available.")))
     }
 
     pub async fn get_blob(&self, timestamp: u64, blob_hash: B256) -> anyhow::Result<BlobData> {
@@ -0,0 +1,60 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Mutex};
+
+/// Weight of the latest request outcome in an endpoint's health score
+pub const HEALTH_SCORE_DECAY: f64 = 0.2;
+
+/// Tracks the reliability of a set of redundant endpoints
+#[derive(Clone, Debug)]
+pub struct EndpointHealth {
+    /// Exponentially weighted success rate of each endpoint
+    scores: Arc<Mutex<Vec<f64>>>,
+}
+
+impl EndpointHealth {
+    pub fn new(endpoint_count: usize) -> Self {
+        Self {
+            scores: Arc::new(Mutex::new(vec![1.0; endpoint_count])),
+        }
+    }
+
+    /// Returns the endpoint indices ordered from most to least healthy
+    pub fn ranking(&self) -> Vec<usize> {
+        let scores = self.scores.lock().unwrap();
+        let mut ranking = (0..scores.len()).collect::<Vec<_>>();
+        ranking.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+        ranking
+    }
+
+    /// Records the outcome of a request made to an endpoint
+    pub fn record(&self, endpoint: usize, success: bool) {
+        let mut scores = self.scores.lock().unwrap();
+        if let Some(score) = scores.get_mut(endpoint) {
+            let outcome = if success { 1.0 } else { 0.0 };
+            *score = (1.0 - HEALTH_SCORE_DECAY) * *score + HEALTH_SCORE_DECAY * outcome;
+        }
+    }
+
+    /// Returns the current health score of an endpoint
+    pub fn score(&self, endpoint: usize) -> f64 {
+        self.scores
+            .lock()
+            .unwrap()
+            .get(endpoint)
+            .copied()
+            .unwrap_or_default()
+    }
+}
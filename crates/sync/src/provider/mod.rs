@@ -13,33 +13,85 @@
 // limitations under the License.
 
 use crate::provider::beacon::BlobProvider;
-use crate::provider::optimism::OpNodeProvider;
-use crate::{await_tel, retry_res_ctx};
+use crate::provider::optimism::{fetch_output_root_proof, OpNodeProvider, OpNodeQuorum};
+use crate::{await_tel, await_tel_res, retry_res_ctx, retry_res_ctx_timeout};
+use alloy::primitives::B256;
 use alloy::providers::RootProvider;
+use alloy::rpc::client::RpcClient;
+use alloy::transports::http::reqwest::Url;
+use alloy::transports::http::{Client, Http};
+use alloy::transports::layers::FallbackLayer;
+use anyhow::{bail, Context};
+use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use std::fmt::{Display, Formatter};
+use std::num::NonZeroUsize;
+use tower::ServiceBuilder;
+use tracing::{error, info};
 
 pub mod beacon;
+pub mod health;
 pub mod optimism;
 
 #[derive(clap::Args, Debug, Clone)]
 pub struct ProviderArgs {
-    /// Address of the OP-NODE endpoint to use
+    /// Comma-separated addresses of the OP-NODE endpoints to use
     #[clap(long, env)]
     pub op_node_url: String,
-    /// Address of the OP-GETH endpoint to use (eth and debug namespace required).
+    /// Comma-separated addresses of the OP-GETH endpoints to use (eth and debug namespace required).
     #[clap(long, env)]
     pub op_geth_url: String,
     /// Number of L2 blocks to delay observation by
     #[clap(long, env, default_value_t = 0)]
     pub op_rpc_delay: u64,
-    /// Address of the ethereum rpc endpoint to use (eth namespace required)
+    /// Number of OP-NODE endpoints that must agree on an output root for it to be accepted
+    #[clap(long, env)]
+    pub op_node_quorum: Option<usize>,
+    /// Whether to recompute each OP-NODE output root from OP-GETH block headers and storage proofs
+    #[clap(long, env, default_value_t = false)]
+    pub verify_output_roots: bool,
+    /// Comma-separated addresses of the ethereum rpc endpoints to use (eth namespace required)
     #[clap(long, env)]
     pub eth_rpc_url: String,
-    /// Address of the L1 Beacon API endpoint to use.
+    /// Comma-separated addresses of the L1 Beacon API endpoints to use.
     #[clap(long, env)]
     pub beacon_rpc_url: String,
 }
 
+/// Splits a comma-separated list of endpoint addresses
+pub fn endpoint_urls(urls: &str) -> Vec<&str> {
+    urls.split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
+/// Returns the first address in a comma-separated list of endpoints
+pub fn primary_url(urls: &str) -> &str {
+    endpoint_urls(urls).first().copied().unwrap_or(urls)
+}
+
+/// Creates an RPC client that scores the health of each endpoint in a comma-separated list and
+/// fails over to the healthiest available one
+pub fn failover_client(urls: &str) -> anyhow::Result<RpcClient> {
+    let transports = endpoint_urls(urls)
+        .into_iter()
+        .map(|url| Ok(Http::<Client>::new(Url::parse(url).context("Url::parse")?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if transports.is_empty() {
+        bail!("No endpoints provided.");
+    }
+    let transport = ServiceBuilder::new()
+        .layer(FallbackLayer::default().with_active_transport_count(NonZeroUsize::MIN))
+        .service(transports);
+    Ok(RpcClient::builder().transport(transport, false))
+}
+
+/// Creates a provider that fails over between the endpoints in a comma-separated list
+pub fn failover_provider(urls: &str) -> anyhow::Result<RootProvider> {
+    Ok(RootProvider::new(failover_client(urls)?))
+}
+
 /// A collection of RPC providers for L1 and L2 data
 pub struct SyncProvider {
     /// DA provider for blobs
@@ -48,8 +100,12 @@ pub struct SyncProvider {
     pub l1_provider: RootProvider,
     /// Provider for op-node queries
     pub op_provider: OpNodeProvider,
+    /// Independent op-node providers that must agree on output roots
+    pub op_quorum: Option<OpNodeQuorum>,
     /// Provider for L2 chain data
     pub l2_provider: RootProvider,
+    /// Whether op-node output roots are recomputed using L2 chain data before use
+    pub verify_output_roots: bool,
 }
 
 impl SyncProvider {
@@ -64,17 +120,111 @@ impl SyncProvider {
             retry_res_ctx!(BlobProvider::new(args.beacon_rpc_url.clone()))
         );
 
-        let l1_provider = RootProvider::new_http(args.eth_rpc_url.as_str().try_into()?);
-        let op_provider = OpNodeProvider(RootProvider::new_http(
-            args.op_node_url.as_str().try_into()?,
-        ));
-        let l2_provider = RootProvider::new_http(args.op_geth_url.as_str().try_into()?);
+        let l1_provider = failover_provider(&args.eth_rpc_url).context("eth_rpc_url")?;
+        let op_provider =
+            OpNodeProvider(failover_provider(&args.op_node_url).context("op_node_url")?);
+        let op_quorum = args
+            .op_node_quorum
+            .map(|threshold| OpNodeQuorum::new(&args.op_node_url, threshold))
+            .transpose()?;
+        let l2_provider = failover_provider(&args.op_geth_url).context("op_geth_url")?;
+        if args.verify_output_roots {
+            info!("Verifying op-node output roots against op-geth state.");
+        }
 
         Ok(Self {
             da_provider,
             l1_provider,
             op_provider,
+            op_quorum,
             l2_provider,
+            verify_output_roots: args.verify_output_roots,
         })
     }
+
+    /// Fetches the output root reported by op-node at the given L2 block, requiring agreement in
+    /// quorum mode
+    pub async fn reported_output_at_block(&self, output_block_number: u64) -> anyhow::Result<B256> {
+        let tracer = tracer("kailua");
+        let context = opentelemetry::Context::current_with_span(
+            tracer.start("SyncProvider::reported_output_at_block"),
+        );
+
+        match &self.op_quorum {
+            Some(op_quorum) => await_tel_res!(
+                context,
+                op_quorum.output_at_block(output_block_number),
+                "OpNodeQuorum::output_at_block"
+            ),
+            None => await_tel_res!(
+                context,
+                self.op_provider.output_at_block(output_block_number),
+                "OpNodeProvider::output_at_block"
+            ),
+        }
+    }
+
+    /// Fetches the output root at the given L2 block, retrying failed queries until they succeed.
+    ///
+    /// In verification mode, a reported output root that does not match the one recomputed from
+    /// the L2 chain state is not retried and fails with [OutputRootMismatch].
+    pub async fn output_at_block(&self, output_block_number: u64) -> anyhow::Result<B256> {
+        let tracer = tracer("kailua");
+        let context = opentelemetry::Context::current_with_span(
+            tracer.start("SyncProvider::output_at_block"),
+        );
+
+        let output_root = await_tel!(
+            context,
+            tracer,
+            "reported_output_at_block",
+            retry_res_ctx_timeout!(self.reported_output_at_block(output_block_number).await)
+        );
+        if !self.verify_output_roots {
+            return Ok(output_root);
+        }
+
+        let local_root = await_tel!(
+            context,
+            tracer,
+            "fetch_output_root_proof",
+            retry_res_ctx_timeout!(
+                fetch_output_root_proof(&self.l2_provider, output_block_number).await
+            )
+        )
+        .output_root();
+        if local_root != output_root {
+            let mismatch = OutputRootMismatch {
+                output_block_number,
+                reported: output_root,
+                local: local_root,
+            };
+            error!("{mismatch}");
+            return Err(mismatch.into());
+        }
+        Ok(output_root)
+    }
+}
+
+/// An op-node output root that does not match the one recomputed from the L2 chain state
+#[derive(Clone, Copy, Debug)]
+pub struct OutputRootMismatch {
+    /// The L2 block number of the output
+    pub output_block_number: u64,
+    /// The output root reported by op-node
+    pub reported: B256,
+    /// The output root recomputed from op-geth block headers and storage proofs
+    pub local: B256,
 }
+
+impl Display for OutputRootMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Op-node output root {} at block {} does not match locally computed output root {}.",
+            self.reported, self.output_block_number, self.local
+        )
+    }
+}
+
+impl std::error::Error for OutputRootMismatch {}
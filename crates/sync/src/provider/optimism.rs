@@ -13,21 +13,138 @@
 // limitations under the License.
 
 use crate::await_tel;
-use alloy::primitives::B256;
+use crate::provider::{endpoint_urls, failover_client, failover_provider};
+use alloy::consensus::{BlockHeader, Sealable};
+use alloy::eips::{BlockId, BlockNumberOrTag};
+use alloy::network::primitives::HeaderResponse;
+use alloy::network::{BlockResponse, Network};
+use alloy::primitives::{address, keccak256, Address, B256};
 use alloy::providers::{Provider, ProviderBuilder, RootProvider};
-use anyhow::Context;
+use alloy_trie::proof::verify_proof;
+use alloy_trie::{Nibbles, TrieAccount};
+use anyhow::{anyhow, bail, Context};
+use futures::future::join_all;
 use kona_genesis::RollupConfig;
 use kona_registry::Registry;
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::IntoFuture;
 use std::str::FromStr;
-use tracing::log::warn;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-#[derive(Clone)]
+/// Address of the L2ToL1MessagePasser predeploy
+pub const L2_TO_L1_MESSAGE_PASSER: Address = address!("0x4200000000000000000000000000000000000016");
+
+#[derive(Clone, Debug)]
 pub struct OpNodeProvider(pub RootProvider);
 
+/// A set of independent op-node endpoints that must agree on output roots
+#[derive(Clone, Debug)]
+pub struct OpNodeQuorum {
+    /// Provider for each op-node endpoint
+    pub providers: Vec<OpNodeProvider>,
+    /// Number of endpoints required to agree on an output root
+    pub threshold: usize,
+}
+
+/// The preimage of a version 0 L2 output root
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputRootProof {
+    /// Output root version
+    pub version: B256,
+    /// L2 block state root
+    pub state_root: B256,
+    /// Storage root of the L2ToL1MessagePasser contract
+    pub message_passer_storage_root: B256,
+    /// L2 block hash
+    pub latest_blockhash: B256,
+}
+
+impl OutputRootProof {
+    /// Computes the output root committed to by this preimage
+    pub fn output_root(&self) -> B256 {
+        keccak256(
+            [
+                self.version.as_slice(),
+                self.state_root.as_slice(),
+                self.message_passer_storage_root.as_slice(),
+                self.latest_blockhash.as_slice(),
+            ]
+            .concat(),
+        )
+    }
+}
+
+/// Fetches the output root preimage of an L2 block from an op-geth endpoint.
+///
+/// The storage root of the message passer is verified against the state root of the block, and
+/// the reported block hash is recomputed from the header, but the block itself is trusted to be
+/// canonical as reported by op-geth.
+pub async fn fetch_output_root_proof<P: Provider<N>, N: Network>(
+    l2_provider: &P,
+    block_number: u64,
+) -> anyhow::Result<OutputRootProof> {
+    let tracer = tracer("kailua");
+    let context =
+        opentelemetry::Context::current_with_span(tracer.start("fetch_output_root_proof"));
+
+    let block = await_tel!(
+        context,
+        tracer,
+        "Provider::get_block_by_number",
+        l2_provider
+            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+            .into_future()
+    )
+    .context("get_block_by_number")?
+    .ok_or_else(|| anyhow!("L2 block {block_number} not found"))?;
+    // Check that the reported block hash commits to the returned header
+    let block_hash = AsRef::<N::Header>::as_ref(block.header()).hash_slow();
+    if block_hash != block.header().hash() {
+        bail!(
+            "L2 block {block_number} header hashes to {block_hash} instead of reported {}",
+            block.header().hash()
+        );
+    }
+    let account_proof = await_tel!(
+        context,
+        tracer,
+        "Provider::get_proof",
+        l2_provider
+            .get_proof(L2_TO_L1_MESSAGE_PASSER, vec![])
+            .block_id(BlockId::Number(BlockNumberOrTag::Number(block_number)))
+            .into_future()
+    )
+    .context("get_proof")?;
+    // Check that the message passer storage root is committed to by the block's state root
+    let state_root = block.header().state_root();
+    let account = TrieAccount {
+        nonce: account_proof.nonce,
+        balance: account_proof.balance,
+        storage_root: account_proof.storage_hash,
+        code_hash: account_proof.code_hash,
+    };
+    verify_proof(
+        state_root,
+        Nibbles::unpack(keccak256(L2_TO_L1_MESSAGE_PASSER)),
+        Some(alloy_rlp::encode(account)),
+        &account_proof.account_proof,
+    )
+    .map_err(|e| {
+        anyhow!("Invalid L2ToL1MessagePasser account proof at block {block_number}: {e}")
+    })?;
+
+    Ok(OutputRootProof {
+        version: B256::ZERO,
+        state_root,
+        message_passer_storage_root: account_proof.storage_hash,
+        latest_blockhash: block_hash,
+    })
+}
+
 impl OpNodeProvider {
     pub async fn output_at_block(&self, output_block_number: u64) -> anyhow::Result<B256> {
         let tracer = tracer("kailua");
@@ -79,6 +196,74 @@ impl OpNodeProvider {
     }
 }
 
+impl OpNodeQuorum {
+    pub fn new(op_node_urls: &str, threshold: usize) -> anyhow::Result<Self> {
+        let providers = endpoint_urls(op_node_urls)
+            .into_iter()
+            .map(|url| Ok(OpNodeProvider(RootProvider::new_http(url.try_into()?))))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if threshold == 0 || threshold > providers.len() {
+            bail!(
+                "Quorum of {threshold} op-nodes cannot be reached with {} endpoints.",
+                providers.len()
+            );
+        }
+        info!(
+            "Requiring {threshold} of {} op-nodes to agree on output roots.",
+            providers.len()
+        );
+        Ok(Self {
+            providers,
+            threshold,
+        })
+    }
+
+    /// Fetches the output root at the given block that at least `threshold` op-nodes agree on
+    pub async fn output_at_block(&self, output_block_number: u64) -> anyhow::Result<B256> {
+        let tracer = tracer("kailua");
+        let context = opentelemetry::Context::current_with_span(
+            tracer.start("OpNodeQuorum::output_at_block"),
+        );
+
+        let responses = await_tel!(
+            context,
+            join_all(
+                self.providers
+                    .iter()
+                    .map(|provider| provider.output_at_block(output_block_number))
+            )
+        );
+        let mut votes = HashMap::<B256, usize>::new();
+        for (i, response) in responses.into_iter().enumerate() {
+            match response {
+                Ok(output_root) => *votes.entry(output_root).or_default() += 1,
+                Err(err) => {
+                    warn!("Op-node {i} failed to report output {output_block_number}: {err:?}")
+                }
+            }
+        }
+        if votes.len() > 1 {
+            warn!("Op-nodes disagree on output {output_block_number}: {votes:?}");
+        }
+        quorum_output_root(&votes, self.threshold)
+            .with_context(|| format!("Output {output_block_number} votes: {votes:?}"))
+    }
+}
+
+/// Returns the single output root reported by at least `threshold` op-nodes, failing if no root
+/// or more than one root reaches the threshold
+pub fn quorum_output_root(votes: &HashMap<B256, usize>, threshold: usize) -> anyhow::Result<B256> {
+    let mut elected = votes
+        .iter()
+        .filter(|(_, count)| **count >= threshold)
+        .map(|(output_root, _)| *output_root);
+    match (elected.next(), elected.next()) {
+        (Some(output_root), None) => Ok(output_root),
+        (Some(_), Some(_)) => bail!("Conflicting output roots reached a quorum of {threshold}"),
+        (None, _) => bail!("No quorum of {threshold} op-nodes"),
+    }
+}
+
 pub async fn fetch_rollup_config(
     op_node_address: &str,
     l2_node_address: &str,
@@ -105,8 +290,8 @@ pub async fn fetch_rollup_config(
         return Ok(chain_config);
     }
 
-    let op_node_provider = OpNodeProvider(RootProvider::new_http(op_node_address.try_into()?));
-    let l2_node_provider = ProviderBuilder::new().connect_http(l2_node_address.try_into()?);
+    let op_node_provider = OpNodeProvider(failover_provider(op_node_address)?);
+    let l2_node_provider = ProviderBuilder::new().connect_client(failover_client(l2_node_address)?);
 
     let mut rollup_config: Value = op_node_provider
         .rollup_config()
@@ -170,3 +355,47 @@ pub fn load_registry_config(chain_id: u64) -> Option<RollupConfig> {
     let registry = Registry::from_chain_list();
     registry.rollup_configs.get(&chain_id).cloned()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tally(output_roots: &[u8]) -> HashMap<B256, usize> {
+        let mut votes = HashMap::<B256, usize>::new();
+        for output_root in output_roots {
+            *votes.entry(B256::repeat_byte(*output_root)).or_default() += 1;
+        }
+        votes
+    }
+
+    #[test]
+    fn test_quorum_output_root() {
+        // unanimous
+        assert_eq!(
+            quorum_output_root(&tally(&[1, 1, 1]), 3).unwrap(),
+            B256::repeat_byte(1)
+        );
+        // majority
+        assert_eq!(
+            quorum_output_root(&tally(&[1, 2, 1]), 2).unwrap(),
+            B256::repeat_byte(1)
+        );
+        // below threshold
+        assert!(quorum_output_root(&tally(&[1, 2, 3]), 2).is_err());
+        assert!(quorum_output_root(&tally(&[1, 1]), 3).is_err());
+        // no responses
+        assert!(quorum_output_root(&tally(&[]), 1).is_err());
+    }
+
+    #[test]
+    fn test_quorum_output_root_split_vote() {
+        // two roots reach a threshold at most half of the endpoints
+        assert!(quorum_output_root(&tally(&[1, 1, 2, 2]), 2).is_err());
+        assert!(quorum_output_root(&tally(&[1, 2]), 1).is_err());
+        // a single root reaching the threshold is still accepted alongside minority roots
+        assert_eq!(
+            quorum_output_root(&tally(&[1, 1, 2, 3]), 2).unwrap(),
+            B256::repeat_byte(1)
+        );
+    }
+}
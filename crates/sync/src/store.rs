@@ -0,0 +1,437 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::cursor::SyncCursor;
+use crate::proposal::Proposal;
+use alloy::primitives::{Address, B256};
+use anyhow::{bail, ensure, Context};
+use rocksdb::{ColumnFamily, ColumnFamilyDescriptor, IteratorMode, WriteBatch};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::warn;
+
+/// Version of the persisted agent state layout
+pub const SYNC_STORE_VERSION: u32 = 1;
+
+/// Column family of store metadata and the sync cursor
+const CF_META: &str = "meta";
+/// Column family of (factory index -> proposal) entries
+const CF_PROPOSALS: &str = "proposals";
+/// Column family of (proposer -> elimination round) entries
+const CF_ELIMINATIONS: &str = "eliminations";
+/// Column family of (l1 block number -> (proposal, l1 head)) entries
+const CF_L1_HEADS: &str = "l1_heads";
+/// Column families holding agent state that is discarded together
+const STATE_COLUMN_FAMILIES: [&str; 3] = [CF_PROPOSALS, CF_ELIMINATIONS, CF_L1_HEADS];
+
+const VERSION_KEY: &[u8] = b"version";
+const CURSOR_KEY: &[u8] = b"cursor";
+
+/// The agent state restored from storage
+pub struct SyncState {
+    /// Pointers to the latest synchronized items
+    pub cursor: SyncCursor,
+    /// On-chain proposal data
+    pub proposals: BTreeMap<u64, Proposal>,
+    /// Proposer elimination rounds
+    pub eliminations: BTreeMap<Address, u64>,
+    /// Available l1-heads for derivation
+    pub l1_heads: BTreeMap<u64, (Address, B256)>,
+}
+
+impl SyncState {
+    /// Checks that the restored state is internally consistent and belongs to the treasury
+    pub fn validate(&self, treasury: Address) -> anyhow::Result<()> {
+        for (index, proposal) in &self.proposals {
+            ensure!(
+                proposal.index == *index,
+                "Proposal {} stored under index {index}.",
+                proposal.index
+            );
+            ensure!(
+                proposal.treasury == treasury,
+                "Proposal {index} belongs to treasury {}.",
+                proposal.treasury
+            );
+            ensure!(
+                *index >= self.cursor.last_resolved_game && *index < self.cursor.next_factory_index,
+                "Proposal {index} lies outside of the synchronized range."
+            );
+        }
+        let Some(last_resolved) = self.proposals.get(&self.cursor.last_resolved_game) else {
+            bail!(
+                "Last resolved proposal {} is missing.",
+                self.cursor.last_resolved_game
+            );
+        };
+        ensure!(
+            last_resolved.resolved_at != 0,
+            "Last resolved proposal {} is unresolved.",
+            last_resolved.index
+        );
+        ensure!(
+            self.l1_heads
+                .values()
+                .any(|(_, l1_head)| l1_head == &last_resolved.l1_head),
+            "L1 head of last resolved proposal {} is missing.",
+            last_resolved.index
+        );
+        ensure!(
+            self.proposals
+                .contains_key(&self.cursor.canonical_proposal_tip),
+            "Canonical proposal tip {} is missing.",
+            self.cursor.canonical_proposal_tip
+        );
+        Ok(())
+    }
+}
+
+/// Local persistent storage of output commitments and agent state
+pub struct SyncStore {
+    /// Local persistent key-value store
+    pub db: rocksdb::DB,
+}
+
+impl SyncStore {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let mut options = rocksdb::Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        let column_families = [CF_META]
+            .into_iter()
+            .chain(STATE_COLUMN_FAMILIES)
+            .map(|name| ColumnFamilyDescriptor::new(name, rocksdb::Options::default()));
+        let db = rocksdb::DB::open_cf_descriptors(&options, path, column_families)
+            .context("rocksdb::DB::open_cf_descriptors")?;
+        let store = Self { db };
+        store.check_version()?;
+        Ok(store)
+    }
+
+    fn cf(&self, name: &str) -> anyhow::Result<&ColumnFamily> {
+        self.db
+            .cf_handle(name)
+            .with_context(|| format!("Column family {name} missing."))
+    }
+
+    /// Discards persisted agent state written using a different layout version
+    fn check_version(&self) -> anyhow::Result<()> {
+        let meta = self.cf(CF_META)?;
+        let version = match self
+            .db
+            .get_cf(meta, VERSION_KEY)
+            .context("rocksdb::DB::get_cf")?
+        {
+            Some(value) => Some(u32::from_be_bytes(value.as_slice().try_into()?)),
+            None => None,
+        };
+        if version == Some(SYNC_STORE_VERSION) {
+            return Ok(());
+        }
+        if let Some(version) = version {
+            warn!("Discarding persisted agent state of unsupported version {version}.");
+        }
+        self.clear_state()?;
+        self.db
+            .put_cf(meta, VERSION_KEY, SYNC_STORE_VERSION.to_be_bytes())
+            .context("rocksdb::DB::put_cf")
+    }
+
+    /// Returns the persisted output commitment at the given block height
+    pub fn get_output(&self, block_number: u64) -> anyhow::Result<Option<B256>> {
+        let Some(value) = self
+            .db
+            .get(block_number.to_be_bytes())
+            .context("rocksdb::DB::get")?
+        else {
+            return Ok(None);
+        };
+        ensure!(
+            value.len() == 32,
+            "Malformed output commitment {block_number} in storage."
+        );
+        Ok(Some(B256::from_slice(&value)))
+    }
+
+    /// Persists the output commitment at the given block height
+    pub fn put_output(&self, block_number: u64, output: B256) -> anyhow::Result<()> {
+        self.db
+            .put(block_number.to_be_bytes(), output.0)
+            .context("rocksdb::DB::put")
+    }
+
+    /// Deletes the output commitment at the given block height
+    pub fn delete_output(&self, block_number: u64) -> anyhow::Result<()> {
+        self.db
+            .delete(block_number.to_be_bytes())
+            .context("rocksdb::DB::delete")
+    }
+
+    /// Deletes all persisted agent state except for output commitments
+    pub fn clear_state(&self) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        for name in STATE_COLUMN_FAMILIES {
+            let cf = self.cf(name)?;
+            for entry in self.db.iterator_cf(cf, IteratorMode::Start) {
+                let (key, _) = entry.context("rocksdb::DB::iterator_cf")?;
+                batch.delete_cf(cf, key);
+            }
+        }
+        batch.delete_cf(self.cf(CF_META)?, CURSOR_KEY);
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+
+    /// Loads the persisted agent state, if any
+    pub fn load_state(&self) -> anyhow::Result<Option<SyncState>> {
+        let Some(cursor) = self
+            .db
+            .get_cf(self.cf(CF_META)?, CURSOR_KEY)
+            .context("rocksdb::DB::get_cf")?
+        else {
+            return Ok(None);
+        };
+        let cursor: SyncCursor =
+            serde_json::from_slice(&cursor).context("Malformed cursor in storage.")?;
+
+        let mut proposals = BTreeMap::new();
+        for entry in self
+            .db
+            .iterator_cf(self.cf(CF_PROPOSALS)?, IteratorMode::Start)
+        {
+            let (key, value) = entry.context("rocksdb::DB::iterator_cf")?;
+            let index = u64::from_be_bytes(
+                key.as_ref()
+                    .try_into()
+                    .context("Malformed proposal key in storage.")?,
+            );
+            let proposal: Proposal = serde_json::from_slice(&value)
+                .with_context(|| format!("Malformed proposal {index} in storage."))?;
+            proposals.insert(index, proposal);
+        }
+
+        let mut eliminations = BTreeMap::new();
+        for entry in self
+            .db
+            .iterator_cf(self.cf(CF_ELIMINATIONS)?, IteratorMode::Start)
+        {
+            let (key, value) = entry.context("rocksdb::DB::iterator_cf")?;
+            if key.len() != 20 || value.len() != 8 {
+                bail!("Malformed elimination entry in storage.");
+            }
+            eliminations.insert(
+                Address::from_slice(&key),
+                u64::from_be_bytes(value.as_ref().try_into()?),
+            );
+        }
+
+        let mut l1_heads = BTreeMap::new();
+        for entry in self
+            .db
+            .iterator_cf(self.cf(CF_L1_HEADS)?, IteratorMode::Start)
+        {
+            let (key, value) = entry.context("rocksdb::DB::iterator_cf")?;
+            if key.len() != 8 || value.len() != 52 {
+                bail!("Malformed l1 head entry in storage.");
+            }
+            l1_heads.insert(
+                u64::from_be_bytes(key.as_ref().try_into()?),
+                (
+                    Address::from_slice(&value[..20]),
+                    B256::from_slice(&value[20..]),
+                ),
+            );
+        }
+
+        Ok(Some(SyncState {
+            cursor,
+            proposals,
+            eliminations,
+            l1_heads,
+        }))
+    }
+
+    /// Atomically persists the updated agent state
+    pub fn put_state<'a>(
+        &self,
+        cursor: &SyncCursor,
+        updated_proposals: impl IntoIterator<Item = &'a Proposal>,
+        pruned_proposals: impl IntoIterator<Item = u64>,
+        eliminations: &BTreeMap<Address, u64>,
+        l1_heads: &BTreeMap<u64, (Address, B256)>,
+    ) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        // cursor
+        batch.put_cf(
+            self.cf(CF_META)?,
+            CURSOR_KEY,
+            serde_json::to_vec(cursor).context("serde_json::to_vec")?,
+        );
+        // proposals
+        let cf_proposals = self.cf(CF_PROPOSALS)?;
+        for proposal in updated_proposals {
+            batch.put_cf(
+                cf_proposals,
+                proposal.index.to_be_bytes(),
+                serde_json::to_vec(proposal).context("serde_json::to_vec")?,
+            );
+        }
+        for index in pruned_proposals {
+            batch.delete_cf(cf_proposals, index.to_be_bytes());
+        }
+        // eliminations
+        let cf_eliminations = self.cf(CF_ELIMINATIONS)?;
+        for (proposer, round) in eliminations {
+            batch.put_cf(cf_eliminations, proposer.as_slice(), round.to_be_bytes());
+        }
+        // l1 heads
+        let cf_l1_heads = self.cf(CF_L1_HEADS)?;
+        if let Some(earliest) = l1_heads.first_key_value().map(|(k, _)| *k) {
+            batch.delete_range_cf(cf_l1_heads, 0u64.to_be_bytes(), earliest.to_be_bytes());
+        }
+        for (block_number, (proposal, l1_head)) in l1_heads {
+            batch.put_cf(
+                cf_l1_heads,
+                block_number.to_be_bytes(),
+                [proposal.as_slice(), l1_head.as_slice()].concat(),
+            );
+        }
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use tempfile::tempdir;
+
+    fn proposal(index: u64, treasury: Address) -> Proposal {
+        Proposal {
+            treasury,
+            correct_claim: Some(true),
+            correct_parent: Some(true),
+            canonical: Some(true),
+            resolved_at: index + 1,
+            ..Proposal::mock(index, index.saturating_sub(1))
+        }
+    }
+
+    fn cursor() -> SyncCursor {
+        SyncCursor {
+            canonical_proposal_tip: 2,
+            next_factory_index: 3,
+            delayed_factory_indices: VecDeque::new(),
+            last_output_index: 20,
+            last_resolved_game: 1,
+        }
+    }
+
+    fn state(treasury: Address) -> SyncState {
+        let proposals = BTreeMap::from([(1, proposal(1, treasury)), (2, proposal(2, treasury))]);
+        let l1_heads = proposals
+            .values()
+            .map(|p| (p.index * 100, (p.contract, p.l1_head)))
+            .collect();
+        SyncState {
+            cursor: cursor(),
+            proposals,
+            eliminations: BTreeMap::from([(Address::repeat_byte(0xBB), 4)]),
+            l1_heads,
+        }
+    }
+
+    fn put(store: &SyncStore, state: &SyncState, pruned: &[u64]) {
+        store
+            .put_state(
+                &state.cursor,
+                state.proposals.values(),
+                pruned.iter().copied(),
+                &state.eliminations,
+                &state.l1_heads,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let dir = tempdir().unwrap();
+        let treasury = Address::repeat_byte(0x01);
+        let expected = state(treasury);
+        {
+            let store = SyncStore::open(dir.path()).unwrap();
+            assert!(store.load_state().unwrap().is_none());
+            store.put_output(20, B256::repeat_byte(0x20)).unwrap();
+            put(&store, &expected, &[]);
+        }
+
+        // Reopening the store restores everything that was written
+        let store = SyncStore::open(dir.path()).unwrap();
+        assert_eq!(store.get_output(20).unwrap(), Some(B256::repeat_byte(0x20)));
+        let loaded = store.load_state().unwrap().unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded.cursor).unwrap(),
+            serde_json::to_value(&expected.cursor).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&loaded.proposals).unwrap(),
+            serde_json::to_value(&expected.proposals).unwrap()
+        );
+        assert_eq!(loaded.eliminations, expected.eliminations);
+        assert_eq!(loaded.l1_heads, expected.l1_heads);
+        loaded.validate(treasury).unwrap();
+
+        // Pruned proposals and superseded l1 heads are removed
+        let mut pruned = expected;
+        pruned.proposals.remove(&1);
+        pruned.l1_heads.remove(&100);
+        put(&store, &pruned, &[1]);
+        let loaded = store.load_state().unwrap().unwrap();
+        assert_eq!(loaded.proposals.keys().collect::<Vec<_>>(), vec![&2]);
+        assert_eq!(loaded.l1_heads.keys().collect::<Vec<_>>(), vec![&200]);
+
+        // Clearing the state keeps output commitments
+        store.clear_state().unwrap();
+        assert!(store.load_state().unwrap().is_none());
+        assert!(store.get_output(20).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_state_validate() {
+        let treasury = Address::repeat_byte(0x01);
+        state(treasury).validate(treasury).unwrap();
+        // foreign treasury
+        assert!(state(treasury).validate(Address::ZERO).is_err());
+        // misplaced proposal
+        let mut misplaced = state(treasury);
+        let moved = misplaced.proposals.remove(&2).unwrap();
+        misplaced.proposals.insert(0, moved);
+        assert!(misplaced.validate(treasury).is_err());
+        // proposal beyond the cursor
+        let mut ahead = state(treasury);
+        ahead.proposals.insert(3, proposal(3, treasury));
+        assert!(ahead.validate(treasury).is_err());
+        // unresolved last resolved proposal
+        let mut unresolved = state(treasury);
+        unresolved.proposals.get_mut(&1).unwrap().resolved_at = 0;
+        assert!(unresolved.validate(treasury).is_err());
+        // missing l1 head of last resolved proposal
+        let mut headless = state(treasury);
+        headless.l1_heads.remove(&100);
+        assert!(headless.validate(treasury).is_err());
+        // missing canonical tip
+        let mut tipless = state(treasury);
+        tipless.cursor.canonical_proposal_tip = 5;
+        assert!(tipless.validate(treasury).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::consensus::transaction::SignerRecoverable;
+use alloy::consensus::{BlobTransactionSidecar, Transaction, TxEip4844Variant, TxEnvelope};
+use alloy::contract::SolCallBuilder;
+use alloy::eips::eip2718::Decodable2718;
+use alloy::network::{Network, TransactionBuilder, TransactionBuilder4844};
+use alloy::primitives::{Address, Bytes, TxKind, U256};
+use alloy::providers::Provider;
+use alloy::sol_types::SolCall;
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// A fully encoded contract call awaiting signature by an offline wallet
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionBundle {
+    /// Name of the contract function being called
+    pub description: String,
+    /// Chain the transaction must be signed for
+    pub chain_id: u64,
+    /// Account expected to sign the transaction
+    pub from: Address,
+    /// Target contract
+    pub to: Address,
+    /// Amount of wei to send along with the call
+    pub value: U256,
+    /// ABI-encoded calldata
+    pub calldata: Bytes,
+    /// Gas estimate at the time of export, if the call could be simulated
+    pub gas_limit: Option<u64>,
+    /// Blob sidecar to attach to the transaction, if any
+    pub sidecar: Option<BlobTransactionSidecar>,
+    /// The EIP-2718 encoded signed transaction (in network encoding for blob transactions)
+    #[serde(default)]
+    pub signed_transaction: Option<Bytes>,
+}
+
+impl TransactionBundle {
+    /// Encodes the call into a bundle to be signed by the given account
+    pub async fn from_call<P: Provider<N>, C: SolCall, N: Network>(
+        call: SolCallBuilder<P, C, N>,
+        description: &str,
+        chain_id: u64,
+        from: Address,
+    ) -> anyhow::Result<Self>
+    where
+        N::TransactionRequest: TransactionBuilder4844,
+    {
+        let call = call.from(from);
+        let gas_limit = match call.estimate_gas().await {
+            Ok(gas_limit) => Some(gas_limit),
+            Err(err) => {
+                warn!("Failed to estimate gas for {description}: {err:?}");
+                None
+            }
+        };
+        let request = call.into_transaction_request();
+        let Some(TxKind::Call(to)) = request.kind() else {
+            bail!("Bundled transaction for {description} has no target contract.");
+        };
+        Ok(Self {
+            description: description.to_string(),
+            chain_id,
+            from,
+            to,
+            value: request.value().unwrap_or_default(),
+            calldata: request.input().cloned().unwrap_or_default(),
+            gas_limit,
+            sidecar: request.blob_sidecar().cloned(),
+            signed_transaction: None,
+        })
+    }
+
+    /// Writes the bundle as JSON under the given directory and returns the path of the file
+    pub async fn save(&self, bundle_dir: &Path, name: &str) -> anyhow::Result<PathBuf> {
+        let json = serde_json::to_string_pretty(self).context("serde_json::to_string_pretty")?;
+        let file_name = bundle_dir.join(format!("{name}.json"));
+        tokio::fs::write(&file_name, json)
+            .await
+            .with_context(|| format!("Failed to write {}", file_name.display()))?;
+        Ok(file_name)
+    }
+
+    /// Reads a bundle previously written by [TransactionBundle::save]
+    pub async fn load(file_name: &Path) -> anyhow::Result<Self> {
+        let json = tokio::fs::read(file_name)
+            .await
+            .with_context(|| format!("Failed to read {}", file_name.display()))?;
+        serde_json::from_slice(&json).context("serde_json::from_slice")
+    }
+
+    /// Decodes the signed transaction and checks that it carries out exactly the bundled call
+    pub fn verify_signed(&self) -> anyhow::Result<TxEnvelope> {
+        let Some(signed_transaction) = &self.signed_transaction else {
+            bail!("Bundle does not contain a signed transaction.");
+        };
+        let envelope = TxEnvelope::decode_2718(&mut signed_transaction.as_ref())
+            .context("TxEnvelope::decode_2718")?;
+        if envelope.chain_id() != Some(self.chain_id) {
+            bail!(
+                "Signed transaction chain id {:?} does not match bundle chain id {}.",
+                envelope.chain_id(),
+                self.chain_id
+            );
+        }
+        if envelope.to() != Some(self.to) {
+            bail!(
+                "Signed transaction target {:?} does not match bundle target {}.",
+                envelope.to(),
+                self.to
+            );
+        }
+        if envelope.value() != self.value {
+            bail!(
+                "Signed transaction value {} does not match bundle value {}.",
+                envelope.value(),
+                self.value
+            );
+        }
+        if envelope.input() != &self.calldata {
+            bail!("Signed transaction calldata does not match bundle calldata.");
+        }
+        let signer = envelope
+            .recover_signer()
+            .context("TxEnvelope::recover_signer")?;
+        if signer != self.from {
+            bail!(
+                "Transaction signed by {signer} instead of bundle sender {}.",
+                self.from
+            );
+        }
+        if self.sidecar.is_some()
+            && !envelope
+                .as_eip4844()
+                .is_some_and(|txn| matches!(txn.tx(), TxEip4844Variant::TxEip4844WithSidecar(_)))
+        {
+            bail!("Signed transaction is missing the bundled blob sidecar.");
+        }
+        Ok(envelope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::consensus::{SignableTransaction, TxEip1559};
+    use alloy::eips::eip2718::Encodable2718;
+    use alloy::network::TxSignerSync;
+    use alloy::signers::local::PrivateKeySigner;
+
+    fn bundle(from: Address) -> TransactionBundle {
+        TransactionBundle {
+            description: String::from("resolve"),
+            chain_id: 1,
+            from,
+            to: Address::repeat_byte(0xaa),
+            value: U256::from(7),
+            calldata: Bytes::from(vec![1, 2, 3, 4]),
+            gas_limit: Some(100_000),
+            sidecar: None,
+            signed_transaction: None,
+        }
+    }
+
+    fn sign(signer: &PrivateKeySigner, bundle: &TransactionBundle) -> Bytes {
+        let mut txn = TxEip1559 {
+            chain_id: bundle.chain_id,
+            nonce: 0,
+            gas_limit: 100_000,
+            max_fee_per_gas: 2,
+            max_priority_fee_per_gas: 1,
+            to: TxKind::Call(bundle.to),
+            value: bundle.value,
+            access_list: Default::default(),
+            input: bundle.calldata.clone(),
+        };
+        let signature = signer.sign_transaction_sync(&mut txn).unwrap();
+        TxEnvelope::Eip1559(txn.into_signed(signature))
+            .encoded_2718()
+            .into()
+    }
+
+    #[test]
+    fn test_verify_signed() {
+        let signer = PrivateKeySigner::random();
+        let mut bundle = bundle(signer.address());
+        // unsigned bundles are rejected
+        assert!(bundle.verify_signed().is_err());
+        // matching signature is accepted
+        bundle.signed_transaction = Some(sign(&signer, &bundle));
+        let envelope = bundle.verify_signed().unwrap();
+        assert_eq!(envelope.to(), Some(bundle.to));
+        // a missing sidecar is rejected
+        let mut with_sidecar = bundle.clone();
+        with_sidecar.sidecar = Some(BlobTransactionSidecar::default());
+        assert!(with_sidecar.verify_signed().is_err());
+        // garbage is rejected
+        let mut garbage = bundle.clone();
+        garbage.signed_transaction = Some(Bytes::from(vec![0xff; 8]));
+        assert!(garbage.verify_signed().is_err());
+    }
+
+    #[test]
+    fn test_verify_signed_mismatch() {
+        let signer = PrivateKeySigner::random();
+        let expected = bundle(signer.address());
+        let signed_transaction = Some(sign(&signer, &expected));
+        let mismatches = [
+            TransactionBundle {
+                chain_id: 2,
+                ..expected.clone()
+            },
+            TransactionBundle {
+                to: Address::repeat_byte(0xbb),
+                ..expected.clone()
+            },
+            TransactionBundle {
+                value: U256::ZERO,
+                ..expected.clone()
+            },
+            TransactionBundle {
+                calldata: Bytes::from(vec![4, 3, 2, 1]),
+                ..expected.clone()
+            },
+            TransactionBundle {
+                from: PrivateKeySigner::random().address(),
+                ..expected.clone()
+            },
+        ];
+        for mut bundle in mismatches {
+            bundle.signed_transaction = signed_transaction.clone();
+            assert!(bundle.verify_signed().is_err());
+        }
+    }
+}
@@ -12,25 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod bundle;
 pub mod fillers;
 pub mod provider;
 pub mod rpc;
 pub mod safe;
 pub mod signer;
 
+use alloy::consensus::Transaction;
 use alloy::contract::{CallBuilder, CallDecoder, EthCall};
-use alloy::network::{Network, TransactionBuilder4844};
+use alloy::network::{Network, TransactionBuilder4844, TransactionResponse};
+use alloy::primitives::{Address, TxHash};
 use alloy::providers::fillers::JoinFill;
-use alloy::providers::Provider;
 use alloy::providers::{Identity, ProviderBuilder};
-use anyhow::Context;
+use alloy::providers::{
+    PendingTransactionBuilder, PendingTransactionError, Provider, RootProvider, WatchTxError,
+};
+use alloy::transports::RpcError;
+use anyhow::{bail, Context};
 use async_trait::async_trait;
 use fillers::{PremiumBlobGasFiller, PremiumExecGasFiller, PremiumFiller};
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
 use std::future::IntoFuture;
 use std::time::Duration;
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(clap::Args, Debug, Clone)]
 pub struct TransactArgs {
@@ -43,6 +49,18 @@ pub struct TransactArgs {
     /// Blob Gas Fee Premium
     #[clap(long, env, required = false, default_value_t = 25)]
     pub blob_gas_premium: u128,
+    /// Maximum number of times a pending transaction is re-broadcast with increased fees
+    #[clap(long, env, required = false, default_value_t = 3)]
+    pub txn_max_fee_bumps: u64,
+    /// Fee increase percentage of each re-broadcast (blob transactions are bumped by at least 100)
+    #[clap(long, env, required = false, default_value_t = 25)]
+    pub txn_fee_bump: u128,
+    /// Maximum fee per gas (wei) that a re-broadcast may offer
+    #[clap(long, env, required = false)]
+    pub txn_max_fee_per_gas: Option<u128>,
+    /// Maximum fee per blob gas (wei) that a re-broadcast may offer
+    #[clap(long, env, required = false)]
+    pub txn_max_fee_per_blob_gas: Option<u128>,
 }
 
 impl TransactArgs {
@@ -80,6 +98,111 @@ pub trait Transact<N: Network> {
     ) -> anyhow::Result<N::ReceiptResponse> {
         self.timed_transact_with_context(context, span, None).await
     }
+
+    /// Publishes the transaction and re-broadcasts it using the same nonce with increased fees
+    /// whenever it is not mined within the configured timeout
+    async fn replaceable_transact(
+        &self,
+        span: &'static str,
+        txn_args: &TransactArgs,
+    ) -> anyhow::Result<N::ReceiptResponse>
+    where
+        N::TransactionRequest: TransactionBuilder4844;
+
+    async fn replaceable_transact_with_context(
+        &self,
+        context: opentelemetry::Context,
+        span: &'static str,
+        txn_args: &TransactArgs,
+    ) -> anyhow::Result<N::ReceiptResponse>
+    where
+        N::TransactionRequest: TransactionBuilder4844,
+    {
+        self.replaceable_transact(span, txn_args)
+            .with_context(context)
+            .await
+    }
+}
+
+/// The parameters shared by all broadcasts of a replaceable transaction
+#[derive(Clone, Copy, Debug)]
+struct BroadcastFees {
+    nonce: u64,
+    gas_limit: u64,
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+    max_fee_per_blob_gas: Option<u128>,
+}
+
+impl BroadcastFees {
+    fn from_transaction<T: Transaction>(transaction: &T) -> Self {
+        Self {
+            nonce: transaction.nonce(),
+            gas_limit: transaction.gas_limit(),
+            max_fee_per_gas: transaction.max_fee_per_gas(),
+            max_priority_fee_per_gas: transaction
+                .max_priority_fee_per_gas()
+                .unwrap_or(transaction.max_fee_per_gas()),
+            max_fee_per_blob_gas: transaction.max_fee_per_blob_gas(),
+        }
+    }
+
+    /// Increases all fees by the given percentage, doubling them at least for blob transactions,
+    /// without exceeding the configured ceilings.
+    ///
+    /// Returns None if the ceilings leave no room for any fee increase.
+    fn bump(&self, percentage: u128, txn_args: &TransactArgs) -> Option<Self> {
+        let percentage = if self.max_fee_per_blob_gas.is_some() {
+            percentage.max(100)
+        } else {
+            percentage
+        };
+        let bump = |fee: u128, ceiling: Option<u128>| {
+            let bumped = fee + (fee * percentage / 100).max(1);
+            ceiling.map_or(bumped, |ceiling| bumped.min(ceiling).max(fee))
+        };
+        let max_fee_per_gas = bump(self.max_fee_per_gas, txn_args.txn_max_fee_per_gas);
+        let bumped = Self {
+            max_fee_per_gas,
+            max_priority_fee_per_gas: bump(self.max_priority_fee_per_gas, Some(max_fee_per_gas)),
+            max_fee_per_blob_gas: self
+                .max_fee_per_blob_gas
+                .map(|fee| bump(fee, txn_args.txn_max_fee_per_blob_gas)),
+            ..*self
+        };
+        (bumped.max_fee_per_gas > self.max_fee_per_gas
+            || bumped.max_fee_per_blob_gas > self.max_fee_per_blob_gas)
+            .then_some(bumped)
+    }
+}
+
+/// JSON-RPC error codes with which execution clients reject transactions from their pools
+const TXPOOL_REJECTION_CODES: [i64; 2] = [-32000, -32003];
+
+/// Returns true if the node rejected a transaction for offering insufficient fees
+fn is_underpriced(err: &alloy::contract::Error) -> bool {
+    let alloy::contract::Error::TransportError(RpcError::ErrorResp(payload)) = err else {
+        return false;
+    };
+    TXPOOL_REJECTION_CODES.contains(&payload.code) && payload.message.contains("underpriced")
+}
+
+/// Returns the receipt of whichever broadcast was mined, if any
+async fn find_mined_broadcast<N: Network>(
+    provider: &RootProvider<N>,
+    broadcasts: &[TxHash],
+) -> anyhow::Result<Option<N::ReceiptResponse>> {
+    for tx_hash in broadcasts {
+        if let Some(receipt) = provider
+            .get_transaction_receipt(*tx_hash)
+            .await
+            .context("get_transaction_receipt")?
+        {
+            info!("Transaction mined: {tx_hash:?}");
+            return Ok(Some(receipt));
+        }
+    }
+    Ok(None)
 }
 
 #[async_trait]
@@ -113,6 +236,134 @@ where
             .await
             .context("get_receipt")
     }
+
+    async fn replaceable_transact(
+        &self,
+        span: &'static str,
+        txn_args: &TransactArgs,
+    ) -> anyhow::Result<N::ReceiptResponse>
+    where
+        N::TransactionRequest: TransactionBuilder4844,
+    {
+        let tracer = tracer("kailua");
+        let context = opentelemetry::Context::current_with_span(tracer.start(span));
+        let timeout = Duration::from_secs(txn_args.txn_timeout);
+
+        // Publish transaction
+        let pending_txn = self
+            .send()
+            .with_context(context.with_span(tracer.start_with_context("send", &context)))
+            .await
+            .context("send")?;
+        info!("Transaction published: {:?}", pending_txn.tx_hash());
+        let provider = pending_txn.provider().clone();
+        let mut broadcasts = vec![*pending_txn.tx_hash()];
+        let mut last_broadcast: Option<(Address, BroadcastFees)> = None;
+        let mut fee_bumps = 0;
+
+        loop {
+            // Wait for receipt of the latest broadcast with timeout
+            let latest_broadcast = *broadcasts.last().unwrap();
+            match PendingTransactionBuilder::new(provider.clone(), latest_broadcast)
+                .with_timeout(Some(timeout))
+                .get_receipt()
+                .with_context(context.with_span(tracer.start_with_context("get_receipt", &context)))
+                .await
+            {
+                Ok(receipt) => return Ok(receipt),
+                Err(PendingTransactionError::TxWatcher(WatchTxError::Timeout)) => {}
+                Err(err) => return Err(err).context("get_receipt"),
+            }
+
+            // Check if an earlier broadcast was mined instead
+            if let Some(receipt) = find_mined_broadcast(&provider, &broadcasts)
+                .with_context(
+                    context.with_span(tracer.start_with_context("find_mined_broadcast", &context)),
+                )
+                .await?
+            {
+                return Ok(receipt);
+            }
+            if fee_bumps >= txn_args.txn_max_fee_bumps {
+                bail!(
+                    "Transaction not mined after {fee_bumps} fee bumps: {:?}",
+                    broadcasts
+                );
+            }
+            fee_bumps += 1;
+
+            // Load the parameters of the original broadcast
+            let (sender, current_fees) = match last_broadcast {
+                Some(last_broadcast) => last_broadcast,
+                None => match provider
+                    .get_transaction_by_hash(latest_broadcast)
+                    .await
+                    .context("get_transaction_by_hash")?
+                {
+                    Some(transaction) => (
+                        transaction.from(),
+                        BroadcastFees::from_transaction(&transaction),
+                    ),
+                    None => {
+                        warn!("Pending transaction {latest_broadcast:?} not found.");
+                        continue;
+                    }
+                },
+            };
+            // Do not replace a transaction whose nonce was already consumed
+            let sender_nonce = provider
+                .get_transaction_count(sender)
+                .await
+                .context("get_transaction_count")?;
+            if sender_nonce > current_fees.nonce {
+                warn!(
+                    "Nonce {} of pending transaction already used.",
+                    current_fees.nonce
+                );
+                continue;
+            }
+
+            // Re-broadcast with the same nonce and increased fees
+            let Some(bumped_fees) = current_fees.bump(txn_args.txn_fee_bump, txn_args) else {
+                bail!(
+                    "Transaction fees reached the configured ceilings after {} fee bumps: {:?}",
+                    fee_bumps - 1,
+                    broadcasts
+                );
+            };
+            info!(
+                "Replacing transaction with nonce {} using max fee per gas {} (bump {fee_bumps}/{}).",
+                bumped_fees.nonce, bumped_fees.max_fee_per_gas, txn_args.txn_max_fee_bumps
+            );
+            let mut replacement = self
+                .clone()
+                .nonce(bumped_fees.nonce)
+                .gas(bumped_fees.gas_limit)
+                .max_fee_per_gas(bumped_fees.max_fee_per_gas)
+                .max_priority_fee_per_gas(bumped_fees.max_priority_fee_per_gas);
+            if let Some(max_fee_per_blob_gas) = bumped_fees.max_fee_per_blob_gas {
+                replacement = replacement.max_fee_per_blob_gas(max_fee_per_blob_gas);
+            }
+            match replacement
+                .send()
+                .with_context(context.with_span(tracer.start_with_context("send", &context)))
+                .await
+            {
+                Ok(pending_txn) => {
+                    info!("Transaction re-published: {:?}", pending_txn.tx_hash());
+                    broadcasts.push(*pending_txn.tx_hash());
+                    last_broadcast = Some((sender, bumped_fees));
+                }
+                Err(err) => {
+                    // Keep bumping from the attempted fees if the replacement was underpriced
+                    warn!("Failed to replace transaction: {err:?}");
+                    if is_underpriced(&err) {
+                        last_broadcast = Some((sender, bumped_fees));
+                    }
+                }
+            }
+        }
+    }
 }
 
 pub fn premium_provider<N: Network>(
@@ -130,3 +381,111 @@ where
         ),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::json_rpc::ErrorPayload;
+
+    fn txn_args(
+        txn_max_fee_per_gas: Option<u128>,
+        txn_max_fee_per_blob_gas: Option<u128>,
+    ) -> TransactArgs {
+        TransactArgs {
+            txn_timeout: 12,
+            exec_gas_premium: 0,
+            blob_gas_premium: 0,
+            txn_max_fee_bumps: 3,
+            txn_fee_bump: 25,
+            txn_max_fee_per_gas,
+            txn_max_fee_per_blob_gas,
+        }
+    }
+
+    fn fees(max_fee_per_blob_gas: Option<u128>) -> BroadcastFees {
+        BroadcastFees {
+            nonce: 7,
+            gas_limit: 21_000,
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 10,
+            max_fee_per_blob_gas,
+        }
+    }
+
+    #[test]
+    fn test_bump_fees() {
+        let args = txn_args(None, None);
+        // regular transactions are bumped by the configured percentage
+        let bumped = fees(None).bump(25, &args).unwrap();
+        assert_eq!(bumped.nonce, 7);
+        assert_eq!(bumped.gas_limit, 21_000);
+        assert_eq!(bumped.max_fee_per_gas, 125);
+        assert_eq!(bumped.max_priority_fee_per_gas, 12);
+        assert_eq!(bumped.max_fee_per_blob_gas, None);
+        // fees always increase
+        let bumped = fees(None).bump(0, &args).unwrap();
+        assert_eq!(bumped.max_fee_per_gas, 101);
+        assert_eq!(bumped.max_priority_fee_per_gas, 11);
+        // blob transactions are at least doubled
+        let bumped = fees(Some(50)).bump(25, &args).unwrap();
+        assert_eq!(bumped.max_fee_per_gas, 200);
+        assert_eq!(bumped.max_priority_fee_per_gas, 20);
+        assert_eq!(bumped.max_fee_per_blob_gas, Some(100));
+    }
+
+    #[test]
+    fn test_bump_fees_ceiling() {
+        // fees are capped at the ceilings
+        let bumped = fees(Some(50))
+            .bump(25, &txn_args(Some(150), Some(80)))
+            .unwrap();
+        assert_eq!(bumped.max_fee_per_gas, 150);
+        assert_eq!(bumped.max_priority_fee_per_gas, 20);
+        assert_eq!(bumped.max_fee_per_blob_gas, Some(80));
+        // the priority fee never exceeds the max fee
+        let mut generous = fees(None);
+        generous.max_priority_fee_per_gas = 100;
+        let bumped = generous.bump(25, &txn_args(Some(110), None)).unwrap();
+        assert_eq!(bumped.max_fee_per_gas, 110);
+        assert_eq!(bumped.max_priority_fee_per_gas, 110);
+        // fees already above the ceiling are not lowered
+        assert!(fees(None).bump(25, &txn_args(Some(50), None)).is_none());
+        // no bump is possible once all ceilings are reached
+        assert!(fees(None).bump(25, &txn_args(Some(100), None)).is_none());
+        assert!(fees(Some(50))
+            .bump(25, &txn_args(Some(100), Some(50)))
+            .is_none());
+        // a blob fee bump suffices
+        let bumped = fees(Some(50)).bump(25, &txn_args(Some(100), None)).unwrap();
+        assert_eq!(bumped.max_fee_per_gas, 100);
+        assert_eq!(bumped.max_fee_per_blob_gas, Some(100));
+    }
+
+    fn rpc_error(code: i64, message: &'static str) -> alloy::contract::Error {
+        alloy::contract::Error::TransportError(RpcError::ErrorResp(ErrorPayload {
+            code,
+            message: message.into(),
+            data: None,
+        }))
+    }
+
+    #[test]
+    fn test_is_underpriced() {
+        assert!(is_underpriced(&rpc_error(
+            -32000,
+            "replacement transaction underpriced"
+        )));
+        assert!(is_underpriced(&rpc_error(
+            -32003,
+            "transaction underpriced"
+        )));
+        assert!(!is_underpriced(&rpc_error(-32000, "nonce too low")));
+        assert!(!is_underpriced(&rpc_error(
+            3,
+            "execution reverted: underpriced"
+        )));
+        assert!(!is_underpriced(&alloy::contract::Error::UnknownFunction(
+            "underpriced".to_string()
+        )));
+    }
+}
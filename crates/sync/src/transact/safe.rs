@@ -12,40 +12,449 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::stall::Stall;
+use crate::transact::signer::{args_to_signer, KailuaSigner};
 use crate::transact::Transact;
 use alloy::contract::SolCallBuilder;
 use alloy::network::{Network, TransactionBuilder};
-use alloy::primitives::{Address, Uint, U256};
+use alloy::primitives::{Address, Bytes, ChainId, Signature, Uint, B256, U256};
 use alloy::providers::Provider;
-use anyhow::Context;
+use alloy::sol_types::{eip712_domain, SolStruct};
+use anyhow::{anyhow, bail, Context};
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{TraceContextExt, Tracer};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+alloy::sol! {
+    /// The EIP-712 struct signed by Safe owners to approve a transaction
+    struct SafeTx {
+        address to;
+        uint256 value;
+        bytes data;
+        uint8 operation;
+        uint256 safeTxGas;
+        uint256 baseGas;
+        uint256 gasPrice;
+        address gasToken;
+        address refundReceiver;
+        uint256 nonce;
+    }
+}
+
+/// Additional Safe owners whose signatures are collected to meet the Safe's threshold
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct SafeCosignerArgs {
+    /// Comma-separated private keys of additional Safe owners
+    #[clap(long, env, value_delimiter = ',')]
+    pub safe_cosigner_keys: Vec<String>,
+    /// Comma-separated AWS KMS Key IDs of additional Safe owners
+    #[clap(long, env, value_delimiter = ',')]
+    pub safe_cosigner_aws_key_ids: Vec<String>,
+    /// Comma-separated GCP KMS keys of additional Safe owners (`project/location/keyring/key`)
+    #[clap(long, env, value_delimiter = ',')]
+    pub safe_cosigner_google_keys: Vec<String>,
+    /// Comma-separated paths of files holding hex-encoded owner signatures of Safe transactions
+    #[clap(long, env, value_delimiter = ',')]
+    pub safe_signature_files: Vec<PathBuf>,
+    /// Directory to write Safe transactions to for signing whenever the threshold is not met
+    #[clap(long, env)]
+    pub safe_signature_request_dir: Option<PathBuf>,
+}
+
+impl SafeCosignerArgs {
+    pub async fn cosigners(&self, chain_id: Option<ChainId>) -> anyhow::Result<SafeCosigners> {
+        let mut signers = vec![];
+        for key in &self.safe_cosigner_keys {
+            signers.push(
+                args_to_signer(
+                    &Some(key.clone()),
+                    &None,
+                    &None,
+                    &None,
+                    &None,
+                    &None,
+                    chain_id,
+                )
+                .await?,
+            );
+        }
+        for key_id in &self.safe_cosigner_aws_key_ids {
+            signers.push(
+                args_to_signer(
+                    &None,
+                    &Some(key_id.clone()),
+                    &None,
+                    &None,
+                    &None,
+                    &None,
+                    chain_id,
+                )
+                .await?,
+            );
+        }
+        for key in &self.safe_cosigner_google_keys {
+            let [project_id, location, keyring, key_name] = key
+                .split('/')
+                .map(|part| Some(part.to_string()))
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| anyhow!("Invalid GCP KMS key {key}"))?;
+            signers.push(
+                args_to_signer(
+                    &None,
+                    &None,
+                    &project_id,
+                    &location,
+                    &keyring,
+                    &key_name,
+                    chain_id,
+                )
+                .await?,
+            );
+        }
+        Ok(SafeCosigners {
+            signers,
+            signature_files: self.safe_signature_files.clone(),
+            request_dir: self.safe_signature_request_dir.clone(),
+        })
+    }
+}
+
+/// The sources of owner signatures besides the account executing the Safe transaction
+#[derive(Default)]
+pub struct SafeCosigners {
+    /// Keys of owners that sign on demand
+    pub signers: Vec<KailuaSigner>,
+    /// Files holding signatures produced offline
+    pub signature_files: Vec<PathBuf>,
+    /// Directory to write transactions lacking signatures to
+    pub request_dir: Option<PathBuf>,
+}
+
+/// A Safe transaction awaiting more owner signatures
+#[derive(Clone, Debug, Serialize)]
+pub struct SafeSignatureRequest {
+    pub safe: Address,
+    pub chain_id: u64,
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub operation: u8,
+    pub safe_tx_gas: U256,
+    pub nonce: U256,
+    /// The EIP-712 hash each owner must sign
+    pub safe_tx_hash: B256,
+    pub threshold: usize,
+    pub signed_by: Vec<Address>,
+}
+
+/// Computes the EIP-712 hash of a Safe transaction that owners sign to approve it
+pub fn safe_tx_hash(safe_txn: &SafeTx, chain_id: u64, safe: Address) -> B256 {
+    let domain = eip712_domain! {
+        chain_id: chain_id,
+        verifying_contract: safe,
+    };
+    safe_txn.eip712_signing_hash(&domain)
+}
+
+/// Returns the signature of an owner that approves a transaction by executing it
+pub fn pre_validated_signature(owner: Address) -> Vec<u8> {
+    [
+        [0u8; 12].as_slice(),
+        owner.as_slice(),
+        [0u8; 32].as_slice(),
+        [1u8].as_slice(),
+    ]
+    .concat()
+}
+
+/// Concatenates the first threshold signatures in ascending order of owner address, as
+/// required by `Safe::checkSignatures`
+pub fn pack_signatures(signatures: BTreeMap<Address, Vec<u8>>, threshold: usize) -> Vec<u8> {
+    signatures.into_values().take(threshold).flatten().collect()
+}
 
 pub async fn exec_safe_txn<P1: Provider<N>, P2: Provider<N>, C, N: Network>(
     txn: SolCallBuilder<P1, C, N>,
     safe: &kailua_contracts::Safe::SafeInstance<P2, N>,
     from: Address,
+    cosigners: &SafeCosigners,
 ) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("exec_safe_txn"));
+
+    // Compute the EIP-712 hash of the safe transaction
     let req = txn.into_transaction_request();
+    let safe_txn = SafeTx {
+        to: req.to().unwrap(),
+        value: req.value().unwrap_or_default(),
+        data: req.input().cloned().unwrap_or_default(),
+        operation: 0,
+        safeTxGas: Uint::from(req.gas_limit().unwrap_or_default()),
+        // Gas refunds are only needed when relaying transactions on behalf of the owners
+        baseGas: U256::ZERO,
+        gasPrice: U256::ZERO,
+        gasToken: Address::ZERO,
+        refundReceiver: Address::ZERO,
+        nonce: safe
+            .nonce()
+            .stall_with_context(context.clone(), "Safe::nonce")
+            .await,
+    };
+    let chain_id = safe
+        .provider()
+        .get_chain_id()
+        .await
+        .context("get_chain_id")?;
+    let safe_txn_hash = safe_tx_hash(&safe_txn, chain_id, *safe.address());
+    let contract_txn_hash = safe
+        .getTransactionHash(
+            safe_txn.to,
+            safe_txn.value,
+            safe_txn.data.clone(),
+            safe_txn.operation,
+            safe_txn.safeTxGas,
+            safe_txn.baseGas,
+            safe_txn.gasPrice,
+            safe_txn.gasToken,
+            safe_txn.refundReceiver,
+            safe_txn.nonce,
+        )
+        .stall_with_context(context.clone(), "Safe::getTransactionHash")
+        .await;
+    if safe_txn_hash != contract_txn_hash {
+        bail!("Computed Safe transaction hash {safe_txn_hash} does not match {contract_txn_hash}.");
+    }
+
+    // Collect owner signatures sorted by owner address
+    let owners = safe
+        .getOwners()
+        .stall_with_context(context.clone(), "Safe::getOwners")
+        .await;
+    let threshold = safe
+        .getThreshold()
+        .stall_with_context(context.clone(), "Safe::getThreshold")
+        .await
+        .saturating_to::<usize>();
+    let mut signatures = BTreeMap::new();
+    if owners.contains(&from) {
+        // The executing owner approves the transaction by sending it
+        signatures.insert(from, pre_validated_signature(from));
+    }
+    for signer in &cosigners.signers {
+        let owner = signer.address();
+        if !owners.contains(&owner) {
+            warn!(
+                "Ignoring signer {owner} who is not an owner of Safe {}.",
+                safe.address()
+            );
+            continue;
+        } else if signatures.contains_key(&owner) {
+            continue;
+        }
+        let signature = signer.sign_hash(&safe_txn_hash).await?;
+        signatures.insert(owner, signature.as_bytes().to_vec());
+    }
+    for signature_file in &cosigners.signature_files {
+        let contents = tokio::fs::read_to_string(signature_file)
+            .await
+            .with_context(|| format!("Failed to read {}", signature_file.display()))?;
+        let signature = match Bytes::from_str(contents.trim())
+            .context("Bytes::from_str")
+            .and_then(|bytes| Signature::from_raw(&bytes).context("Signature::from_raw"))
+        {
+            Ok(signature) => signature,
+            Err(err) => {
+                warn!(
+                    "Ignoring invalid signature in {}: {err:?}",
+                    signature_file.display()
+                );
+                continue;
+            }
+        };
+        // Signatures of other transactions recover to unrelated addresses
+        match signature.recover_address_from_prehash(&safe_txn_hash) {
+            Ok(owner) if owners.contains(&owner) => {
+                signatures
+                    .entry(owner)
+                    .or_insert_with(|| signature.as_bytes().to_vec());
+            }
+            Ok(signer) => {
+                warn!(
+                    "Ignoring signature in {} by {signer} who is not an owner of Safe {} or did not sign {safe_txn_hash}.",
+                    signature_file.display(),
+                    safe.address()
+                );
+            }
+            Err(err) => {
+                warn!(
+                    "Ignoring unrecoverable signature in {}: {err:?}",
+                    signature_file.display()
+                );
+            }
+        }
+    }
+    info!(
+        "Collected {}/{threshold} owner signatures for Safe transaction {safe_txn_hash}.",
+        signatures.len()
+    );
+
+    // Request the missing signatures
+    if signatures.len() < threshold {
+        if let Some(request_dir) = &cosigners.request_dir {
+            let request = SafeSignatureRequest {
+                safe: *safe.address(),
+                chain_id,
+                to: safe_txn.to,
+                value: safe_txn.value,
+                data: safe_txn.data.clone(),
+                operation: safe_txn.operation,
+                safe_tx_gas: safe_txn.safeTxGas,
+                nonce: safe_txn.nonce,
+                safe_tx_hash: safe_txn_hash,
+                threshold,
+                signed_by: signatures.keys().copied().collect(),
+            };
+            let file_name =
+                request_dir.join(format!("safe-{}-{}.json", safe.address(), safe_txn.nonce));
+            tokio::fs::create_dir_all(request_dir)
+                .await
+                .context("Failed to create signature request directory")?;
+            tokio::fs::write(
+                &file_name,
+                serde_json::to_string_pretty(&request).context("serde_json::to_string_pretty")?,
+            )
+            .await
+            .with_context(|| format!("Failed to write {}", file_name.display()))?;
+            info!("Safe signature request written to {}.", file_name.display());
+        }
+        bail!(
+            "Safe transaction {safe_txn_hash} has {} of {threshold} required owner signatures.",
+            signatures.len()
+        );
+    }
+    let packed_signatures = pack_signatures(signatures, threshold);
+
     safe.execTransaction(
-        req.to().unwrap(),
-        req.value().unwrap_or_default(),
-        req.input().cloned().unwrap_or_default(),
-        0,
-        Uint::from(req.gas_limit().unwrap_or_default()),
-        U256::ZERO,
-        U256::ZERO,
-        Address::ZERO,
-        Address::ZERO,
-        [
-            [0u8; 12].as_slice(),
-            from.as_slice(),
-            [0u8; 32].as_slice(),
-            [1u8].as_slice(),
-        ]
-        .concat()
-        .into(),
+        safe_txn.to,
+        safe_txn.value,
+        safe_txn.data,
+        safe_txn.operation,
+        safe_txn.safeTxGas,
+        safe_txn.baseGas,
+        safe_txn.gasPrice,
+        safe_txn.gasToken,
+        safe_txn.refundReceiver,
+        packed_signatures.into(),
     )
     .transact("Safe::execTransaction", None)
     .await
     .context("Safe::execTransaction")?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{b256, keccak256};
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::signers::SignerSync;
+    use alloy::sol_types::SolValue;
+
+    /// `SAFE_TX_TYPEHASH` as defined by the Safe contracts
+    const SAFE_TX_TYPEHASH: B256 =
+        b256!("bb8310d486368db6bd6f849402fdd73ad53d316b5a4b2644ad6efe0f941286d8");
+    /// `DOMAIN_SEPARATOR_TYPEHASH` as defined by the Safe contracts
+    const DOMAIN_SEPARATOR_TYPEHASH: B256 =
+        b256!("47e79534a245952e8b16893a336b85a3d9ea9fa8c573f3d803afb92a79469218");
+
+    fn safe_txn() -> SafeTx {
+        SafeTx {
+            to: Address::repeat_byte(0x11),
+            value: U256::from(5),
+            data: Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+            operation: 0,
+            safeTxGas: U256::from(21_000),
+            baseGas: U256::ZERO,
+            gasPrice: U256::ZERO,
+            gasToken: Address::ZERO,
+            refundReceiver: Address::ZERO,
+            nonce: U256::from(3),
+        }
+    }
+
+    #[test]
+    fn test_safe_tx_hash() {
+        let safe_txn = safe_txn();
+        let safe = Address::repeat_byte(0x22);
+        // Reproduce Safe::getTransactionHash
+        let struct_hash = keccak256(
+            (
+                SAFE_TX_TYPEHASH,
+                safe_txn.to,
+                safe_txn.value,
+                keccak256(&safe_txn.data),
+                safe_txn.operation,
+                safe_txn.safeTxGas,
+                safe_txn.baseGas,
+                safe_txn.gasPrice,
+                safe_txn.gasToken,
+                safe_txn.refundReceiver,
+                safe_txn.nonce,
+            )
+                .abi_encode(),
+        );
+        let domain_separator =
+            keccak256((DOMAIN_SEPARATOR_TYPEHASH, U256::from(1), safe).abi_encode());
+        let expected = keccak256(
+            [
+                [0x19u8, 0x01].as_slice(),
+                domain_separator.as_slice(),
+                struct_hash.as_slice(),
+            ]
+            .concat(),
+        );
+        assert_eq!(safe_tx_hash(&safe_txn, 1, safe), expected);
+        // The hash is bound to the chain and the Safe
+        assert_ne!(safe_tx_hash(&safe_txn, 2, safe), expected);
+        assert_ne!(safe_tx_hash(&safe_txn, 1, Address::ZERO), expected);
+    }
+
+    #[test]
+    fn test_pack_signatures() {
+        let safe_txn_hash = safe_tx_hash(&safe_txn(), 1, Address::repeat_byte(0x22));
+        let executor = Address::repeat_byte(0x33);
+        let mut signatures = BTreeMap::new();
+        signatures.insert(executor, pre_validated_signature(executor));
+        for _ in 0..3 {
+            let signer = PrivateKeySigner::random();
+            let signature = signer.sign_hash_sync(&safe_txn_hash).unwrap();
+            signatures.insert(signer.address(), signature.as_bytes().to_vec());
+        }
+        let owners = signatures.keys().copied().collect::<Vec<_>>();
+        let packed = pack_signatures(signatures, 3);
+        assert_eq!(packed.len(), 3 * 65);
+        for (owner, signature) in owners.iter().zip(packed.chunks(65)) {
+            if *owner == executor {
+                // approved by execution: r = owner, s = 0, v = 1
+                assert_eq!(&signature[12..32], executor.as_slice());
+                assert_eq!(&signature[32..64], [0u8; 32].as_slice());
+                assert_eq!(signature[64], 1);
+            } else {
+                // ECDSA signatures over the hash itself: v = 27 or 28
+                assert!(matches!(signature[64], 27 | 28));
+                let signature = Signature::from_raw(signature).unwrap();
+                assert_eq!(
+                    signature
+                        .recover_address_from_prehash(&safe_txn_hash)
+                        .unwrap(),
+                    *owner
+                );
+            }
+        }
+    }
+}
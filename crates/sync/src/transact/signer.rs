@@ -13,13 +13,14 @@
 // limitations under the License.
 
 use alloy::network::EthereumWallet;
-use alloy::primitives::ChainId;
+use alloy::primitives::{Address, ChainId, Signature, B256};
 use alloy::signers::{
     aws::AwsSigner,
     gcp::{GcpKeyRingRef, GcpSigner, KeySpecifier},
-    local::LocalSigner,
+    local::{LocalSigner, PrivateKeySigner},
+    Signer,
 };
-use anyhow::Context;
+use anyhow::{bail, Context};
 use aws_config::BehaviorVersion;
 use gcloud_sdk::{
     google::cloud::kms::v1::key_management_service_client::KeyManagementServiceClient, GoogleApi,
@@ -28,21 +29,21 @@ use std::str::FromStr;
 
 #[macro_export]
 macro_rules! define_signer_args {
-    ($vis: vis $struct_name: ident, $prefix: ident) => {
+    ($vis: vis $struct_name: ident, $prefix: ident $(, $optional_unless: literal)*) => {
         paste::paste! {
             #[derive(clap::Args, Debug, Clone, Default)]
             $vis struct $struct_name {
                 /// Wallet private key
-                #[clap(long, env, required_unless_present_any = [stringify!([<$prefix aws_key_id>]), stringify!([<$prefix google_keyring>])])]
+                #[clap(long, env, required_unless_present_any = [stringify!([<$prefix aws_key_id>]), stringify!([<$prefix google_keyring>]) $(, $optional_unless)*])]
                 pub [<$prefix key>]: Option<String>,
 
                 /// AWS KMS Key ID
-                #[clap(long, env, required_unless_present_any = [stringify!([<$prefix key>]), stringify!([<$prefix google_keyring>])])]
+                #[clap(long, env, required_unless_present_any = [stringify!([<$prefix key>]), stringify!([<$prefix google_keyring>]) $(, $optional_unless)*])]
                 pub [<$prefix aws_key_id>]: Option<String>,
 
                 /// GCP KMS Project ID
                 #[clap(long, env, requires = stringify!([<$prefix google_location>]))]
-                #[clap(required_unless_present_any = [stringify!([<$prefix key>]), stringify!([<$prefix aws_key_id>])])]
+                #[clap(required_unless_present_any = [stringify!([<$prefix key>]), stringify!([<$prefix aws_key_id>]) $(, $optional_unless)*])]
                 pub [<$prefix google_project_id>]: Option<String>,
                 /// GCP KMS Location
                 #[clap(long, env, requires = stringify!([<$prefix google_keyring>]))]
@@ -56,6 +57,18 @@ macro_rules! define_signer_args {
             }
 
             impl $struct_name {
+                pub async fn signer(&self, chain_id: Option<ChainId>) -> anyhow::Result<KailuaSigner> {
+                    args_to_signer(
+                        &self.[<$prefix key>],
+                        &self.[<$prefix aws_key_id>],
+                        &self.[<$prefix google_project_id>],
+                        &self.[<$prefix google_location>],
+                        &self.[<$prefix google_keyring>],
+                        &self.[<$prefix google_key_name>],
+                        chain_id
+                    ).await
+                }
+
                 pub async fn wallet(&self, chain_id: Option<ChainId>) -> anyhow::Result<EthereumWallet> {
                     args_to_wallet(
                         &self.[<$prefix key>],
@@ -85,7 +98,47 @@ define_signer_args!(pub DeployerSignerArgs, deployer_);
 define_signer_args!(pub OwnerSignerArgs, owner_);
 define_signer_args!(pub GuardianSignerArgs, guardian_);
 define_signer_args!(pub ProposerSignerArgs, proposer_);
-define_signer_args!(pub ValidatorSignerArgs, validator_);
+// The validator's wallet is optional when running in watch-only mode or when exported proof
+// bundles are signed offline
+define_signer_args!(pub ValidatorSignerArgs, validator_, "watch_only", "bundle_sender");
+define_signer_args!(pub ClaimantSignerArgs, claimant_);
+
+/// A local or KMS-backed key
+pub enum KailuaSigner {
+    Local(PrivateKeySigner),
+    Aws(AwsSigner),
+    Gcp(GcpSigner),
+}
+
+impl KailuaSigner {
+    pub fn address(&self) -> Address {
+        match self {
+            KailuaSigner::Local(signer) => signer.address(),
+            KailuaSigner::Aws(signer) => signer.address(),
+            KailuaSigner::Gcp(signer) => signer.address(),
+        }
+    }
+
+    /// Signs the hash without any prefix
+    pub async fn sign_hash(&self, hash: &B256) -> anyhow::Result<Signature> {
+        let signature = match self {
+            KailuaSigner::Local(signer) => signer.sign_hash(hash).await,
+            KailuaSigner::Aws(signer) => signer.sign_hash(hash).await,
+            KailuaSigner::Gcp(signer) => signer.sign_hash(hash).await,
+        };
+        signature.context("Signer::sign_hash")
+    }
+}
+
+impl From<KailuaSigner> for EthereumWallet {
+    fn from(signer: KailuaSigner) -> Self {
+        match signer {
+            KailuaSigner::Local(signer) => EthereumWallet::from(signer),
+            KailuaSigner::Aws(signer) => EthereumWallet::from(signer),
+            KailuaSigner::Gcp(signer) => EthereumWallet::from(signer),
+        }
+    }
+}
 
 pub async fn args_to_wallet(
     key: &Option<String>,
@@ -96,21 +149,48 @@ pub async fn args_to_wallet(
     google_key_name: &Option<String>,
     chain_id: Option<ChainId>,
 ) -> anyhow::Result<EthereumWallet> {
+    let signer = args_to_signer(
+        key,
+        aws_key_id,
+        google_project_id,
+        google_location,
+        google_keyring,
+        google_key_name,
+        chain_id,
+    )
+    .await?;
+    Ok(EthereumWallet::from(signer))
+}
+
+pub async fn args_to_signer(
+    key: &Option<String>,
+    aws_key_id: &Option<String>,
+    google_project_id: &Option<String>,
+    google_location: &Option<String>,
+    google_keyring: &Option<String>,
+    google_key_name: &Option<String>,
+    chain_id: Option<ChainId>,
+) -> anyhow::Result<KailuaSigner> {
     if let Some(key) = key {
         let local_signer = LocalSigner::from_str(key)?;
-        return Ok(EthereumWallet::from(local_signer));
+        return Ok(KailuaSigner::Local(local_signer));
     } else if let Some(key_id) = aws_key_id {
         let config = aws_config::load_defaults(BehaviorVersion::latest()).await;
         let client = aws_sdk_kms::Client::new(&config);
         let signer = AwsSigner::new(client, key_id.clone(), chain_id)
             .await
             .context("AwsSigner::new")?;
-        return Ok(EthereumWallet::from(signer));
+        return Ok(KailuaSigner::Aws(signer));
     }
 
-    let project_id = google_project_id.clone().unwrap();
-    let location = google_location.clone().unwrap();
-    let keyring_name = google_keyring.clone().unwrap();
+    let (Some(project_id), Some(location), Some(keyring_name), Some(key_name)) = (
+        google_project_id.clone(),
+        google_location.clone(),
+        google_keyring.clone(),
+        google_key_name.clone(),
+    ) else {
+        bail!("No signing key, AWS KMS key or complete GCP KMS key configuration was provided.");
+    };
     let keyring = GcpKeyRingRef::new(&project_id, &location, &keyring_name);
     let client = GoogleApi::from_function(
         KeyManagementServiceClient::new,
@@ -120,11 +200,10 @@ pub async fn args_to_wallet(
     .await
     .context("Failed to create GCP KMS Client")?;
 
-    let key_name = google_key_name.clone().unwrap();
     let key_specifier = KeySpecifier::new(keyring, &key_name, 1);
     let signer = GcpSigner::new(client, key_specifier, chain_id)
         .await
         .context("GcpSigner::new")?;
 
-    Ok(EthereumWallet::from(signer))
+    Ok(KailuaSigner::Gcp(signer))
 }
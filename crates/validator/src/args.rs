@@ -12,9 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::coordinator::CoordinatorArgs;
+use crate::proposals::policy::ProvingPolicy;
+use crate::proposals::watchdog::WatchdogArgs;
+use alloy::primitives::Address;
 use kailua_prover::args::ProvingArgs;
 use kailua_prover::risczero::boundless::BoundlessArgs;
-use kailua_sync::args::SyncArgs;
+use kailua_sync::args::{parse_address, SyncArgs};
+use kailua_sync::bonds::BondManagerArgs;
 use kailua_sync::transact::signer::ValidatorSignerArgs;
 use kailua_sync::transact::TransactArgs;
 use std::path::PathBuf;
@@ -37,6 +42,9 @@ pub struct ValidateArgs {
     /// How many proofs to compute simultaneously
     #[clap(long, env, default_value_t = 1)]
     pub num_concurrent_provers: u64,
+    /// Remote proving worker pool configuration
+    #[clap(flatten)]
+    pub coordinator: CoordinatorArgs,
     /// Optionally enables the use of `debug_executePayload` to collect the execution witness from
     /// the execution layer.
     #[arg(long, env, default_value_t = false)]
@@ -47,6 +55,12 @@ pub struct ValidateArgs {
     /// The maximum amount of seconds to wait before starting to compute a validity proof. (Default 0)
     #[clap(long, env, default_value_t = 0)]
     pub max_validity_proving_delay: u64,
+    /// The order in which to dispatch queued proof requests to the provers. (Default random)
+    #[clap(long, env, value_enum, default_value_t = ProvingPolicy::Random)]
+    pub proving_policy: ProvingPolicy,
+    /// Proof deadline monitoring and escalation configuration
+    #[clap(flatten)]
+    pub watchdog: WatchdogArgs,
 
     /// The number of l1 heads to jump back when initially proving
     #[cfg(feature = "devnet")]
@@ -56,9 +70,26 @@ pub struct ValidateArgs {
     /// Secret key of L1 wallet to use for challenging and proving outputs
     #[clap(flatten)]
     pub validator_signer: ValidatorSignerArgs,
+    /// Only assess proposal correctness and report faults, without a wallet or proving
+    #[clap(long, env, default_value_t = false)]
+    pub watch_only: bool,
+    /// Directory to write JSON fault reports to in watch-only mode
+    #[clap(long, env, requires = "watch_only")]
+    pub fault_report_dir: Option<PathBuf>,
+    /// Directory to write proof submission transactions to for offline signing, instead of
+    /// publishing them
+    #[clap(long, env)]
+    pub export_bundle_dir: Option<PathBuf>,
+    /// Address of the offline wallet signing the exported bundles, in place of the validator wallet
+    #[clap(long, env, value_parser = parse_address, requires = "export_bundle_dir")]
+    #[clap(conflicts_with = "claim_bonds")]
+    pub bundle_sender: Option<Address>,
     /// Transaction publication configuration
     #[clap(flatten)]
     pub txn_args: TransactArgs,
+    /// Treasury bond management configuration
+    #[clap(flatten)]
+    pub bonds: BondManagerArgs,
 
     #[clap(flatten)]
     pub proving: ProvingArgs,
@@ -15,6 +15,7 @@
 use alloy::primitives::FixedBytes;
 use kailua_kona::precondition::PreconditionValidationData;
 use risc0_zkvm::Receipt;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
 /// A channel for two-way communication
@@ -43,7 +44,7 @@ impl<T> DuplexChannel<T> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum Message {
     // The proposal and its parent
@@ -57,4 +58,6 @@ pub enum Message {
         claimed_l2_output_root: FixedBytes<32>,
     },
     Proof(u64, Option<Receipt>),
+    // The proposal whose proof is at risk of missing its deadline
+    Escalate(u64),
 }
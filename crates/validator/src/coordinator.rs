@@ -0,0 +1,495 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::channel::Message;
+use crate::tasks::{create_proving_args, Task, TaskOutcome};
+use anyhow::{anyhow, Context};
+use jsonrpsee::core::{async_trait, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::server::ServerConfig;
+use jsonrpsee::types::error::INTERNAL_ERROR_CODE;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use kailua_prover::args::ProveArgs;
+use kailua_prover::channel::AsyncChannel;
+use kailua_prover::risczero::boundless::BoundlessArgs;
+use kailua_prover::store::ProofStoreArgs;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::spawn;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::{info, trace, warn};
+
+/// Error code returned to workers that fail to authenticate
+pub const UNAUTHORIZED_CODE: i32 = -32001;
+/// Maximum size in bytes of a proving queue request or response
+pub const MAX_MESSAGE_SIZE: u32 = 256 * 1024 * 1024;
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct CoordinatorArgs {
+    /// Socket on which to serve proving tasks to remote `kailua-cli prove-worker` processes
+    /// instead of proving locally
+    #[clap(long, env)]
+    pub coordinator_socket_addr: Option<SocketAddr>,
+    /// Secret token that remote workers must present to the coordinator
+    #[clap(long, env)]
+    pub coordinator_auth_token: Option<String>,
+    /// Seconds without a heartbeat after which a leased proving task is reassigned
+    #[clap(long, env, required = false, default_value_t = 300)]
+    pub worker_lease_timeout: u64,
+}
+
+/// A proving task leased to a remote worker
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskLease {
+    /// Identifier of the lease to heartbeat and complete
+    pub lease_id: u64,
+    /// Local index of the proposal to prove
+    pub proposal_index: u64,
+    /// Arguments of the `kailua-cli prove` invocation computing the proof, excluding the
+    /// Boundless and proof store configurations that workers provide locally
+    pub prove_args: Vec<String>,
+    /// Name under which the computed proof is stored
+    pub proof_file_name: String,
+}
+
+#[rpc(client, server, namespace = "kailua")]
+pub trait ProvingQueueApi {
+    #[method(name = "leaseTask")]
+    async fn lease_task(&self, auth_token: String, worker: String) -> RpcResult<Option<TaskLease>>;
+
+    #[method(name = "heartbeat")]
+    async fn heartbeat(&self, auth_token: String, lease_id: u64) -> RpcResult<bool>;
+
+    #[method(name = "completeTask")]
+    async fn complete_task(
+        &self,
+        auth_token: String,
+        lease_id: u64,
+        outcome: TaskOutcome,
+    ) -> RpcResult<bool>;
+}
+
+/// A proving task being computed by a remote worker
+#[derive(Debug)]
+struct Lease {
+    task: Task,
+    worker: String,
+    heartbeat: Instant,
+}
+
+/// The proving tasks awaiting and undergoing computation by remote workers
+#[derive(Debug, Default)]
+pub struct ProvingQueue {
+    pending: VecDeque<Task>,
+    leases: BTreeMap<u64, Lease>,
+    next_lease_id: u64,
+}
+
+impl ProvingQueue {
+    /// Returns the tasks of workers that stopped sending heartbeats back to the queue
+    fn reclaim_expired(&mut self, lease_timeout: Duration) {
+        let expired = self
+            .leases
+            .iter()
+            .filter(|(_, lease)| lease.heartbeat.elapsed() > lease_timeout)
+            .map(|(lease_id, _)| *lease_id)
+            .collect::<Vec<_>>();
+        for lease_id in expired {
+            let lease = self.leases.remove(&lease_id).unwrap();
+            warn!(
+                "Lease {lease_id} of proving task for local index {} by worker {} expired.",
+                lease.task.proposal_index, lease.worker
+            );
+            self.pending.push_front(lease.task);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ProvingQueueHandler {
+    pub auth_token: String,
+    pub lease_timeout: Duration,
+    pub queue: Arc<Mutex<ProvingQueue>>,
+    pub task_channel: AsyncChannel<Task>,
+    pub proof_sender: Sender<Message>,
+}
+
+impl ProvingQueueHandler {
+    fn authorize(&self, auth_token: &str) -> RpcResult<()> {
+        // compare all bytes to avoid leaking the matching prefix length through timing
+        let expected = self.auth_token.as_bytes();
+        let presented = auth_token.as_bytes();
+        let mismatch = expected
+            .iter()
+            .zip(presented)
+            .fold(expected.len() ^ presented.len(), |acc, (a, b)| {
+                acc | (a ^ b) as usize
+            });
+        if mismatch != 0 {
+            return Err(ErrorObject::owned(
+                UNAUTHORIZED_CODE,
+                "Unauthorized",
+                None::<()>,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProvingQueueApiServer for ProvingQueueHandler {
+    async fn lease_task(&self, auth_token: String, worker: String) -> RpcResult<Option<TaskLease>> {
+        trace!(target: "rpc::kailua", "Serving kailua_leaseTask");
+        self.authorize(&auth_token)?;
+        let mut queue = self.queue.lock().await;
+        queue.reclaim_expired(self.lease_timeout);
+        // serve retried tasks before new ones
+        let Some(task) = queue
+            .pending
+            .pop_front()
+            .or_else(|| self.task_channel.1.try_recv().ok())
+        else {
+            return Ok(None);
+        };
+        let lease_id = queue.next_lease_id;
+        queue.next_lease_id += 1;
+        info!(
+            "Leasing proving task for local index {} to worker {worker} under lease {lease_id}.",
+            task.proposal_index
+        );
+        let lease = TaskLease {
+            lease_id,
+            proposal_index: task.proposal_index,
+            prove_args: create_proving_args(&lease_prove_args(&task.prove_args), 0),
+            proof_file_name: task.proof_file_name.clone(),
+        };
+        queue.leases.insert(
+            lease_id,
+            Lease {
+                task,
+                worker,
+                heartbeat: Instant::now(),
+            },
+        );
+        Ok(Some(lease))
+    }
+
+    async fn heartbeat(&self, auth_token: String, lease_id: u64) -> RpcResult<bool> {
+        trace!(target: "rpc::kailua", "Serving kailua_heartbeat");
+        self.authorize(&auth_token)?;
+        let mut queue = self.queue.lock().await;
+        let Some(lease) = queue.leases.get_mut(&lease_id) else {
+            return Ok(false);
+        };
+        lease.heartbeat = Instant::now();
+        Ok(true)
+    }
+
+    async fn complete_task(
+        &self,
+        auth_token: String,
+        lease_id: u64,
+        outcome: TaskOutcome,
+    ) -> RpcResult<bool> {
+        trace!(target: "rpc::kailua", "Serving kailua_completeTask");
+        self.authorize(&auth_token)?;
+        let mut queue = self.queue.lock().await;
+        let Some(Lease { task, worker, .. }) = queue.leases.remove(&lease_id) else {
+            warn!("Discarding result of unknown lease {lease_id}.");
+            return Ok(false);
+        };
+        let proposal_index = task.proposal_index;
+        match outcome {
+            TaskOutcome::Proof(proof) => {
+                self.proof_sender
+                    .send(Message::Proof(proposal_index, Some(proof)))
+                    .await
+                    .map_err(|err| internal_error(anyhow!(err)))?;
+                info!("Proof for local index {proposal_index} completed by worker {worker}.");
+            }
+            TaskOutcome::InsufficientL1Data => {
+                self.proof_sender
+                    .send(Message::Proof(proposal_index, None))
+                    .await
+                    .map_err(|err| internal_error(anyhow!(err)))?;
+                warn!("Cannot prove local index {proposal_index} due to insufficient l1 head.");
+            }
+            TaskOutcome::Skipped => {
+                info!("Worker {worker} skipped proving task for local index {proposal_index}.");
+            }
+            TaskOutcome::Failed => {
+                info!("Resubmitting proving task for local index {proposal_index} failed by worker {worker}.");
+                queue.pending.push_back(task);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Strips the credentials of the validator's Boundless market, storage provider and proof store
+/// from the proving arguments sent to remote workers
+pub fn lease_prove_args(prove_args: &ProveArgs) -> ProveArgs {
+    let mut prove_args = prove_args.clone();
+    prove_args.boundless = BoundlessArgs::default();
+    prove_args.proving.proof_store = ProofStoreArgs::default();
+    prove_args
+}
+
+/// Starts serving proving tasks to remote workers until the task channel is closed and all leased
+/// tasks are completed
+pub async fn serve_proving_queue(
+    args: CoordinatorArgs,
+    task_channel: AsyncChannel<Task>,
+    proof_sender: Sender<Message>,
+) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
+    let socket_addr = args
+        .coordinator_socket_addr
+        .context("Coordinator socket address not set.")?;
+    let auth_token = args
+        .coordinator_auth_token
+        .context("Coordinator auth token required to serve proving tasks.")?;
+    let lease_timeout = Duration::from_secs(args.worker_lease_timeout);
+
+    let queue: Arc<Mutex<ProvingQueue>> = Default::default();
+    let proving_queue_handler = ProvingQueueHandler {
+        auth_token,
+        lease_timeout,
+        queue: queue.clone(),
+        task_channel: task_channel.clone(),
+        proof_sender,
+    }
+    .into_rpc();
+
+    let server_config = ServerConfig::builder()
+        .http_only()
+        .max_request_body_size(MAX_MESSAGE_SIZE)
+        .max_response_body_size(MAX_MESSAGE_SIZE)
+        .build();
+    let server_handle = jsonrpsee::server::Server::builder()
+        .set_config(server_config)
+        .build(socket_addr)
+        .await
+        .context("Server::build")?
+        .start(proving_queue_handler);
+    info!("Serving proving tasks to remote workers on {socket_addr}.");
+    if !socket_addr.ip().is_loopback() {
+        warn!(
+            "Proving tasks are served without TLS. Only expose {socket_addr} to trusted networks."
+        );
+    }
+
+    Ok(spawn(async move {
+        // Stop serving once no more tasks may be queued or completed
+        loop {
+            sleep(Duration::from_secs(1)).await;
+            let mut queue = queue.lock().await;
+            queue.reclaim_expired(lease_timeout);
+            if task_channel.1.is_closed() && queue.leases.is_empty() {
+                if !queue.pending.is_empty() || !task_channel.1.is_empty() {
+                    warn!("Abandoning unleased proving tasks.");
+                }
+                break;
+            }
+        }
+        if server_handle.stop().is_ok() {
+            server_handle.stopped().await;
+        }
+
+        warn!("serve_proving_queue terminated");
+        Ok(())
+    }))
+}
+
+fn internal_error(err: anyhow::Error) -> ErrorObjectOwned {
+    ErrorObject::owned(INTERNAL_ERROR_CODE, format!("{err:?}"), None::<()>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::B256;
+    use clap::Parser;
+    use tokio::sync::mpsc::{channel, Receiver};
+
+    const AUTH_TOKEN: &str = "secret-token";
+    const WALLET_KEY: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    const S3_SECRET_KEY: &str = "s3-secret-key";
+
+    fn task(proposal_index: u64) -> Task {
+        let zero = B256::ZERO.to_string();
+        let prove_args = ProveArgs::try_parse_from([
+            "kailua-cli",
+            "--l1-head",
+            &zero,
+            "--agreed-l2-head-hash",
+            &zero,
+            "--agreed-l2-output-root",
+            &zero,
+            "--claimed-l2-output-root",
+            &zero,
+            "--claimed-l2-block-number",
+            "10",
+            "--l2-chain-id",
+            "10",
+            "--boundless-rpc-url",
+            "http://localhost:8545",
+            "--boundless-wallet-key",
+            WALLET_KEY,
+            "--proof-store-s3-bucket",
+            "proofs",
+            "--proof-store-s3-secret-key",
+            S3_SECRET_KEY,
+        ])
+        .unwrap();
+        Task {
+            proposal_index,
+            prove_args,
+            proof_file_name: format!("proof-{proposal_index}"),
+        }
+    }
+
+    fn handler(lease_timeout: Duration) -> (ProvingQueueHandler, Receiver<Message>) {
+        let (proof_sender, proof_receiver) = channel(16);
+        let handler = ProvingQueueHandler {
+            auth_token: AUTH_TOKEN.to_string(),
+            lease_timeout,
+            queue: Default::default(),
+            task_channel: async_channel::unbounded(),
+            proof_sender,
+        };
+        (handler, proof_receiver)
+    }
+
+    async fn lease(handler: &ProvingQueueHandler) -> Option<TaskLease> {
+        handler
+            .lease_task(AUTH_TOKEN.to_string(), String::from("worker"))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_lease_heartbeat_complete() {
+        let (handler, mut proof_receiver) = handler(Duration::from_secs(300));
+        assert!(lease(&handler).await.is_none());
+        handler.task_channel.0.send(task(7)).await.unwrap();
+
+        // Leases exclude the validator's credentials
+        let task_lease = lease(&handler).await.unwrap();
+        assert_eq!(task_lease.proposal_index, 7);
+        assert_eq!(task_lease.proof_file_name, "proof-7");
+        for secret in [WALLET_KEY, S3_SECRET_KEY] {
+            assert!(!task_lease.prove_args.iter().any(|arg| arg.contains(secret)));
+        }
+        assert!(!task_lease
+            .prove_args
+            .contains(&String::from("--proof-store-s3-bucket")));
+        assert!(lease(&handler).await.is_none());
+
+        // Heartbeats keep the lease alive until it is completed
+        assert!(handler
+            .heartbeat(AUTH_TOKEN.to_string(), task_lease.lease_id)
+            .await
+            .unwrap());
+        assert!(handler
+            .complete_task(
+                AUTH_TOKEN.to_string(),
+                task_lease.lease_id,
+                TaskOutcome::InsufficientL1Data
+            )
+            .await
+            .unwrap());
+        assert!(matches!(
+            proof_receiver.recv().await,
+            Some(Message::Proof(7, None))
+        ));
+        assert!(!handler
+            .heartbeat(AUTH_TOKEN.to_string(), task_lease.lease_id)
+            .await
+            .unwrap());
+        assert!(!handler
+            .complete_task(
+                AUTH_TOKEN.to_string(),
+                task_lease.lease_id,
+                TaskOutcome::Skipped
+            )
+            .await
+            .unwrap());
+        assert!(handler.queue.lock().await.leases.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lease_requeue() {
+        let (handler, mut proof_receiver) = handler(Duration::from_millis(50));
+        handler.task_channel.0.send(task(1)).await.unwrap();
+        handler.task_channel.0.send(task(2)).await.unwrap();
+
+        // Expired leases are handed out again before new tasks
+        let expired = lease(&handler).await.unwrap();
+        assert_eq!(expired.proposal_index, 1);
+        sleep(Duration::from_millis(100)).await;
+        let reassigned = lease(&handler).await.unwrap();
+        assert_eq!(reassigned.proposal_index, 1);
+        assert_ne!(reassigned.lease_id, expired.lease_id);
+        assert!(!handler
+            .heartbeat(AUTH_TOKEN.to_string(), expired.lease_id)
+            .await
+            .unwrap());
+
+        // Failed tasks are queued again
+        assert!(handler
+            .complete_task(
+                AUTH_TOKEN.to_string(),
+                reassigned.lease_id,
+                TaskOutcome::Failed
+            )
+            .await
+            .unwrap());
+        let next = lease(&handler).await.unwrap();
+        assert_eq!(next.proposal_index, 2);
+        let retried = lease(&handler).await.unwrap();
+        assert_eq!(retried.proposal_index, 1);
+        assert!(proof_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized() {
+        let (handler, _) = handler(Duration::from_secs(300));
+        handler.task_channel.0.send(task(1)).await.unwrap();
+        for auth_token in ["", "secret", "secret-token-", "Secret-token"] {
+            let err = handler
+                .lease_task(auth_token.to_string(), String::from("worker"))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), UNAUTHORIZED_CODE);
+            let err = handler
+                .heartbeat(auth_token.to_string(), 0)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), UNAUTHORIZED_CODE);
+            let err = handler
+                .complete_task(auth_token.to_string(), 0, TaskOutcome::Skipped)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), UNAUTHORIZED_CODE);
+        }
+        // The task remains available to authorized workers
+        assert_eq!(lease(&handler).await.unwrap().proposal_index, 1);
+    }
+}
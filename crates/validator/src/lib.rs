@@ -14,7 +14,10 @@
 
 pub mod args;
 pub mod channel;
+pub mod coordinator;
 pub mod proposals;
 pub mod requests;
+pub mod store;
 pub mod tasks;
 pub mod validate;
+pub mod worker;
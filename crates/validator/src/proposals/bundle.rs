@@ -0,0 +1,72 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::contract::SolCallBuilder;
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use alloy::sol_types::SolCall;
+use anyhow::Context;
+use kailua_sync::agent::SyncAgent;
+use kailua_sync::await_tel_res;
+use kailua_sync::proposal::Proposal;
+use kailua_sync::transact::bundle::TransactionBundle;
+use opentelemetry::global::{meter, tracer};
+use opentelemetry::trace::FutureExt;
+use opentelemetry::trace::{TraceContextExt, Tracer};
+use opentelemetry::KeyValue;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Writes the proof submission call to a bundle file for offline signing instead of publishing it
+pub async fn export_proof_bundle<P: Provider, C: SolCall>(
+    bundle_dir: &Path,
+    call: SolCallBuilder<P, C>,
+    description: &'static str,
+    agent: &SyncAgent,
+    sender: Address,
+    proof_type: &'static str,
+    proposal: &Proposal,
+) -> anyhow::Result<PathBuf> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("export_proof_bundle"));
+
+    let bundle = await_tel_res!(
+        context,
+        TransactionBundle::from_call(call, description, agent.config.l1_chain_id, sender),
+        "TransactionBundle::from_call"
+    )?;
+    let file_name = await_tel_res!(
+        context,
+        bundle.save(bundle_dir, &format!("{proof_type}-{}", proposal.index)),
+        "TransactionBundle::save"
+    )?;
+    info!(
+        "Exported {description} bundle for proposal {} to {}.",
+        proposal.index,
+        file_name.display()
+    );
+    meter("kailua")
+        .u64_counter("validator.proofs.exported")
+        .build()
+        .add(
+            1,
+            &[
+                KeyValue::new("type", proof_type),
+                KeyValue::new("proposal", proposal.contract.to_string()),
+                KeyValue::new("l2_height", proposal.output_block_number.to_string()),
+                KeyValue::new("bundle", file_name.display().to_string()),
+            ],
+        );
+    Ok(file_name)
+}
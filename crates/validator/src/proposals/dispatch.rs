@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::args::ValidateArgs;
 use crate::channel::{DuplexChannel, Message};
 use crate::proposals::get_next_l1_head;
+use crate::proposals::policy::proving_deadline;
+use crate::proposals::watchdog::DeadlineWatchdog;
 use crate::requests::{request_fault_proof, request_validity_proof};
 use kailua_contracts::*;
 use kailua_sync::agent::SyncAgent;
@@ -29,26 +32,25 @@ use std::collections::{BTreeMap, BinaryHeap};
 use std::time::SystemTime;
 use tracing::{error, info, warn};
 
+#[allow(clippy::too_many_arguments)]
 pub async fn dispatch_proof_requests(
-    #[cfg(feature = "devnet")] args: &crate::args::ValidateArgs,
+    args: &ValidateArgs,
     agent: &mut SyncAgent,
     buffer: &mut BinaryHeap<(Reverse<u64>, u64)>,
     meter_proofs_requested: &Counter<u64>,
     last_proof_l1_head: &mut BTreeMap<u64, u64>,
     channel: &mut DuplexChannel<Message>,
+    watchdog: &mut DeadlineWatchdog,
     is_fault: bool,
 ) {
     let tracer = tracer("kailua");
     let context =
         opentelemetry::Context::current_with_span(tracer.start("dispatch_proof_requests"));
 
-    // dispatch buffered validity proof requests
+    // collect buffered proof requests that are due
     let current_timestamp = current_time();
-    let request_count = buffer.len();
-    for _ in 0..request_count {
-        let Some((next_time, proposal_index)) = buffer.peek() else {
-            break;
-        };
+    let mut ready = vec![];
+    while let Some((next_time, proposal_index)) = buffer.peek() {
         if current_timestamp < next_time.0 {
             info!(
                 "Waiting {} more seconds before dispatching (is_fault={is_fault}) proving task for proposal {proposal_index}.",
@@ -56,8 +58,13 @@ pub async fn dispatch_proof_requests(
             );
             break;
         }
+        ready.push(buffer.pop().unwrap());
+    }
+    let policy = args.proving_policy;
+    policy.prioritize(agent, &mut ready);
 
-        let (next_time, proposal_index) = buffer.pop().unwrap();
+    // dispatch due proof requests in order of priority
+    for (next_time, proposal_index) in ready {
         let retry_time = Reverse(next_time.0 + 10);
         let Some(proposal) = agent.proposals.get(&proposal_index) else {
             if agent.cursor.last_resolved_game < proposal_index {
@@ -111,6 +118,12 @@ pub async fn dispatch_proof_requests(
             continue;
         };
 
+        info!(
+            "Dispatching (is_fault={is_fault}) proving task for proposal {proposal_index} \
+                scheduled at {} with challenge deadline {} (policy={policy}).",
+            next_time.0,
+            proving_deadline(agent, proposal)
+        );
         if let Err(err) = await_tel!(context, async {
             if is_fault {
                 request_fault_proof(agent, channel, parent, proposal, l1_head).await
@@ -121,11 +134,13 @@ pub async fn dispatch_proof_requests(
             error!("Could not request (is_fault={is_fault}) proof for {proposal_index}: {err:?}");
             buffer.push((retry_time, proposal_index));
         } else {
+            watchdog.on_dispatch(proposal_index);
             meter_proofs_requested.add(
                 1,
                 &[
                     KeyValue::new("type", format!("(is_fault={is_fault})")),
                     KeyValue::new("proposal", proposal.contract.to_string()),
+                    KeyValue::new("policy", policy.as_str()),
                 ],
             );
         }
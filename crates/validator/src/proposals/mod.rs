@@ -12,21 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod bundle;
 #[cfg(feature = "devnet")]
 pub mod devnet;
 pub mod dispatch;
+pub mod policy;
 pub mod processing;
 pub mod receipts;
+pub mod restore;
 pub mod trails;
+pub mod watch;
+pub mod watchdog;
 
 use crate::args::ValidateArgs;
 use crate::channel::DuplexChannel;
 use crate::channel::Message;
-use alloy::network::{Ethereum, TxSigner};
+use crate::store::{ScheduleSnapshot, ValidatorStore};
+use alloy::network::{Ethereum, EthereumWallet, TxSigner};
 use alloy::primitives::B256;
+use alloy::signers::local::PrivateKeySigner;
 use anyhow::{bail, Context};
 use kailua_sync::agent::{SyncAgent, FINAL_L2_BLOCK_RESOLVED};
+use kailua_sync::bonds::claim_bonds;
 use kailua_sync::proposal::Proposal;
+use kailua_sync::provider::failover_client;
 use kailua_sync::transact::provider::SafeProvider;
 use kailua_sync::{await_tel, await_tel_res};
 use opentelemetry::global::{meter, tracer};
@@ -34,9 +43,11 @@ use opentelemetry::trace::FutureExt;
 use opentelemetry::trace::{TraceContextExt, Tracer};
 use risc0_zkvm::sha::Digestible;
 use risc0_zkvm::InnerReceipt;
-use std::collections::{BTreeMap, BinaryHeap, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
 use std::path::PathBuf;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use tracing::{error, info, warn};
 
@@ -44,6 +55,7 @@ pub async fn handle_proposals(
     mut channel: DuplexChannel<Message>,
     args: ValidateArgs,
     data_dir: PathBuf,
+    store: Arc<ValidatorStore>,
 ) -> anyhow::Result<()> {
     // Telemetry
     let meter = meter("kailua");
@@ -53,6 +65,7 @@ pub async fn handle_proposals(
     let meter_correct_latest = meter.u64_gauge("validator.correct.latest").build();
     let meter_skipped_count = meter.u64_counter("validator.skipped.count").build();
     let meter_skipped_latest = meter.u64_gauge("validator.skipped.latest").build();
+    let meter_proofs_scheduled = meter.u64_counter("validator.proofs.scheduled").build();
     let meter_proofs_requested = meter.u64_counter("validator.proofs.requested").build();
     let meter_proofs_completed = meter.u64_counter("validator.proofs.complete").build();
     let meter_proofs_published = meter.u64_counter("validator.proofs.published").build();
@@ -74,32 +87,77 @@ pub async fn handle_proposals(
 
     // initialize validator wallet
     info!("Initializing validator wallet.");
-    let validator_wallet = await_tel_res!(
-        context,
-        tracer,
-        "ValidatorSigner::wallet",
-        args.validator_signer.wallet(Some(agent.config.l1_chain_id))
-    )?;
-    let validator_address = validator_wallet.default_signer().address();
+    let validator_wallet = if args.bundle_sender.is_some() {
+        // exported bundles are signed offline, so this wallet never signs any transactions
+        EthereumWallet::from(PrivateKeySigner::random())
+    } else {
+        await_tel_res!(
+            context,
+            tracer,
+            "ValidatorSigner::wallet",
+            args.validator_signer.wallet(Some(agent.config.l1_chain_id))
+        )?
+    };
+    let validator_address = args
+        .bundle_sender
+        .unwrap_or(validator_wallet.default_signer().address());
     let validator_provider = SafeProvider::new(
         args.txn_args
             .premium_provider::<Ethereum>()
             .wallet(validator_wallet)
-            .connect_http(args.sync.provider.eth_rpc_url.as_str().try_into()?),
+            .connect_client(failover_client(&args.sync.provider.eth_rpc_url)?),
     );
     info!("Validator address: {validator_address}");
+    if let Some(bundle_dir) = &args.export_bundle_dir {
+        tokio::fs::create_dir_all(bundle_dir)
+            .await
+            .context("Failed to create bundle export directory")?;
+        warn!(
+            "Exporting proof submissions to {} for offline signing.",
+            bundle_dir.display()
+        );
+    }
+    if args.bonds.claim_bonds
+        && args
+            .proving
+            .payout_recipient_address
+            .is_some_and(|recipient| recipient != validator_address)
+    {
+        warn!(
+            "Elimination bonds owed to the payout recipient can not be claimed by the validator."
+        );
+    }
 
     // Run the validator loop
     info!(
         "Starting from proposal at factory index {}",
         agent.cursor.next_factory_index
     );
-    // init channel buffers
-    let mut computed_proof_buffer = VecDeque::new();
-    let mut output_fault_buffer = BinaryHeap::new();
-    let mut trail_fault_buffer = BinaryHeap::new();
-    let mut proposal_validity_buffer = BinaryHeap::new();
-    let mut last_proof_l1_head = BTreeMap::new();
+    // restore channel buffers
+    let queues = await_tel_res!(
+        context,
+        restore::restore_queues(&agent, &store, &mut channel),
+        "restore_queues"
+    )?;
+    let mut computed_proof_buffer = queues.computed_proof_buffer;
+    let mut output_fault_buffer = queues.output_fault_buffer;
+    let mut trail_fault_buffer = queues.trail_fault_buffer;
+    let mut proposal_validity_buffer = queues.proposal_validity_buffer;
+    let mut last_proof_l1_head = queues.last_proof_l1_head;
+    let mut persisted_schedules = ScheduleSnapshot::new(
+        &proposal_validity_buffer,
+        &output_fault_buffer,
+        &trail_fault_buffer,
+        &last_proof_l1_head,
+    );
+    // resume timing the restored proving tasks
+    let mut watchdog = watchdog::DeadlineWatchdog::with_store(store.clone());
+    for message in &queues.proving_tasks {
+        if let Message::Proposal { index, .. } = message {
+            watchdog.on_dispatch(*index);
+        }
+    }
+    let mut last_bond_claim: Option<Instant> = None;
     loop {
         // Wait for new data on every iteration
         sleep(Duration::from_secs(1)).await;
@@ -136,34 +194,46 @@ pub async fn handle_proposals(
             &meter_fault_latest,
             &meter_skipped_count,
             &meter_skipped_latest,
+            &meter_proofs_scheduled,
             &mut proposal_validity_buffer,
             &mut output_fault_buffer,
             &mut trail_fault_buffer,
         )
         .await;
 
+        // check that queued and ongoing proofs can meet their deadlines
+        watchdog
+            .check_deadlines(
+                &args,
+                &agent,
+                &mut proposal_validity_buffer,
+                &mut output_fault_buffer,
+                &mut channel,
+            )
+            .await;
+
         // dispatch buffered output fault proof requests
         dispatch::dispatch_proof_requests(
-            #[cfg(feature = "devnet")]
             &args,
             &mut agent,
             &mut output_fault_buffer,
             &meter_proofs_requested,
             &mut last_proof_l1_head,
             &mut channel,
+            &mut watchdog,
             true,
         )
         .await;
 
         // dispatch buffered validity proof requests
         dispatch::dispatch_proof_requests(
-            #[cfg(feature = "devnet")]
             &args,
             &mut agent,
             &mut proposal_validity_buffer,
             &meter_proofs_requested,
             &mut last_proof_l1_head,
             &mut channel,
+            &mut watchdog,
             false,
         )
         .await;
@@ -180,7 +250,10 @@ pub async fn handle_proposals(
             &meter_proofs_published,
             &meter_proofs_fail,
             &mut channel,
+            validator_address,
             &validator_provider,
+            &store,
+            &mut watchdog,
         )
         .await;
 
@@ -196,6 +269,64 @@ pub async fn handle_proposals(
             &validator_provider,
         )
         .await;
+        // persist the changes made to the schedules in this iteration
+        persist_schedules(
+            &store,
+            &mut persisted_schedules,
+            &proposal_validity_buffer,
+            &output_fault_buffer,
+            &trail_fault_buffer,
+            &last_proof_l1_head,
+        );
+
+        // periodically claim elimination bonds owed by the treasury
+        if args.bonds.claim_bonds
+            && last_bond_claim.is_none_or(|instant| {
+                instant.elapsed() >= Duration::from_secs(args.bonds.bond_claim_interval)
+            })
+        {
+            last_bond_claim = Some(Instant::now());
+            match await_tel!(
+                context,
+                claim_bonds(
+                    &validator_provider,
+                    agent.deployment.treasury,
+                    validator_address,
+                    false,
+                    &args.bonds.claim,
+                    &args.txn_args
+                )
+            ) {
+                Ok(claimed) if !claimed.is_zero() => info!("Claimed {claimed} wei in bonds."),
+                Ok(_) => {}
+                Err(err) => error!("Failed to claim bonds: {err:?}"),
+            }
+        }
+    }
+}
+
+/// Persists the changes made to the proving schedules since they were last persisted, logging
+/// any failure to do so
+pub fn persist_schedules(
+    store: &ValidatorStore,
+    persisted: &mut ScheduleSnapshot,
+    proposal_validity_buffer: &BinaryHeap<(Reverse<u64>, u64)>,
+    output_fault_buffer: &BinaryHeap<(Reverse<u64>, u64)>,
+    trail_fault_buffer: &BinaryHeap<(Reverse<u64>, u64)>,
+    last_proof_l1_head: &BTreeMap<u64, u64>,
+) {
+    let current = ScheduleSnapshot::new(
+        proposal_validity_buffer,
+        output_fault_buffer,
+        trail_fault_buffer,
+        last_proof_l1_head,
+    );
+    if current == *persisted {
+        return;
+    }
+    match store.put_schedules(persisted, &current) {
+        Ok(()) => *persisted = current,
+        Err(err) => error!("Failed to persist proving queues: {err:?}"),
     }
 }
 
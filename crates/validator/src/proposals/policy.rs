@@ -0,0 +1,292 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::proposals::dispatch::current_time;
+use crate::proposals::processing::random_processing_time;
+use kailua_sync::agent::SyncAgent;
+use kailua_sync::proposal::Proposal;
+use opentelemetry::metrics::Counter;
+use opentelemetry::KeyValue;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt::{Display, Formatter};
+use tracing::{error, info};
+
+/// The order in which buffered proof requests are dispatched to the provers
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProvingPolicy {
+    /// Dispatch each request after a random delay bounded by the maximum proving delay
+    #[default]
+    Random,
+    /// Dispatch requests immediately, proving those closest to their challenge deadline first
+    Deadline,
+    /// Dispatch requests after a random delay, computing validity proofs only for disputed
+    /// tournaments
+    Cost,
+    /// Dispatch requests immediately in the order they were queued
+    Fifo,
+}
+
+impl ProvingPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProvingPolicy::Random => "random",
+            ProvingPolicy::Deadline => "deadline",
+            ProvingPolicy::Cost => "cost",
+            ProvingPolicy::Fifo => "fifo",
+        }
+    }
+
+    /// Returns whether validity proofs are reserved for tournaments with invalid proposals
+    pub fn is_cost_aware(&self) -> bool {
+        matches!(self, ProvingPolicy::Cost)
+    }
+
+    /// Returns the time at which a newly queued request should be dispatched and why
+    pub fn dispatch_time(&self, max_delay: u64) -> (Reverse<u64>, &'static str) {
+        match self {
+            ProvingPolicy::Random | ProvingPolicy::Cost => {
+                (random_processing_time(max_delay), "random_delay")
+            }
+            ProvingPolicy::Deadline => (Reverse(current_time()), "deadline_order"),
+            ProvingPolicy::Fifo => (Reverse(current_time()), "arrival_order"),
+        }
+    }
+
+    /// Sorts the requests that are due for dispatch into the order they should be dispatched in
+    pub fn prioritize(&self, agent: &SyncAgent, ready: &mut [(Reverse<u64>, u64)]) {
+        self.prioritize_by(ready, |proposal_index| {
+            agent
+                .proposals
+                .get(&proposal_index)
+                .map(|proposal| proving_deadline(agent, proposal))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Sorts the requests that are due for dispatch using the given proving deadline of each
+    /// proposal
+    pub fn prioritize_by(&self, ready: &mut [(Reverse<u64>, u64)], deadline: impl Fn(u64) -> u64) {
+        match self {
+            // keep the order of the schedule
+            ProvingPolicy::Random | ProvingPolicy::Cost => {}
+            ProvingPolicy::Deadline => ready
+                .sort_by_key(|(_, proposal_index)| (deadline(*proposal_index), *proposal_index)),
+            ProvingPolicy::Fifo => {
+                ready.sort_by_key(|(time, proposal_index)| (time.0, *proposal_index))
+            }
+        }
+    }
+}
+
+impl Display for ProvingPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Queues a proof request for dispatch at the time chosen by the policy and reports the reason
+/// for its scheduling
+pub fn schedule_proof_request(
+    policy: ProvingPolicy,
+    max_delay: u64,
+    buffer: &mut BinaryHeap<(Reverse<u64>, u64)>,
+    proposal: &Proposal,
+    proof_type: &'static str,
+    reason: &'static str,
+    meter_proofs_scheduled: &Counter<u64>,
+) {
+    let (dispatch_time, timing) = policy.dispatch_time(max_delay);
+    info!(
+        "Scheduled {proof_type} proof for proposal {} in {} seconds (policy={policy}, \
+            reason={reason}, timing={timing}).",
+        proposal.index,
+        dispatch_time.0.saturating_sub(current_time())
+    );
+    meter_proofs_scheduled.add(
+        1,
+        &[
+            KeyValue::new("policy", policy.as_str()),
+            KeyValue::new("type", proof_type),
+            KeyValue::new("reason", reason),
+            KeyValue::new("timing", timing),
+            KeyValue::new("proposal", proposal.contract.to_string()),
+        ],
+    );
+    buffer.push((dispatch_time, proposal.index));
+}
+
+/// Returns the timestamp at which the outcome of the proof request becomes moot.
+///
+/// This mirrors `KailuaGame::getChallengerDuration` for the proposal returned by
+/// [deadline_proposal].
+pub fn proving_deadline(agent: &SyncAgent, proposal: &Proposal) -> u64 {
+    deadline_proposal(agent, proposal).created_at + agent.deployment.timeout
+}
+
+/// Returns the proposal whose challenge window bounds the usefulness of proving the given one.
+///
+/// A fault must be proven before the faulty proposal's challenge window expires, while a
+/// validity proof must land before the window of the earliest invalid sibling it is meant to
+/// eliminate.
+pub fn deadline_proposal<'a>(agent: &'a SyncAgent, proposal: &'a Proposal) -> &'a Proposal {
+    earliest_invalid_sibling(&agent.proposals, proposal, |sibling| {
+        agent.was_proposer_eliminated_before(sibling)
+    })
+}
+
+/// Returns the earliest created invalid sibling of a correct proposal whose proposer was not
+/// eliminated, or the proposal itself if there is none
+fn earliest_invalid_sibling<'a>(
+    proposals: &'a BTreeMap<u64, Proposal>,
+    proposal: &'a Proposal,
+    was_proposer_eliminated_before: impl Fn(&Proposal) -> bool,
+) -> &'a Proposal {
+    if !proposal.is_correct().unwrap_or_default() {
+        return proposal;
+    }
+    let Some(parent) = proposals.get(&proposal.parent) else {
+        return proposal;
+    };
+    parent
+        .children
+        .iter()
+        .filter_map(|p| proposals.get(p))
+        .filter(|sibling| !was_proposer_eliminated_before(sibling))
+        .filter(|sibling| sibling.is_correct() == Some(false))
+        .min_by_key(|sibling| sibling.created_at)
+        .unwrap_or(proposal)
+}
+
+/// Returns true if an invalid sibling was proposed before the given proposal in its tournament
+pub fn is_disputed(agent: &SyncAgent, parent: &Proposal, proposal_index: u64) -> bool {
+    parent
+        .children
+        .iter()
+        .filter(|p| **p < proposal_index)
+        .any(|p| {
+            let Some(sibling) = agent.proposals.get(p) else {
+                error!("Proposal {p} missing from database.");
+                return false;
+            };
+            !agent.was_proposer_eliminated_before(sibling) && sibling.is_correct() == Some(false)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proposal(index: u64, parent: u64, created_at: u64, correct: Option<bool>) -> Proposal {
+        Proposal {
+            created_at,
+            correct_claim: correct,
+            correct_parent: Some(true),
+            ..Proposal::mock(index, parent)
+        }
+    }
+
+    /// A tournament under proposal 0 with the children (index, created_at, correctness)
+    fn tournament(children: &[(u64, u64, Option<bool>)]) -> BTreeMap<u64, Proposal> {
+        let mut parent = proposal(0, 0, 0, Some(true));
+        parent.children = children.iter().map(|(index, ..)| *index).collect();
+        let mut proposals = BTreeMap::from([(0, parent)]);
+        for (index, created_at, correct) in children {
+            proposals.insert(*index, proposal(*index, 0, *created_at, *correct));
+        }
+        proposals
+    }
+
+    fn ready() -> Vec<(Reverse<u64>, u64)> {
+        vec![
+            (Reverse(30), 1),
+            (Reverse(10), 3),
+            (Reverse(20), 2),
+            (Reverse(10), 4),
+        ]
+    }
+
+    #[test]
+    fn test_prioritize() {
+        let deadline = |proposal_index: u64| match proposal_index {
+            1 => 300,
+            2 => 100,
+            3 => 200,
+            _ => 100,
+        };
+        // random and cost-aware policies keep the schedule order
+        for policy in [ProvingPolicy::Random, ProvingPolicy::Cost] {
+            let mut ordered = ready();
+            policy.prioritize_by(&mut ordered, deadline);
+            assert_eq!(ordered, ready());
+        }
+        // the deadline policy orders by deadline, then index
+        let mut ordered = ready();
+        ProvingPolicy::Deadline.prioritize_by(&mut ordered, deadline);
+        assert_eq!(
+            ordered.iter().map(|(_, i)| *i).collect::<Vec<_>>(),
+            vec![2, 4, 3, 1]
+        );
+        // the fifo policy orders by queueing time, then index
+        let mut ordered = ready();
+        ProvingPolicy::Fifo.prioritize_by(&mut ordered, deadline);
+        assert_eq!(
+            ordered.iter().map(|(_, i)| *i).collect::<Vec<_>>(),
+            vec![3, 4, 2, 1]
+        );
+    }
+
+    #[test]
+    fn test_dispatch_time() {
+        let now = current_time();
+        for policy in [ProvingPolicy::Deadline, ProvingPolicy::Fifo] {
+            let (time, _) = policy.dispatch_time(1000);
+            assert!(time.0 >= now && time.0 <= current_time());
+        }
+        for policy in [ProvingPolicy::Random, ProvingPolicy::Cost] {
+            let (time, timing) = policy.dispatch_time(1000);
+            assert_eq!(timing, "random_delay");
+            assert!(time.0 >= now && time.0 <= current_time() + 1000);
+        }
+        assert!(ProvingPolicy::Cost.is_cost_aware());
+        assert!(!ProvingPolicy::Deadline.is_cost_aware());
+    }
+
+    #[test]
+    fn test_deadline_proposal() {
+        let not_eliminated = |_: &Proposal| false;
+        let proposals = tournament(&[
+            (1, 100, Some(true)),
+            (2, 300, Some(false)),
+            (3, 200, Some(false)),
+            (4, 50, None),
+        ]);
+        // a correct proposal is bounded by the earliest invalid sibling
+        let deadline = earliest_invalid_sibling(&proposals, &proposals[&1], not_eliminated);
+        assert_eq!(deadline.index, 3);
+        // eliminated proposers are ignored
+        let deadline = earliest_invalid_sibling(&proposals, &proposals[&1], |p| p.index == 3);
+        assert_eq!(deadline.index, 2);
+        // faulty proposals are bounded by their own window
+        let deadline = earliest_invalid_sibling(&proposals, &proposals[&2], not_eliminated);
+        assert_eq!(deadline.index, 2);
+        // undecided proposals are bounded by their own window
+        let deadline = earliest_invalid_sibling(&proposals, &proposals[&4], not_eliminated);
+        assert_eq!(deadline.index, 4);
+        // undisputed proposals are bounded by their own window
+        let proposals = tournament(&[(1, 100, Some(true)), (2, 50, None)]);
+        let deadline = earliest_invalid_sibling(&proposals, &proposals[&1], not_eliminated);
+        assert_eq!(deadline.index, 1);
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::args::ValidateArgs;
+use crate::proposals::policy::{is_disputed, schedule_proof_request};
 use kailua_contracts::*;
 use kailua_sync::agent::SyncAgent;
 use kailua_sync::await_tel;
@@ -39,6 +40,7 @@ pub async fn process_proposals(
     meter_fault_latest: &Gauge<u64>,
     meter_skipped_count: &Counter<u64>,
     meter_skipped_latest: &Gauge<u64>,
+    meter_proofs_scheduled: &Counter<u64>,
     proposal_validity_buffer: &mut BinaryHeap<(Reverse<u64>, u64)>,
     output_fault_buffer: &mut BinaryHeap<(Reverse<u64>, u64)>,
     trail_fault_buffer: &mut BinaryHeap<(Reverse<u64>, u64)>,
@@ -152,14 +154,69 @@ pub async fn process_proposals(
         {
             // prove the validity of this proposal if it is canon
             if is_proposal_canonical {
-                // Randomize proving wait
-                let random_wait = random_processing_time(args.max_validity_proving_delay);
+                // Only settle disputed tournaments under the cost-aware policy
+                if args.proving_policy.is_cost_aware()
+                    && !is_disputed(agent, parent, *proposal_index)
+                {
+                    info!(
+                        "Deferring validity proving for undisputed proposal {proposal_index} \
+                            (policy={}).",
+                        args.proving_policy
+                    );
+                    meter_skipped_count.add(
+                        1,
+                        &[
+                            KeyValue::new("proposal", proposal.contract.to_string()),
+                            KeyValue::new("tournament", parent.contract.to_string()),
+                            KeyValue::new("reason", "undisputed_tournament"),
+                        ],
+                    );
+                    meter_skipped_latest.record(
+                        proposal.index,
+                        &[
+                            KeyValue::new("proposal", proposal.contract.to_string()),
+                            KeyValue::new("tournament", parent.contract.to_string()),
+                            KeyValue::new("reason", "undisputed_tournament"),
+                        ],
+                    );
+                    continue;
+                }
                 // Prove full validity
-                proposal_validity_buffer.push((random_wait, *proposal_index));
+                schedule_proof_request(
+                    args.proving_policy,
+                    args.max_validity_proving_delay,
+                    proposal_validity_buffer,
+                    proposal,
+                    "validity",
+                    "fast_forward",
+                    meter_proofs_scheduled,
+                );
                 continue;
             }
             // skip fault proving if a validity proof is en-route
             if let Some(successor) = parent.successor {
+                // Prove the validity of a deferred successor once its tournament is disputed
+                if args.proving_policy.is_cost_aware()
+                    && proposal.is_correct() == Some(false)
+                    && !agent.was_proposer_eliminated_before(proposal)
+                    && successor < *proposal_index
+                    && !is_disputed(agent, parent, *proposal_index)
+                    && !proposal_validity_buffer
+                        .iter()
+                        .any(|(_, p)| *p == successor)
+                {
+                    if let Some(successor) = agent.proposals.get(&successor) {
+                        schedule_proof_request(
+                            args.proving_policy,
+                            args.max_validity_proving_delay,
+                            proposal_validity_buffer,
+                            successor,
+                            "validity",
+                            "disputed_tournament",
+                            meter_proofs_scheduled,
+                        );
+                    }
+                }
                 info!(
                     "Skipping proving for proposal {proposal_index} assuming ongoing \
                         validity proof generation for proposal {successor}."
@@ -197,8 +254,15 @@ pub async fn process_proposals(
                     info!(
                             "Computing validity proof for {proposal_index} to discard invalid predecessors."
                         );
-                    let random_wait = random_processing_time(args.max_fault_proving_delay);
-                    proposal_validity_buffer.push((random_wait, p));
+                    schedule_proof_request(
+                        args.proving_policy,
+                        args.max_fault_proving_delay,
+                        proposal_validity_buffer,
+                        proposal,
+                        "validity",
+                        "invalid_predecessor",
+                        meter_proofs_scheduled,
+                    );
                 }
                 Some(p) if p == proposal.index => {
                     // Skip proving as no conflicts exist
@@ -207,8 +271,19 @@ pub async fn process_proposals(
                 Some(p) if proposal.is_correct() == Some(false) && !is_prior_fault => {
                     // Compute validity proof on arrival of faulty proposal after correct proposal
                     info!("Computing validity proof for {p} to discard invalid successor.");
-                    let random_wait = random_processing_time(args.max_fault_proving_delay);
-                    proposal_validity_buffer.push((random_wait, p));
+                    if let Some(successor) = agent.proposals.get(&p) {
+                        schedule_proof_request(
+                            args.proving_policy,
+                            args.max_fault_proving_delay,
+                            proposal_validity_buffer,
+                            successor,
+                            "validity",
+                            "invalid_successor",
+                            meter_proofs_scheduled,
+                        );
+                    } else {
+                        error!("Proposal {p} missing from database.");
+                    }
                 }
                 Some(p) if proposal.is_correct() == Some(false) => {
                     // is_prior_fault is true and a successor exists, so some proof must be queued
@@ -301,15 +376,29 @@ pub async fn process_proposals(
             error!("Attempted to request fault proof for correct proposal {proposal_index}");
             continue;
         };
-        // Randomize proving wait
-        let random_wait = random_processing_time(args.max_fault_proving_delay);
         // Queue fault proof
         if fault.is_output() {
             // Queue output fault proof request
-            output_fault_buffer.push((random_wait, *proposal_index));
+            schedule_proof_request(
+                args.proving_policy,
+                args.max_fault_proving_delay,
+                output_fault_buffer,
+                proposal,
+                "output_fault",
+                "invalid_output",
+                meter_proofs_scheduled,
+            );
         } else {
             // Queue trail fault proof submission
-            trail_fault_buffer.push((random_wait, *proposal_index));
+            schedule_proof_request(
+                args.proving_policy,
+                args.max_fault_proving_delay,
+                trail_fault_buffer,
+                proposal,
+                "trail_fault",
+                "invalid_trail",
+                meter_proofs_scheduled,
+            );
         }
     }
 }
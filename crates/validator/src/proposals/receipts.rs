@@ -14,10 +14,13 @@
 
 use crate::args::ValidateArgs;
 use crate::channel::{DuplexChannel, Message};
+use crate::proposals::bundle::export_proof_bundle;
 use crate::proposals::dispatch::current_time;
 use crate::proposals::encode_seal;
+use crate::proposals::watchdog::DeadlineWatchdog;
+use crate::store::{computed_proof_keys, ValidatorStore};
 use alloy::primitives::Bytes;
-use alloy::primitives::B256;
+use alloy::primitives::{Address, B256};
 use alloy::providers::Provider;
 use anyhow::Context;
 use kailua_contracts::*;
@@ -25,9 +28,9 @@ use kailua_kona::blobs::hash_to_fe;
 use kailua_kona::journal::ProofJournal;
 use kailua_kona::precondition::validity_precondition_hash;
 use kailua_sync::agent::SyncAgent;
+use kailua_sync::await_tel;
 use kailua_sync::stall::Stall;
 use kailua_sync::transact::Transact;
-use kailua_sync::{await_tel, retry_res_ctx_timeout};
 use opentelemetry::global::tracer;
 use opentelemetry::metrics::Counter;
 use opentelemetry::trace::FutureExt;
@@ -35,7 +38,6 @@ use opentelemetry::trace::{TraceContextExt, Tracer};
 use opentelemetry::KeyValue;
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, VecDeque};
-use std::time::Duration;
 use tracing::{error, info, warn};
 
 #[allow(clippy::too_many_arguments)]
@@ -50,23 +52,43 @@ pub async fn publish_receipt_proofs<P: Provider>(
     meter_proofs_published: &Counter<u64>,
     meter_proofs_fail: &Counter<u64>,
     channel: &mut DuplexChannel<Message>,
+    validator_address: Address,
     validator_provider: &P,
+    store: &ValidatorStore,
+    watchdog: &mut DeadlineWatchdog,
 ) {
     let tracer = tracer("kailua");
     let context = opentelemetry::Context::current_with_span(tracer.start("publish_receipt_proofs"));
 
     // load newly received proofs into buffer
+    let mut completed_tasks = vec![];
+    let mut received_messages = 0;
     while !channel.receiver.is_empty() {
         let Some(message) = channel.receiver.recv().await else {
             error!("Proofs receiver channel closed");
             break;
         };
         meter_proofs_completed.add(1, &[]);
+        if let Message::Proof(proposal_index, receipt) = &message {
+            watchdog.on_proof(*proposal_index, receipt.is_some());
+            completed_tasks.push(*proposal_index);
+        }
         computed_proof_buffer.push_back(message);
+        received_messages += 1;
+    }
+    // persist the received proofs and retire the fulfilled proving tasks
+    if !completed_tasks.is_empty() {
+        let received_proofs = computed_proof_buffer
+            .range(computed_proof_buffer.len() - received_messages..)
+            .filter(|message| matches!(message, Message::Proof(..)));
+        if let Err(err) = store.put_computed_proofs(received_proofs, &completed_tasks) {
+            error!("Failed to persist computed proofs: {err:?}");
+        }
     }
 
     // publish computed output fault proofs
     let computed_proofs = computed_proof_buffer.len();
+    let pending_proofs = computed_proof_keys(computed_proof_buffer);
     for _ in 0..computed_proofs {
         let Some(Message::Proof(proposal_index, receipt)) = computed_proof_buffer.pop_front()
         else {
@@ -247,17 +269,36 @@ pub async fn publish_receipt_proofs<P: Provider>(
                 }
             }
 
-            match parent_contract
-                .proveValidity(
-                    proof_journal.payout_recipient,
-                    *l1_head_contract,
-                    child_index,
-                    encoded_seal.clone(),
+            let transaction = parent_contract.proveValidity(
+                proof_journal.payout_recipient,
+                *l1_head_contract,
+                child_index,
+                encoded_seal.clone(),
+            );
+            // leave the submission to an offline signer
+            if let Some(bundle_dir) = &args.export_bundle_dir {
+                if let Err(err) = export_proof_bundle(
+                    bundle_dir,
+                    transaction,
+                    "KailuaTournament::proveValidity",
+                    agent,
+                    validator_address,
+                    "validity",
+                    proposal,
                 )
-                .timed_transact_with_context(
+                .await
+                {
+                    error!("Failed to export validity proof bundle: {err:?}");
+                    computed_proof_buffer.push_back(Message::Proof(proposal_index, Some(receipt)));
+                }
+                continue;
+            }
+
+            match transaction
+                .replaceable_transact_with_context(
                     context.clone(),
                     "KailuaTournament::proveValidity",
-                    Some(Duration::from_secs(args.txn_args.txn_timeout)),
+                    &args.txn_args,
                 )
                 .await
                 .context("KailuaTournament::proveValidity")
@@ -340,28 +381,27 @@ pub async fn publish_receipt_proofs<P: Provider>(
                     "Proposal output fe {output_fe} doesn't match proof fe {proof_output_root_fe}",
                 );
             }
-            let op_node_output = await_tel!(
+            match await_tel!(
                 context,
                 tracer,
                 "op_node_output",
-                retry_res_ctx_timeout!(
-                    agent
-                        .provider
-                        .op_provider
-                        .output_at_block(proof_journal.claimed_l2_block_number)
-                        .await
-                )
-            );
-            if proof_journal.claimed_l2_output_root != op_node_output {
-                error!(
-                    "Local op node output {op_node_output} doesn't match proof {}",
-                    proof_journal.claimed_l2_output_root
-                );
-            } else {
-                info!(
-                    "Proven output matches local op node output {}:{op_node_output}.",
-                    proof_journal.claimed_l2_block_number
-                );
+                agent
+                    .provider
+                    .output_at_block(proof_journal.claimed_l2_block_number)
+            ) {
+                Ok(op_node_output) if proof_journal.claimed_l2_output_root != op_node_output => {
+                    error!(
+                        "Local op node output {op_node_output} doesn't match proof {}",
+                        proof_journal.claimed_l2_output_root
+                    );
+                }
+                Ok(op_node_output) => {
+                    info!(
+                        "Proven output matches local op node output {}:{op_node_output}.",
+                        proof_journal.claimed_l2_block_number
+                    );
+                }
+                Err(err) => error!("Failed to fetch local op node output: {err:?}"),
             }
 
             let expected_block_number = parent.output_block_number
@@ -531,22 +571,41 @@ pub async fn publish_receipt_proofs<P: Provider>(
             proofs.len()
         );
 
-        let transaction_dispatch = parent_contract
-            .proveOutputFault(
-                [proof_journal.payout_recipient, *l1_head_contract],
-                [child_index, divergence_point],
-                encoded_seal.clone(),
-                [
-                    proof_journal.agreed_l2_output_root,
-                    proof_journal.claimed_l2_output_root,
-                ],
-                output_fe,
-                [commitments, proofs],
+        let transaction = parent_contract.proveOutputFault(
+            [proof_journal.payout_recipient, *l1_head_contract],
+            [child_index, divergence_point],
+            encoded_seal.clone(),
+            [
+                proof_journal.agreed_l2_output_root,
+                proof_journal.claimed_l2_output_root,
+            ],
+            output_fe,
+            [commitments, proofs],
+        );
+        // leave the submission to an offline signer
+        if let Some(bundle_dir) = &args.export_bundle_dir {
+            if let Err(err) = export_proof_bundle(
+                bundle_dir,
+                transaction,
+                "KailuaTournament::proveOutputFault",
+                agent,
+                validator_address,
+                "fault_output",
+                proposal,
             )
-            .timed_transact_with_context(
+            .await
+            {
+                error!("Failed to export output fault proof bundle: {err:?}");
+                computed_proof_buffer.push_back(Message::Proof(proposal_index, Some(receipt)));
+            }
+            continue;
+        }
+
+        let transaction_dispatch = transaction
+            .replaceable_transact_with_context(
                 context.clone(),
                 "KailuaTournament::proveOutputFault",
-                Some(Duration::from_secs(args.txn_args.txn_timeout)),
+                &args.txn_args,
             )
             .await
             .context("KailuaTournament::proveOutputFault");
@@ -601,4 +660,13 @@ pub async fn publish_receipt_proofs<P: Provider>(
             }
         }
     }
+
+    // forget the proofs that were published or discarded
+    if computed_proofs > 0 {
+        let remaining_proofs = computed_proof_keys(computed_proof_buffer);
+        if let Err(err) = store.delete_computed_proofs(pending_proofs.difference(&remaining_proofs))
+        {
+            error!("Failed to persist computed proofs: {err:?}");
+        }
+    }
 }
@@ -0,0 +1,185 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::channel::{DuplexChannel, Message};
+use crate::store::{computed_proof_keys, ScheduleSnapshot, ValidatorQueues, ValidatorStore};
+use anyhow::Context;
+use kailua_contracts::*;
+use kailua_sync::agent::SyncAgent;
+use kailua_sync::await_tel;
+use kailua_sync::stall::Stall;
+use opentelemetry::global::tracer;
+use opentelemetry::trace::FutureExt;
+use opentelemetry::trace::{TraceContextExt, Tracer};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use tracing::info;
+
+/// Loads the persisted proving queues, discards the entries made obsolete on chain while the
+/// validator was offline, and resubmits the remaining unfulfilled proving tasks
+pub async fn restore_queues(
+    agent: &SyncAgent,
+    store: &ValidatorStore,
+    channel: &mut DuplexChannel<Message>,
+) -> anyhow::Result<ValidatorQueues> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("restore_queues"));
+
+    store
+        .bind_treasury(agent.deployment.treasury)
+        .context("ValidatorStore::bind_treasury")?;
+    let queues = store.load_queues().context("ValidatorStore::load_queues")?;
+    let persisted_schedules = ScheduleSnapshot::new(
+        &queues.proposal_validity_buffer,
+        &queues.output_fault_buffer,
+        &queues.trail_fault_buffer,
+        &queues.last_proof_l1_head,
+    );
+    let persisted_proofs = computed_proof_keys(&queues.computed_proof_buffer);
+
+    let proposal_validity_buffer = await_tel!(
+        context,
+        reconcile_schedule(agent, queues.proposal_validity_buffer)
+    );
+    let output_fault_buffer = await_tel!(
+        context,
+        reconcile_schedule(agent, queues.output_fault_buffer)
+    );
+    let trail_fault_buffer = await_tel!(
+        context,
+        reconcile_schedule(agent, queues.trail_fault_buffer)
+    );
+    let mut computed_proof_buffer = queues.computed_proof_buffer;
+    let computed_proofs = computed_proof_buffer.len();
+    for _ in 0..computed_proofs {
+        let message = computed_proof_buffer.pop_front().unwrap();
+        let Message::Proof(proposal_index, _) = &message else {
+            continue;
+        };
+        if !await_tel!(context, is_proving_obsolete(agent, *proposal_index)) {
+            computed_proof_buffer.push_back(message);
+        }
+    }
+    let mut last_proof_l1_head = queues.last_proof_l1_head;
+    last_proof_l1_head.retain(|index, _| *index > agent.cursor.last_resolved_game);
+
+    // resubmit unfulfilled proving tasks
+    let mut proving_tasks = vec![];
+    for message in queues.proving_tasks {
+        let Message::Proposal { index, .. } = &message else {
+            continue;
+        };
+        if await_tel!(context, is_proving_obsolete(agent, *index)) {
+            store
+                .delete_proving_tasks(*index)
+                .context("ValidatorStore::delete_proving_tasks")?;
+            continue;
+        }
+        info!("Resubmitting persisted proving task for local index {index}.");
+        channel
+            .sender
+            .send(message.clone())
+            .await
+            .context("proof request channel closed")?;
+        proving_tasks.push(message);
+    }
+
+    info!(
+        "Restored {} validity proof requests, {} output fault proof requests, {} trail fault \
+            proof submissions, {} computed proofs and {} proving tasks.",
+        proposal_validity_buffer.len(),
+        output_fault_buffer.len(),
+        trail_fault_buffer.len(),
+        computed_proof_buffer.len(),
+        proving_tasks.len()
+    );
+    let reconciled_schedules = ScheduleSnapshot::new(
+        &proposal_validity_buffer,
+        &output_fault_buffer,
+        &trail_fault_buffer,
+        &last_proof_l1_head,
+    );
+    store
+        .put_schedules(&persisted_schedules, &reconciled_schedules)
+        .context("ValidatorStore::put_schedules")?;
+    store
+        .delete_computed_proofs(
+            persisted_proofs.difference(&computed_proof_keys(&computed_proof_buffer)),
+        )
+        .context("ValidatorStore::delete_computed_proofs")?;
+
+    Ok(ValidatorQueues {
+        proposal_validity_buffer,
+        output_fault_buffer,
+        trail_fault_buffer,
+        computed_proof_buffer,
+        last_proof_l1_head,
+        proving_tasks,
+    })
+}
+
+/// Retains the scheduled entries that still require proving, keeping their original deadlines
+async fn reconcile_schedule(
+    agent: &SyncAgent,
+    buffer: BinaryHeap<(Reverse<u64>, u64)>,
+) -> BinaryHeap<(Reverse<u64>, u64)> {
+    let mut reconciled = BinaryHeap::with_capacity(buffer.len());
+    for (next_time, proposal_index) in buffer {
+        if !is_proving_obsolete(agent, proposal_index).await {
+            reconciled.push((next_time, proposal_index));
+        }
+    }
+    reconciled
+}
+
+/// Returns true if the proposal was resolved, freed, or proven on chain
+async fn is_proving_obsolete(agent: &SyncAgent, proposal_index: u64) -> bool {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("is_proving_obsolete"));
+
+    let Some(proposal) = agent.proposals.get(&proposal_index) else {
+        // proposals that are yet to be synchronized may still require proving
+        return proposal_index <= agent.cursor.last_resolved_game;
+    };
+    if proposal.resolved_at != 0 {
+        info!("Discarding persisted proving entry for resolved proposal {proposal_index}.");
+        return true;
+    }
+    let Some(parent) = agent.proposals.get(&proposal.parent) else {
+        return proposal.parent < agent.cursor.last_resolved_game;
+    };
+    if await_tel!(
+        context,
+        parent.fetch_is_successor_validity_proven(&agent.provider.l1_provider)
+    ) {
+        info!(
+            "Discarding persisted proving entry for proposal {proposal_index} in tournament {} \
+                with validity proof.",
+            parent.index
+        );
+        return true;
+    }
+    let proof_status = KailuaTournament::new(parent.contract, &agent.provider.l1_provider)
+        .proofStatus(proposal.signature)
+        .stall_with_context(context.clone(), "KailuaTournament::proofStatus")
+        .await;
+    if proof_status != 0 {
+        info!(
+            "Discarding persisted proving entry for proposal {proposal_index} already proven \
+                {proof_status}."
+        );
+        return true;
+    }
+    false
+}
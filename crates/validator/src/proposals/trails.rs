@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::args::ValidateArgs;
+use crate::proposals::bundle::export_proof_bundle;
 use crate::proposals::dispatch::current_time;
 use alloy::primitives::Address;
 use alloy::providers::Provider;
@@ -27,7 +28,6 @@ use opentelemetry::trace::{TraceContextExt, Tracer};
 use opentelemetry::KeyValue;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
-use std::time::Duration;
 use tracing::{error, info, warn};
 
 #[allow(clippy::too_many_arguments)]
@@ -169,18 +169,37 @@ pub async fn publish_trail_proofs<P: Provider>(
             parent.index
         );
 
-        let transaction_dispatch = parent_contract
-            .proveTrailFault(
+        let transaction = parent_contract.proveTrailFault(
+            validator_address,
+            [child_index, divergence_point],
+            output_fe,
+            blob_commitment,
+            kzg_proof,
+        );
+        // leave the submission to an offline signer
+        if let Some(bundle_dir) = &args.export_bundle_dir {
+            if let Err(err) = export_proof_bundle(
+                bundle_dir,
+                transaction,
+                "KailuaTournament::proveTrailFault",
+                agent,
                 validator_address,
-                [child_index, divergence_point],
-                output_fe,
-                blob_commitment,
-                kzg_proof,
+                "fault_trail",
+                proposal,
             )
-            .timed_transact_with_context(
+            .await
+            {
+                error!("Failed to export trail fault proof bundle: {err:?}");
+                trail_fault_buffer.push((retry_time, proposal_index));
+            }
+            continue;
+        }
+
+        let transaction_dispatch = transaction
+            .replaceable_transact_with_context(
                 context.clone(),
                 "KailuaTournament::proveTrailFault",
-                Some(Duration::from_secs(args.txn_args.txn_timeout)),
+                &args.txn_args,
             )
             .await
             .context("KailuaTournament::proveTrailFault");
@@ -0,0 +1,259 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::args::ValidateArgs;
+use crate::proposals::processing;
+use alloy::primitives::{Address, B256};
+use anyhow::Context;
+use kailua_kona::blobs::hash_to_fe;
+use kailua_sync::agent::{SyncAgent, FINAL_L2_BLOCK_RESOLVED};
+use kailua_sync::await_tel;
+use opentelemetry::global::{meter, tracer};
+use opentelemetry::metrics::Counter;
+use opentelemetry::trace::FutureExt;
+use opentelemetry::trace::{TraceContextExt, Tracer};
+use opentelemetry::KeyValue;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+/// A structured description of an incorrect proposal
+#[derive(Clone, Debug, Serialize)]
+pub struct FaultReport {
+    /// Local index of the proposal
+    pub proposal_index: u64,
+    /// Address of the proposal's contract
+    pub proposal: Address,
+    /// Address of the tournament the proposal was made in
+    pub tournament: Address,
+    /// Address of the proposer
+    pub proposer: Address,
+    /// L2 block number of the proposal's root claim
+    pub l2_block_number: u64,
+    /// One of `output`, `trail` or `parent` (for proposals extending an incorrect parent)
+    pub fault_type: &'static str,
+    /// Index of the first incorrect commitment in the proposal
+    pub divergence_point: Option<u64>,
+    /// L2 block number of the first incorrect output commitment
+    pub divergence_l2_block_number: Option<u64>,
+    /// The locally derived value of the first incorrect commitment
+    pub expected: Option<B256>,
+    /// The value of the first incorrect commitment claimed by the proposal
+    pub claimed: Option<B256>,
+    /// The proof a validator would compute in response to this proposal, if any
+    pub response: Option<&'static str>,
+    /// Whether the proposal's tournament was already resolved
+    pub resolved: bool,
+}
+
+impl FaultReport {
+    /// Describes the fault of the proposal, if it is incorrect
+    pub fn new(agent: &SyncAgent, proposal_index: u64) -> Option<Self> {
+        let proposal = agent.proposals.get(&proposal_index)?;
+        if !proposal.has_parent() || proposal.is_correct() != Some(false) {
+            return None;
+        }
+        let tournament = agent
+            .proposals
+            .get(&proposal.parent)
+            .map(|parent| parent.contract)
+            .unwrap_or_default();
+
+        let mut report = Self {
+            proposal_index,
+            proposal: proposal.contract,
+            tournament,
+            proposer: proposal.proposer,
+            l2_block_number: proposal.output_block_number,
+            fault_type: "parent",
+            divergence_point: None,
+            divergence_l2_block_number: None,
+            expected: None,
+            claimed: None,
+            response: None,
+            resolved: proposal.resolved_at != 0,
+        };
+        let Some(fault) = proposal.fault() else {
+            return Some(report);
+        };
+        let divergence_point = fault.divergence_point();
+        report.divergence_point = Some(divergence_point as u64);
+        let io_count = proposal.io_field_elements.len();
+        if fault.is_trail() {
+            // trail data must be zero
+            report.fault_type = "trail";
+            report.expected = Some(B256::ZERO);
+            report.claimed = proposal
+                .trail_field_elements
+                .get(divergence_point - io_count - 1)
+                .map(|fe| B256::from(*fe));
+        } else if divergence_point < io_count {
+            // intermediate outputs are committed to as field elements
+            let block_number = proposal
+                .output_block_number
+                .saturating_sub(agent.deployment.blocks_per_proposal())
+                + (divergence_point as u64 + 1) * agent.deployment.output_block_span;
+            report.fault_type = "output";
+            report.divergence_l2_block_number = Some(block_number);
+            report.expected = agent
+                .cached_output_at_block(block_number)
+                .map(|output| B256::from(hash_to_fe(output)));
+            report.claimed = Some(B256::from(proposal.io_field_elements[divergence_point]));
+        } else {
+            report.fault_type = "output";
+            report.divergence_l2_block_number = Some(proposal.output_block_number);
+            report.expected = agent.cached_output_at_block(proposal.output_block_number);
+            report.claimed = Some(proposal.output_root);
+        }
+        Some(report)
+    }
+
+    /// Prints the report as a line of JSON and optionally saves it under the report directory
+    pub async fn emit(&self, report_dir: Option<&Path>) -> anyhow::Result<()> {
+        let json = serde_json::to_string(self).context("serde_json::to_string")?;
+        println!("{json}");
+        if let Some(report_dir) = report_dir {
+            let file_name = report_dir.join(format!("fault-{}.json", self.proposal_index));
+            tokio::fs::write(&file_name, json)
+                .await
+                .with_context(|| format!("Failed to write {}", file_name.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Assesses the correctness of all proposals and reports faults without holding any keys or
+/// publishing any transactions
+pub async fn watch_proposals(args: ValidateArgs, data_dir: PathBuf) -> anyhow::Result<()> {
+    // Telemetry
+    let meter = meter("kailua");
+    let meter_fault_count = meter.u64_counter("validator.fault.count").build();
+    let meter_fault_latest = meter.u64_gauge("validator.fault.latest").build();
+    let meter_correct_count = meter.u64_counter("validator.correct.count").build();
+    let meter_correct_latest = meter.u64_gauge("validator.correct.latest").build();
+    let meter_skipped_count = meter.u64_counter("validator.skipped.count").build();
+    let meter_skipped_latest = meter.u64_gauge("validator.skipped.latest").build();
+    let meter_proofs_scheduled = meter.u64_counter("validator.proofs.scheduled").build();
+    let meter_fault_reports = meter.u64_counter("validator.fault.reports").build();
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("watch_proposals"));
+
+    if let Some(report_dir) = &args.fault_report_dir {
+        tokio::fs::create_dir_all(report_dir)
+            .await
+            .context("Failed to create fault report directory")?;
+    }
+
+    // initialize sync agent
+    let mut agent = SyncAgent::new(
+        &args.sync.provider,
+        data_dir,
+        args.sync.kailua_game_implementation,
+        args.sync.kailua_anchor_address,
+        args.proving.bypass_chain_registry,
+    )
+    .await?;
+    info!("KailuaTreasury({:?})", agent.deployment.treasury);
+    warn!("Running in watch-only mode. No proofs will be computed or published.");
+
+    // Run the watcher loop
+    info!(
+        "Starting from proposal at factory index {}",
+        agent.cursor.next_factory_index
+    );
+    loop {
+        // Wait for new data on every iteration
+        sleep(Duration::from_secs(1)).await;
+        // fetch latest games
+        let loaded_proposals = match await_tel!(
+            context,
+            agent.sync(args.sync.provider.op_rpc_delay, args.sync.final_l2_block)
+        )
+        .context("SyncAgent::sync")
+        {
+            Ok(result) => result,
+            Err(err) => {
+                if err
+                    .root_cause()
+                    .to_string()
+                    .contains(FINAL_L2_BLOCK_RESOLVED)
+                {
+                    warn!("watch_proposals terminated");
+                    return Ok(());
+                }
+                error!("Synchronization error: {err:?}");
+                vec![]
+            }
+        };
+
+        // determine the responses a validator would queue for the new proposals
+        let mut proposal_validity_buffer = BinaryHeap::new();
+        let mut output_fault_buffer = BinaryHeap::new();
+        let mut trail_fault_buffer = BinaryHeap::new();
+        processing::process_proposals(
+            &args,
+            &mut agent,
+            &loaded_proposals,
+            &meter_correct_count,
+            &meter_correct_latest,
+            &meter_fault_count,
+            &meter_fault_latest,
+            &meter_skipped_count,
+            &meter_skipped_latest,
+            &meter_proofs_scheduled,
+            &mut proposal_validity_buffer,
+            &mut output_fault_buffer,
+            &mut trail_fault_buffer,
+        )
+        .await;
+
+        // report faulty proposals
+        for proposal_index in &loaded_proposals {
+            let Some(mut report) = FaultReport::new(&agent, *proposal_index) else {
+                continue;
+            };
+            let is_queued = |buffer: &BinaryHeap<(Reverse<u64>, u64)>, index: u64| {
+                buffer.iter().any(|(_, p)| *p == index)
+            };
+            let successor = agent
+                .proposals
+                .get(proposal_index)
+                .and_then(|proposal| agent.proposals.get(&proposal.parent))
+                .and_then(|parent| parent.successor);
+            report.response = if is_queued(&output_fault_buffer, *proposal_index) {
+                Some("output_fault_proof")
+            } else if is_queued(&trail_fault_buffer, *proposal_index) {
+                Some("trail_fault_proof")
+            } else if successor.is_some_and(|p| is_queued(&proposal_validity_buffer, p)) {
+                Some("validity_proof")
+            } else {
+                None
+            };
+            meter_fault_reports.add(
+                1,
+                &[
+                    KeyValue::new("proposal", report.proposal.to_string()),
+                    KeyValue::new("type", report.fault_type),
+                ],
+            );
+            if let Err(err) = report.emit(args.fault_report_dir.as_deref()).await {
+                error!("Failed to emit fault report for proposal {proposal_index}: {err:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,510 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::args::ValidateArgs;
+use crate::channel::{DuplexChannel, Message};
+use crate::proposals::dispatch::current_time;
+use crate::proposals::policy::deadline_proposal;
+use crate::store::ValidatorStore;
+use alloy::primitives::U256;
+use kailua_prover::risczero::boundless::BoundlessArgs;
+use kailua_sync::agent::SyncAgent;
+use kailua_sync::await_tel;
+use opentelemetry::global::{meter, tracer};
+use opentelemetry::metrics::{Counter, Gauge};
+use opentelemetry::trace::FutureExt;
+use opentelemetry::trace::{TraceContextExt, Tracer};
+use opentelemetry::KeyValue;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// Number of recent proof latencies considered when estimating the duration of a proof
+const LATENCY_SAMPLES: usize = 16;
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct WatchdogArgs {
+    /// Seconds between checks of the time left to prove faults before their deadline
+    #[clap(long, env, required = false, default_value_t = 60)]
+    pub deadline_check_interval: u64,
+    /// Seconds of slack between expected proof completion and the challenge deadline below
+    /// which to warn
+    #[clap(long, env, required = false, default_value_t = 21600)]
+    pub deadline_warning_slack: u64,
+    /// Seconds of slack between expected proof completion and the challenge deadline below
+    /// which the deadline is at risk and proving is escalated
+    #[clap(long, env, required = false, default_value_t = 3600)]
+    pub deadline_critical_slack: u64,
+    /// Seconds a proof is expected to take before any proof latencies were observed
+    #[clap(long, env, required = false, default_value_t = 3600)]
+    pub expected_proof_latency: u64,
+    /// Whether to reserve the configured Boundless market for proofs at risk of missing their
+    /// deadline, proving all others using Bonsai or the local zkVM
+    #[clap(
+        long,
+        env,
+        default_value_t = false,
+        conflicts_with = "coordinator_socket_addr"
+    )]
+    pub escalate_to_boundless: bool,
+    /// Maximum price (wei) per cycle of Boundless orders for proofs at risk of missing their
+    /// deadline
+    #[clap(
+        long,
+        env,
+        required = false,
+        conflicts_with = "coordinator_socket_addr"
+    )]
+    pub escalation_cycle_max_wei: Option<U256>,
+}
+
+impl WatchdogArgs {
+    /// Returns whether proofs at risk of missing their deadline should be escalated
+    pub fn is_escalation_enabled(&self) -> bool {
+        self.escalate_to_boundless || self.escalation_cycle_max_wei.is_some()
+    }
+
+    /// Returns the Boundless configuration to prove escalated tasks with, if any
+    pub fn escalation_boundless(&self, boundless: &BoundlessArgs) -> Option<BoundlessArgs> {
+        if !self.is_escalation_enabled() || boundless.storage.is_none() {
+            return None;
+        }
+        let mut boundless = boundless.clone();
+        let market = boundless.market.as_mut()?;
+        if let Some(cycle_max_wei) = self.escalation_cycle_max_wei {
+            market.boundless_cycle_max_wei = cycle_max_wei;
+        }
+        Some(boundless)
+    }
+
+    /// Returns the Boundless configuration to prove tasks that were not escalated with
+    pub fn task_boundless(&self, boundless: &BoundlessArgs) -> BoundlessArgs {
+        if self.escalate_to_boundless {
+            BoundlessArgs {
+                market: None,
+                ..boundless.clone()
+            }
+        } else {
+            boundless.clone()
+        }
+    }
+}
+
+/// How close a proof is to missing the challenge deadline of its proposal
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DeadlineRisk {
+    /// The proof is expected to complete well before the deadline
+    Safe,
+    /// The slack before the deadline fell below the warning threshold
+    Warning,
+    /// The slack before the deadline fell below the critical threshold
+    Critical,
+    /// The deadline has passed
+    Missed,
+}
+
+impl DeadlineRisk {
+    /// Classifies the slack left between the expected completion of a proof and the end of the
+    /// challenge window
+    pub fn assess(challenger_duration: u64, slack: i64, args: &WatchdogArgs) -> Self {
+        if challenger_duration == 0 {
+            DeadlineRisk::Missed
+        } else if slack < args.deadline_critical_slack as i64 {
+            DeadlineRisk::Critical
+        } else if slack < args.deadline_warning_slack as i64 {
+            DeadlineRisk::Warning
+        } else {
+            DeadlineRisk::Safe
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeadlineRisk::Safe => "safe",
+            DeadlineRisk::Warning => "warning",
+            DeadlineRisk::Critical => "critical",
+            DeadlineRisk::Missed => "missed",
+        }
+    }
+}
+
+impl Display for DeadlineRisk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The most recent proof latencies observed for a proving backend
+#[derive(Debug, Default)]
+struct LatencyHistory {
+    samples: VecDeque<u64>,
+}
+
+impl LatencyHistory {
+    /// Restores the history from the most recent of the given samples
+    fn from_samples(samples: Vec<u64>) -> Self {
+        let skip = samples.len().saturating_sub(LATENCY_SAMPLES);
+        Self {
+            samples: samples.into_iter().skip(skip).collect(),
+        }
+    }
+
+    fn record(&mut self, latency: u64) {
+        if self.samples.len() == LATENCY_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+    }
+
+    /// Conservatively estimates the latency of the next proof as the slowest recent one
+    fn estimate(&self, default: u64) -> u64 {
+        self.samples.iter().max().copied().unwrap_or(default)
+    }
+}
+
+/// A proof request handed over to the provers
+#[derive(Debug)]
+struct PendingProof {
+    dispatched_at: Instant,
+    escalated: bool,
+}
+
+/// Compares the remaining challenge window of proposals awaiting proofs against the expected
+/// proving latency, alerting and escalating proving when a deadline is at risk
+#[derive(Debug)]
+pub struct DeadlineWatchdog {
+    pending: BTreeMap<u64, PendingProof>,
+    escalated: BTreeSet<u64>,
+    risk: BTreeMap<u64, DeadlineRisk>,
+    latency: LatencyHistory,
+    escalated_latency: LatencyHistory,
+    last_check: Option<Instant>,
+    store: Option<Arc<ValidatorStore>>,
+    meter_slack: Gauge<i64>,
+    meter_alerts: Counter<u64>,
+    meter_escalations: Counter<u64>,
+}
+
+impl Default for DeadlineWatchdog {
+    fn default() -> Self {
+        let meter = meter("kailua");
+        Self {
+            pending: Default::default(),
+            escalated: Default::default(),
+            risk: Default::default(),
+            latency: Default::default(),
+            escalated_latency: Default::default(),
+            last_check: None,
+            store: None,
+            meter_slack: meter.i64_gauge("validator.deadline.slack").build(),
+            meter_alerts: meter.u64_counter("validator.deadline.alerts").build(),
+            meter_escalations: meter.u64_counter("validator.deadline.escalations").build(),
+        }
+    }
+}
+
+impl DeadlineWatchdog {
+    /// Returns a watchdog that persists the proof latencies it observes, seeded with those
+    /// observed before the last restart
+    pub fn with_store(store: Arc<ValidatorStore>) -> Self {
+        let mut watchdog = Self::default();
+        for escalated in [false, true] {
+            match store.load_latencies(escalated) {
+                Ok(samples) if escalated => {
+                    watchdog.escalated_latency = LatencyHistory::from_samples(samples)
+                }
+                Ok(samples) => watchdog.latency = LatencyHistory::from_samples(samples),
+                Err(err) => error!("Failed to load proof latencies: {err:?}"),
+            }
+        }
+        watchdog.store = Some(store);
+        watchdog
+    }
+
+    /// Starts timing the proof requested for the proposal
+    pub fn on_dispatch(&mut self, proposal_index: u64) {
+        self.pending.insert(
+            proposal_index,
+            PendingProof {
+                dispatched_at: Instant::now(),
+                escalated: self.escalated.contains(&proposal_index),
+            },
+        );
+    }
+
+    /// Stops timing the proof request of the proposal, recording its latency if it was computed
+    pub fn on_proof(&mut self, proposal_index: u64, is_computed: bool) {
+        self.risk.remove(&proposal_index);
+        let Some(pending) = self.pending.remove(&proposal_index) else {
+            return;
+        };
+        if !is_computed {
+            return;
+        }
+        let latency = pending.dispatched_at.elapsed().as_secs();
+        info!("Proof for proposal {proposal_index} took {latency} seconds.");
+        let history = if pending.escalated {
+            &mut self.escalated_latency
+        } else {
+            &mut self.latency
+        };
+        history.record(latency);
+        if let Some(store) = &self.store {
+            if let Err(err) = store.put_latencies(pending.escalated, &history.samples) {
+                error!("Failed to persist proof latencies: {err:?}");
+            }
+        }
+    }
+
+    /// Returns the expected number of seconds to compute a proof
+    fn expected_latency(&self, args: &ValidateArgs, escalated: bool) -> u64 {
+        let default = args.watchdog.expected_proof_latency;
+        if escalated {
+            self.escalated_latency.estimate(default)
+        } else {
+            self.latency.estimate(default)
+        }
+    }
+
+    /// Checks the deadlines of all queued and ongoing proof requests
+    pub async fn check_deadlines(
+        &mut self,
+        args: &ValidateArgs,
+        agent: &SyncAgent,
+        proposal_validity_buffer: &mut BinaryHeap<(Reverse<u64>, u64)>,
+        output_fault_buffer: &mut BinaryHeap<(Reverse<u64>, u64)>,
+        channel: &mut DuplexChannel<Message>,
+    ) {
+        let tracer = tracer("kailua");
+        let context = opentelemetry::Context::current_with_span(tracer.start("check_deadlines"));
+
+        if self.last_check.is_some_and(|instant| {
+            instant.elapsed() < Duration::from_secs(args.watchdog.deadline_check_interval)
+        }) {
+            return;
+        }
+        self.last_check = Some(Instant::now());
+
+        // Discard requests for proposals that no longer require proving
+        let is_unresolved = |index: &u64| {
+            agent
+                .proposals
+                .get(index)
+                .is_some_and(|proposal| proposal.resolved_at == 0)
+        };
+        self.pending.retain(|index, _| is_unresolved(index));
+        self.escalated.retain(is_unresolved);
+
+        // Collect the queued (with dispatch time) and ongoing proof requests
+        let watched = proposal_validity_buffer
+            .iter()
+            .chain(output_fault_buffer.iter())
+            .map(|(time, index)| (*index, Some(time.0)))
+            .chain(self.pending.keys().map(|index| (*index, None)))
+            .collect::<BTreeMap<_, _>>();
+        self.risk.retain(|index, _| watched.contains_key(index));
+        if watched.is_empty() {
+            return;
+        }
+
+        let current_timestamp = current_time();
+
+        for (proposal_index, dispatch_time) in watched {
+            let Some(proposal) = agent.proposals.get(&proposal_index) else {
+                continue;
+            };
+            if proposal.resolved_at != 0 {
+                continue;
+            }
+            // Nothing is at stake if the proof only finalizes an undisputed proposal early
+            let target = deadline_proposal(agent, proposal);
+            if target.is_correct().unwrap_or_default() {
+                continue;
+            }
+            let challenger_duration = await_tel!(
+                context,
+                target.fetch_current_challenger_duration(&agent.provider.l1_provider)
+            );
+
+            // Estimate how long until the proof is computed
+            let escalated = self.escalated.contains(&proposal_index);
+            let expected_latency = self.expected_latency(args, escalated);
+            let remaining_latency = match (dispatch_time, self.pending.get(&proposal_index)) {
+                (Some(dispatch_time), _) => {
+                    dispatch_time.saturating_sub(current_timestamp) + expected_latency
+                }
+                (None, Some(pending)) => {
+                    expected_latency.saturating_sub(pending.dispatched_at.elapsed().as_secs())
+                }
+                (None, None) => expected_latency,
+            };
+            let slack = challenger_duration as i64 - remaining_latency as i64;
+            let risk = DeadlineRisk::assess(challenger_duration, slack, &args.watchdog);
+            self.meter_slack.record(
+                slack,
+                &[
+                    KeyValue::new("proposal", proposal.contract.to_string()),
+                    KeyValue::new("deadline", target.contract.to_string()),
+                ],
+            );
+
+            // Alert whenever the risk level rises
+            let previous_risk = self
+                .risk
+                .insert(proposal_index, risk)
+                .unwrap_or(DeadlineRisk::Safe);
+            if risk > previous_risk {
+                let message = format!(
+                    "Proof for proposal {proposal_index} expected in {remaining_latency} seconds \
+                        with {challenger_duration} seconds left in the challenge window of \
+                        proposal {} (risk={risk}).",
+                    target.index
+                );
+                match risk {
+                    DeadlineRisk::Safe => {}
+                    DeadlineRisk::Warning => warn!("{message}"),
+                    DeadlineRisk::Critical | DeadlineRisk::Missed => error!("{message}"),
+                }
+                self.meter_alerts.add(
+                    1,
+                    &[
+                        KeyValue::new("proposal", proposal.contract.to_string()),
+                        KeyValue::new("risk", risk.as_str()),
+                    ],
+                );
+            }
+            if risk != DeadlineRisk::Critical {
+                continue;
+            }
+
+            // Stop waiting to dispatch queued requests at risk
+            if dispatch_time.is_some_and(|time| current_timestamp < time) {
+                info!("Dispatching proof request for proposal {proposal_index} immediately.");
+                for buffer in [&mut *proposal_validity_buffer, &mut *output_fault_buffer] {
+                    if buffer.iter().any(|(_, index)| *index == proposal_index) {
+                        buffer.retain(|(_, index)| *index != proposal_index);
+                        buffer.push((Reverse(current_timestamp), proposal_index));
+                    }
+                }
+            }
+            // Switch the proving backend
+            if !args.watchdog.is_escalation_enabled() || escalated {
+                continue;
+            }
+            if let Err(err) = channel.sender.send(Message::Escalate(proposal_index)).await {
+                error!("Failed to escalate proof request for proposal {proposal_index}: {err:?}");
+                continue;
+            }
+            warn!("Escalated proof request for proposal {proposal_index}.");
+            self.escalated.insert(proposal_index);
+            if let Some(pending) = self.pending.get_mut(&proposal_index) {
+                pending.dispatched_at = Instant::now();
+                pending.escalated = true;
+            }
+            self.meter_escalations.add(
+                1,
+                &[KeyValue::new("proposal", proposal.contract.to_string())],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn watchdog_args() -> WatchdogArgs {
+        WatchdogArgs {
+            deadline_warning_slack: 600,
+            deadline_critical_slack: 60,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_deadline_risk() {
+        let args = watchdog_args();
+        assert_eq!(DeadlineRisk::assess(1000, 600, &args), DeadlineRisk::Safe);
+        assert_eq!(
+            DeadlineRisk::assess(1000, 599, &args),
+            DeadlineRisk::Warning
+        );
+        assert_eq!(DeadlineRisk::assess(1000, 60, &args), DeadlineRisk::Warning);
+        assert_eq!(
+            DeadlineRisk::assess(1000, 59, &args),
+            DeadlineRisk::Critical
+        );
+        assert_eq!(
+            DeadlineRisk::assess(1000, -10, &args),
+            DeadlineRisk::Critical
+        );
+        // an expired window is missed regardless of the slack
+        assert_eq!(DeadlineRisk::assess(0, 1000, &args), DeadlineRisk::Missed);
+        // risks are ordered by severity
+        assert!(DeadlineRisk::Safe < DeadlineRisk::Warning);
+        assert!(DeadlineRisk::Warning < DeadlineRisk::Critical);
+        assert!(DeadlineRisk::Critical < DeadlineRisk::Missed);
+    }
+
+    #[test]
+    fn test_latency_history() {
+        let mut history = LatencyHistory::default();
+        assert_eq!(history.estimate(3600), 3600);
+        history.record(100);
+        history.record(300);
+        history.record(200);
+        assert_eq!(history.estimate(3600), 300);
+        // only the most recent samples are considered
+        for _ in 0..LATENCY_SAMPLES {
+            history.record(50);
+        }
+        assert_eq!(history.samples.len(), LATENCY_SAMPLES);
+        assert_eq!(history.estimate(3600), 50);
+        let restored = LatencyHistory::from_samples((0..2 * LATENCY_SAMPLES as u64).collect());
+        assert_eq!(restored.samples.len(), LATENCY_SAMPLES);
+        assert_eq!(restored.samples.front(), Some(&(LATENCY_SAMPLES as u64)));
+    }
+
+    #[test]
+    fn test_persisted_latencies() {
+        let dir = tempdir().unwrap();
+        let store = Arc::new(ValidatorStore::open(dir.path()).unwrap());
+        store.put_latencies(false, &[500, 700]).unwrap();
+
+        // persisted latencies seed the estimates
+        let mut watchdog = DeadlineWatchdog::with_store(store.clone());
+        assert_eq!(watchdog.latency.estimate(3600), 700);
+        assert_eq!(watchdog.escalated_latency.estimate(3600), 3600);
+
+        // observed latencies are persisted by backend
+        watchdog.on_dispatch(1);
+        watchdog.on_proof(1, true);
+        watchdog.escalated.insert(2);
+        watchdog.on_dispatch(2);
+        watchdog.on_proof(2, true);
+        // proofs that were not computed are not timed
+        watchdog.on_dispatch(3);
+        watchdog.on_proof(3, false);
+        assert_eq!(store.load_latencies(false).unwrap(), vec![500, 700, 0]);
+        assert_eq!(store.load_latencies(true).unwrap(), vec![0]);
+
+        let watchdog = DeadlineWatchdog::with_store(store);
+        assert_eq!(watchdog.latency.samples.len(), 3);
+        assert_eq!(watchdog.escalated_latency.estimate(3600), 0);
+    }
+}
@@ -14,7 +14,9 @@
 
 use crate::args::ValidateArgs;
 use crate::channel::{DuplexChannel, Message};
-use crate::tasks::{handle_proving_tasks, Task};
+use crate::coordinator::serve_proving_queue;
+use crate::store::ValidatorStore;
+use crate::tasks::{handle_proving_tasks, Task, TaskEscalation};
 use alloy::eips::eip4844::IndexedBlobHash;
 use alloy::network::primitives::HeaderResponse;
 use alloy::network::{BlockResponse, TxSigner};
@@ -26,17 +28,21 @@ use kailua_kona::journal::ProofJournal;
 use kailua_kona::precondition::PreconditionValidationData;
 use kailua_prover::args::{ProveArgs, ProvingArgs};
 use kailua_prover::channel::AsyncChannel;
-use kailua_prover::proof::proof_file_name;
+use kailua_prover::store::ProofStore;
 use kailua_sync::agent::SyncAgent;
 use kailua_sync::proposal::Proposal;
 use kailua_sync::provider::optimism::fetch_rollup_config;
+use kailua_sync::provider::primary_url;
 use kailua_sync::transact::rpc::{get_block_by_number, get_next_block};
 use kailua_sync::{await_tel, await_tel_res};
 use kona_protocol::BlockInfo;
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use std::collections::BTreeSet;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::spawn;
+use tokio::sync::watch;
 use tracing::{debug, error, info, warn};
 
 pub async fn handle_proof_requests(
@@ -44,6 +50,7 @@ pub async fn handle_proof_requests(
     args: ValidateArgs,
     verbosity: u8,
     data_dir: PathBuf,
+    store: Option<Arc<ValidatorStore>>,
 ) -> anyhow::Result<()> {
     // Telemetry
     let tracer = tracer("kailua");
@@ -63,36 +70,77 @@ pub async fn handle_proof_requests(
     let config_hash = B256::from(config_hash(&rollup_config)?);
     let raw_image_id = args.proving.image_id();
     let fpvm_image_id = B256::from(bytemuck::cast::<[u32; 8], [u8; 32]>(raw_image_id));
-    // Set payout recipient
-    let validator_wallet = await_tel_res!(
-        context,
-        tracer,
-        "ValidatorSigner::wallet",
-        args.validator_signer
-            .wallet(Some(rollup_config.l1_chain_id))
-    )?;
-    let payout_recipient = args
-        .proving
-        .payout_recipient_address
-        .unwrap_or_else(|| validator_wallet.default_signer().address());
+    // Set payout recipient, falling back to the bundle sender when signing offline
+    let payout_recipient = match args.proving.payout_recipient_address.or(args.bundle_sender) {
+        Some(payout_recipient) => payout_recipient,
+        None => await_tel_res!(
+            context,
+            tracer,
+            "ValidatorSigner::wallet",
+            args.validator_signer
+                .wallet(Some(rollup_config.l1_chain_id))
+        )?
+        .default_signer()
+        .address(),
+    };
     info!("Proof payout recipient: {payout_recipient}");
 
+    // Set up the backend for proofs at risk of missing their deadline
+    let (escalation_sender, escalated) = watch::channel(BTreeSet::new());
+    let escalation = TaskEscalation {
+        escalated,
+        boundless: args.watchdog.escalation_boundless(&args.boundless),
+    };
+    if args.watchdog.is_escalation_enabled() && escalation.boundless.is_none() {
+        warn!("Proof escalation requires a Boundless market and storage provider configuration.");
+    }
+
     let task_channel: AsyncChannel<Task> = async_channel::unbounded();
     let mut proving_handlers = vec![];
-    // instantiate worker pool
-    for _ in 0..args.num_concurrent_provers {
-        proving_handlers.push(spawn(handle_proving_tasks(
-            args.kailua_cli.clone(),
-            task_channel.clone(),
-            channel.sender.clone(),
-            verbosity,
-        )));
+    if args.coordinator.coordinator_socket_addr.is_some() {
+        // serve tasks to remote workers
+        proving_handlers.push(
+            serve_proving_queue(
+                args.coordinator.clone(),
+                task_channel.clone(),
+                channel.sender.clone(),
+            )
+            .await
+            .context("serve_proving_queue")?,
+        );
+    } else {
+        // instantiate worker pool
+        for _ in 0..args.num_concurrent_provers {
+            proving_handlers.push(spawn(handle_proving_tasks(
+                args.kailua_cli.clone(),
+                task_channel.clone(),
+                channel.sender.clone(),
+                verbosity,
+                escalation.clone(),
+            )));
+        }
     }
 
     // Run task generator loop
     loop {
         // Dequeue messages
-        let Some(Message::Proposal {
+        let Some(message) = channel.receiver.recv().await else {
+            // The channel was closed because the handle_proposals loop ended
+            break;
+        };
+        // Switch the proving backend of tasks at risk
+        if let Message::Escalate(proposal_index) = message {
+            if escalation.boundless.is_none() {
+                warn!("No escalation backend available for local index {proposal_index}.");
+            } else {
+                info!("Escalating proving task for local index {proposal_index}.");
+                escalation_sender.send_modify(|escalated| {
+                    escalated.insert(proposal_index);
+                });
+            }
+            continue;
+        }
+        let Message::Proposal {
             index: proposal_index,
             precondition_validation_data,
             l1_head,
@@ -100,13 +148,13 @@ pub async fn handle_proof_requests(
             agreed_l2_output_root,
             claimed_l2_block_number,
             claimed_l2_output_root,
-        }) = channel.receiver.recv().await
+        } = message.clone()
         else {
-            // The channel was closed because the handle_proposals loop ended
-            break;
+            error!("Proof request handler received an unexpected message.");
+            continue;
         };
         info!("Processing proof for local index {proposal_index}.");
-        // Compute proof file name
+        // Compute stored proof name
         let precondition_hash = precondition_validation_data
             .as_ref()
             .map(|d| d.precondition_hash())
@@ -121,7 +169,13 @@ pub async fn handle_proof_requests(
             config_hash,
             fpvm_image_id,
         };
-        let file_name = proof_file_name(raw_image_id, &proof_journal);
+        let file_name = ProofStore::key(raw_image_id, &proof_journal);
+        // Persist the task until its proof is received by the proposal handler
+        if let Some(store) = &store {
+            if let Err(err) = store.put_proving_task(&file_name, &message) {
+                error!("Failed to persist proving task for local index {proposal_index}: {err:?}");
+            }
+        }
         // Prepare proving args
         let (precondition_params, precondition_block_hashes, precondition_blob_hashes) =
             precondition_validation_data
@@ -157,9 +211,11 @@ pub async fn handle_proof_requests(
                 agreed_l2_output_root,
                 claimed_l2_output_root,
                 claimed_l2_block_number,
-                l2_node_address: Some(args.sync.provider.op_geth_url.clone()),
-                l1_node_address: Some(args.sync.provider.eth_rpc_url.clone()),
-                l1_beacon_address: Some(args.sync.provider.beacon_rpc_url.clone()),
+                l2_node_address: Some(primary_url(&args.sync.provider.op_geth_url).to_string()),
+                l1_node_address: Some(primary_url(&args.sync.provider.eth_rpc_url).to_string()),
+                l1_beacon_address: Some(
+                    primary_url(&args.sync.provider.beacon_rpc_url).to_string(),
+                ),
                 data_dir: Some(data_dir),
                 native: true,
                 server: false,
@@ -167,12 +223,12 @@ pub async fn handle_proof_requests(
                 rollup_config_path: None,
                 enable_experimental_witness_endpoint: args.enable_experimental_witness_endpoint,
             },
-            op_node_address: Some(args.sync.provider.op_node_url.clone()),
+            op_node_address: Some(primary_url(&args.sync.provider.op_node_url).to_string()),
             proving: ProvingArgs {
                 payout_recipient_address: Some(payout_recipient),
                 ..args.proving.clone()
             },
-            boundless: args.boundless.clone(),
+            boundless: args.watchdog.task_boundless(&args.boundless),
             precondition_params,
             precondition_block_hashes,
             precondition_blob_hashes,
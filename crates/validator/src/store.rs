@@ -0,0 +1,624 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::channel::Message;
+use alloy::primitives::{keccak256, Address, B256};
+use anyhow::{bail, Context};
+use rocksdb::{ColumnFamily, ColumnFamilyDescriptor, IteratorMode, WriteBatch};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::path::Path;
+use tracing::warn;
+
+/// Version of the persisted validator queue layout
+pub const VALIDATOR_STORE_VERSION: u32 = 1;
+
+/// Column family of store metadata
+const CF_META: &str = "meta";
+/// Column family of (dispatch time, proposal index) validity proof requests
+const CF_VALIDITY_QUEUE: &str = "validity_queue";
+/// Column family of (dispatch time, proposal index) output fault proof requests
+const CF_OUTPUT_FAULT_QUEUE: &str = "output_fault_queue";
+/// Column family of (dispatch time, proposal index) trail fault proof submissions
+const CF_TRAIL_FAULT_QUEUE: &str = "trail_fault_queue";
+/// Column family of (proposal index -> l1 block number) entries of the last l1 head proven with
+const CF_LAST_PROOF_L1_HEAD: &str = "last_proof_l1_head";
+/// Column family of (proposal index, journal hash) -> proof message entries awaiting publication
+const CF_COMPUTED_PROOFS: &str = "computed_proofs";
+/// Column family of (proposal index, proof file name) -> proposal message entries being proven
+const CF_PROVING_TASKS: &str = "proving_tasks";
+/// Column families holding validator queues that are discarded together
+const QUEUE_COLUMN_FAMILIES: [&str; 6] = [
+    CF_VALIDITY_QUEUE,
+    CF_OUTPUT_FAULT_QUEUE,
+    CF_TRAIL_FAULT_QUEUE,
+    CF_LAST_PROOF_L1_HEAD,
+    CF_COMPUTED_PROOFS,
+    CF_PROVING_TASKS,
+];
+
+const VERSION_KEY: &[u8] = b"version";
+const TREASURY_KEY: &[u8] = b"treasury";
+const LATENCY_KEY: &[u8] = b"proof_latency";
+const ESCALATED_LATENCY_KEY: &[u8] = b"escalated_proof_latency";
+
+/// The validator queues restored from storage
+#[derive(Debug, Default)]
+pub struct ValidatorQueues {
+    /// Scheduled validity proof requests
+    pub proposal_validity_buffer: BinaryHeap<(Reverse<u64>, u64)>,
+    /// Scheduled output fault proof requests
+    pub output_fault_buffer: BinaryHeap<(Reverse<u64>, u64)>,
+    /// Scheduled trail fault proof submissions
+    pub trail_fault_buffer: BinaryHeap<(Reverse<u64>, u64)>,
+    /// Computed proofs awaiting publication
+    pub computed_proof_buffer: VecDeque<Message>,
+    /// Last l1 head block number used to prove each proposal
+    pub last_proof_l1_head: BTreeMap<u64, u64>,
+    /// Proof requests handed over to the provers but not yet fulfilled
+    pub proving_tasks: Vec<Message>,
+}
+
+/// The contents of the proving schedules, used to persist only the changes made to them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScheduleSnapshot {
+    /// Scheduled validity proof requests
+    pub proposal_validity: BTreeSet<(u64, u64)>,
+    /// Scheduled output fault proof requests
+    pub output_fault: BTreeSet<(u64, u64)>,
+    /// Scheduled trail fault proof submissions
+    pub trail_fault: BTreeSet<(u64, u64)>,
+    /// Last l1 head block number used to prove each proposal
+    pub last_proof_l1_head: BTreeMap<u64, u64>,
+}
+
+impl ScheduleSnapshot {
+    pub fn new(
+        proposal_validity_buffer: &BinaryHeap<(Reverse<u64>, u64)>,
+        output_fault_buffer: &BinaryHeap<(Reverse<u64>, u64)>,
+        trail_fault_buffer: &BinaryHeap<(Reverse<u64>, u64)>,
+        last_proof_l1_head: &BTreeMap<u64, u64>,
+    ) -> Self {
+        let entries = |buffer: &BinaryHeap<(Reverse<u64>, u64)>| {
+            buffer
+                .iter()
+                .map(|(time, index)| (time.0, *index))
+                .collect()
+        };
+        Self {
+            proposal_validity: entries(proposal_validity_buffer),
+            output_fault: entries(output_fault_buffer),
+            trail_fault: entries(trail_fault_buffer),
+            last_proof_l1_head: last_proof_l1_head.clone(),
+        }
+    }
+}
+
+/// Returns the storage key of a computed proof, or None if the message is not a proof
+pub fn computed_proof_key(message: &Message) -> Option<Vec<u8>> {
+    let Message::Proof(index, receipt) = message else {
+        return None;
+    };
+    let journal_hash = receipt
+        .as_ref()
+        .map(|receipt| keccak256(&receipt.journal.bytes))
+        .unwrap_or(B256::ZERO);
+    Some([index.to_be_bytes().as_slice(), journal_hash.as_slice()].concat())
+}
+
+/// Returns the storage keys of all computed proofs in the buffer
+pub fn computed_proof_keys(computed_proof_buffer: &VecDeque<Message>) -> BTreeSet<Vec<u8>> {
+    computed_proof_buffer
+        .iter()
+        .filter_map(computed_proof_key)
+        .collect()
+}
+
+/// Local persistent storage of the validator's proving queues
+pub struct ValidatorStore {
+    /// Local persistent key-value store
+    pub db: rocksdb::DB,
+}
+
+impl ValidatorStore {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let mut options = rocksdb::Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        let column_families = [CF_META]
+            .into_iter()
+            .chain(QUEUE_COLUMN_FAMILIES)
+            .map(|name| ColumnFamilyDescriptor::new(name, rocksdb::Options::default()));
+        let db = rocksdb::DB::open_cf_descriptors(&options, path, column_families)
+            .context("rocksdb::DB::open_cf_descriptors")?;
+        let store = Self { db };
+        store.check_version()?;
+        Ok(store)
+    }
+
+    fn cf(&self, name: &str) -> anyhow::Result<&ColumnFamily> {
+        self.db
+            .cf_handle(name)
+            .with_context(|| format!("Column family {name} missing."))
+    }
+
+    /// Discards persisted queues written using a different layout version
+    fn check_version(&self) -> anyhow::Result<()> {
+        let meta = self.cf(CF_META)?;
+        let version = match self
+            .db
+            .get_cf(meta, VERSION_KEY)
+            .context("rocksdb::DB::get_cf")?
+        {
+            Some(value) => Some(u32::from_be_bytes(value.as_slice().try_into()?)),
+            None => None,
+        };
+        if version == Some(VALIDATOR_STORE_VERSION) {
+            return Ok(());
+        }
+        if let Some(version) = version {
+            warn!("Discarding persisted validator queues of unsupported version {version}.");
+        }
+        let mut batch = WriteBatch::default();
+        self.clear_queues(&mut batch)?;
+        batch.put_cf(meta, VERSION_KEY, VALIDATOR_STORE_VERSION.to_be_bytes());
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+
+    /// Discards persisted queues that belong to a different treasury than the given one
+    pub fn bind_treasury(&self, treasury: Address) -> anyhow::Result<()> {
+        let meta = self.cf(CF_META)?;
+        let stored = self
+            .db
+            .get_cf(meta, TREASURY_KEY)
+            .context("rocksdb::DB::get_cf")?;
+        if stored.as_deref() == Some(treasury.as_slice()) {
+            return Ok(());
+        }
+        if let Some(stored) = stored {
+            warn!(
+                "Discarding persisted validator queues of treasury {}.",
+                Address::try_from(stored.as_slice()).unwrap_or_default()
+            );
+        }
+        let mut batch = WriteBatch::default();
+        self.clear_queues(&mut batch)?;
+        batch.put_cf(meta, TREASURY_KEY, treasury.as_slice());
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+
+    /// Queues the deletion of all persisted queues
+    fn clear_queues(&self, batch: &mut WriteBatch) -> anyhow::Result<()> {
+        for name in QUEUE_COLUMN_FAMILIES {
+            self.clear_cf(batch, name)?;
+        }
+        Ok(())
+    }
+
+    /// Queues the deletion of all entries in the column family
+    fn clear_cf(&self, batch: &mut WriteBatch, name: &str) -> anyhow::Result<()> {
+        let cf = self.cf(name)?;
+        for entry in self.db.iterator_cf(cf, IteratorMode::Start) {
+            let (key, _) = entry.context("rocksdb::DB::iterator_cf")?;
+            batch.delete_cf(cf, key);
+        }
+        Ok(())
+    }
+
+    /// Queues the changes made to the schedule since it was last persisted
+    fn write_schedule(
+        &self,
+        batch: &mut WriteBatch,
+        name: &str,
+        persisted: &BTreeSet<(u64, u64)>,
+        current: &BTreeSet<(u64, u64)>,
+    ) -> anyhow::Result<()> {
+        let cf = self.cf(name)?;
+        for (time, index) in persisted.difference(current) {
+            batch.delete_cf(cf, [time.to_be_bytes(), index.to_be_bytes()].concat());
+        }
+        for (time, index) in current.difference(persisted) {
+            batch.put_cf(cf, [time.to_be_bytes(), index.to_be_bytes()].concat(), b"");
+        }
+        Ok(())
+    }
+
+    fn read_schedule(&self, name: &str) -> anyhow::Result<BinaryHeap<(Reverse<u64>, u64)>> {
+        let mut buffer = BinaryHeap::new();
+        for entry in self.db.iterator_cf(self.cf(name)?, IteratorMode::Start) {
+            let (key, _) = entry.context("rocksdb::DB::iterator_cf")?;
+            if key.len() != 16 {
+                bail!("Malformed {name} entry in storage.");
+            }
+            buffer.push((
+                Reverse(u64::from_be_bytes(key[..8].try_into()?)),
+                u64::from_be_bytes(key[8..].try_into()?),
+            ));
+        }
+        Ok(buffer)
+    }
+
+    /// Loads the persisted validator queues
+    pub fn load_queues(&self) -> anyhow::Result<ValidatorQueues> {
+        let proposal_validity_buffer = self.read_schedule(CF_VALIDITY_QUEUE)?;
+        let output_fault_buffer = self.read_schedule(CF_OUTPUT_FAULT_QUEUE)?;
+        let trail_fault_buffer = self.read_schedule(CF_TRAIL_FAULT_QUEUE)?;
+
+        let mut last_proof_l1_head = BTreeMap::new();
+        for entry in self
+            .db
+            .iterator_cf(self.cf(CF_LAST_PROOF_L1_HEAD)?, IteratorMode::Start)
+        {
+            let (key, value) = entry.context("rocksdb::DB::iterator_cf")?;
+            if key.len() != 8 || value.len() != 8 {
+                bail!("Malformed last proof l1 head entry in storage.");
+            }
+            last_proof_l1_head.insert(
+                u64::from_be_bytes(key.as_ref().try_into()?),
+                u64::from_be_bytes(value.as_ref().try_into()?),
+            );
+        }
+
+        let mut computed_proof_buffer = VecDeque::new();
+        for entry in self
+            .db
+            .iterator_cf(self.cf(CF_COMPUTED_PROOFS)?, IteratorMode::Start)
+        {
+            let (_, value) = entry.context("rocksdb::DB::iterator_cf")?;
+            let message: Message =
+                bincode::deserialize(&value).context("Malformed computed proof in storage.")?;
+            computed_proof_buffer.push_back(message);
+        }
+
+        let mut proving_tasks = vec![];
+        for entry in self
+            .db
+            .iterator_cf(self.cf(CF_PROVING_TASKS)?, IteratorMode::Start)
+        {
+            let (_, value) = entry.context("rocksdb::DB::iterator_cf")?;
+            let message: Message =
+                bincode::deserialize(&value).context("Malformed proving task in storage.")?;
+            proving_tasks.push(message);
+        }
+
+        Ok(ValidatorQueues {
+            proposal_validity_buffer,
+            output_fault_buffer,
+            trail_fault_buffer,
+            computed_proof_buffer,
+            last_proof_l1_head,
+            proving_tasks,
+        })
+    }
+
+    /// Atomically persists the changes made to the scheduled proof requests and submissions
+    /// since the persisted snapshot was taken
+    pub fn put_schedules(
+        &self,
+        persisted: &ScheduleSnapshot,
+        current: &ScheduleSnapshot,
+    ) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        self.write_schedule(
+            &mut batch,
+            CF_VALIDITY_QUEUE,
+            &persisted.proposal_validity,
+            &current.proposal_validity,
+        )?;
+        self.write_schedule(
+            &mut batch,
+            CF_OUTPUT_FAULT_QUEUE,
+            &persisted.output_fault,
+            &current.output_fault,
+        )?;
+        self.write_schedule(
+            &mut batch,
+            CF_TRAIL_FAULT_QUEUE,
+            &persisted.trail_fault,
+            &current.trail_fault,
+        )?;
+        let cf_last_proof_l1_head = self.cf(CF_LAST_PROOF_L1_HEAD)?;
+        for index in persisted.last_proof_l1_head.keys() {
+            if !current.last_proof_l1_head.contains_key(index) {
+                batch.delete_cf(cf_last_proof_l1_head, index.to_be_bytes());
+            }
+        }
+        for (index, block_number) in &current.last_proof_l1_head {
+            if persisted.last_proof_l1_head.get(index) != Some(block_number) {
+                batch.put_cf(
+                    cf_last_proof_l1_head,
+                    index.to_be_bytes(),
+                    block_number.to_be_bytes(),
+                );
+            }
+        }
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+
+    /// Atomically persists newly received computed proofs and retires the proving tasks of the
+    /// proposals whose proofs were received
+    pub fn put_computed_proofs<'a>(
+        &self,
+        received_proofs: impl IntoIterator<Item = &'a Message>,
+        completed_tasks: &[u64],
+    ) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        let cf_computed_proofs = self.cf(CF_COMPUTED_PROOFS)?;
+        for message in received_proofs {
+            let Some(key) = computed_proof_key(message) else {
+                bail!("Computed proof is not a proof.");
+            };
+            batch.put_cf(
+                cf_computed_proofs,
+                key,
+                bincode::serialize(message).context("bincode::serialize")?,
+            );
+        }
+        let cf_proving_tasks = self.cf(CF_PROVING_TASKS)?;
+        for index in completed_tasks {
+            batch.delete_range_cf(
+                cf_proving_tasks,
+                index.to_be_bytes(),
+                (index + 1).to_be_bytes(),
+            );
+        }
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+
+    /// Deletes the computed proofs that are no longer awaiting publication
+    pub fn delete_computed_proofs<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a Vec<u8>>,
+    ) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        let cf_computed_proofs = self.cf(CF_COMPUTED_PROOFS)?;
+        for key in keys {
+            batch.delete_cf(cf_computed_proofs, key);
+        }
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+
+    /// Loads the persisted recent proof latencies of regular or escalated proving
+    pub fn load_latencies(&self, escalated: bool) -> anyhow::Result<Vec<u64>> {
+        let key = if escalated {
+            ESCALATED_LATENCY_KEY
+        } else {
+            LATENCY_KEY
+        };
+        let Some(value) = self
+            .db
+            .get_cf(self.cf(CF_META)?, key)
+            .context("rocksdb::DB::get_cf")?
+        else {
+            return Ok(vec![]);
+        };
+        if value.len() % 8 != 0 {
+            bail!("Malformed proof latencies in storage.");
+        }
+        Ok(value
+            .chunks_exact(8)
+            .map(|sample| u64::from_be_bytes(sample.try_into().unwrap()))
+            .collect())
+    }
+
+    /// Persists the recent proof latencies of regular or escalated proving
+    pub fn put_latencies<'a>(
+        &self,
+        escalated: bool,
+        samples: impl IntoIterator<Item = &'a u64>,
+    ) -> anyhow::Result<()> {
+        let key = if escalated {
+            ESCALATED_LATENCY_KEY
+        } else {
+            LATENCY_KEY
+        };
+        let value = samples
+            .into_iter()
+            .flat_map(|sample| sample.to_be_bytes())
+            .collect::<Vec<_>>();
+        self.db
+            .put_cf(self.cf(CF_META)?, key, value)
+            .context("rocksdb::DB::put_cf")
+    }
+
+    /// Persists a proof request handed over to the provers
+    pub fn put_proving_task(&self, file_name: &str, message: &Message) -> anyhow::Result<()> {
+        let Message::Proposal { index, .. } = message else {
+            bail!("Proving task is not a proposal.");
+        };
+        self.db
+            .put_cf(
+                self.cf(CF_PROVING_TASKS)?,
+                [index.to_be_bytes().as_slice(), file_name.as_bytes()].concat(),
+                bincode::serialize(message).context("bincode::serialize")?,
+            )
+            .context("rocksdb::DB::put_cf")
+    }
+
+    /// Deletes all proving tasks of the given proposal
+    pub fn delete_proving_tasks(&self, index: u64) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        batch.delete_range_cf(
+            self.cf(CF_PROVING_TASKS)?,
+            index.to_be_bytes(),
+            (index + 1).to_be_bytes(),
+        );
+        self.db.write(batch).context("rocksdb::DB::write")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn schedule(entries: &[(u64, u64)]) -> BinaryHeap<(Reverse<u64>, u64)> {
+        entries
+            .iter()
+            .map(|(time, index)| (Reverse(*time), *index))
+            .collect()
+    }
+
+    fn proposal(index: u64) -> Message {
+        Message::Proposal {
+            index,
+            precondition_validation_data: None,
+            l1_head: B256::repeat_byte(index as u8),
+            agreed_l2_head_hash: B256::repeat_byte(0x01),
+            agreed_l2_output_root: B256::repeat_byte(0x02),
+            claimed_l2_block_number: index * 10,
+            claimed_l2_output_root: B256::repeat_byte(0x03),
+        }
+    }
+
+    fn proof_indices(buffer: &VecDeque<Message>) -> Vec<u64> {
+        buffer
+            .iter()
+            .filter_map(|message| match message {
+                Message::Proof(index, _) => Some(*index),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_schedules_round_trip() {
+        let dir = tempdir().unwrap();
+        let validity = schedule(&[(10, 1), (20, 2)]);
+        let output_fault = schedule(&[(15, 3)]);
+        let trail_fault = schedule(&[(5, 4)]);
+        let last_proof_l1_head = BTreeMap::from([(1, 100), (3, 300)]);
+        let snapshot =
+            ScheduleSnapshot::new(&validity, &output_fault, &trail_fault, &last_proof_l1_head);
+        {
+            let store = ValidatorStore::open(dir.path()).unwrap();
+            store
+                .put_schedules(&ScheduleSnapshot::default(), &snapshot)
+                .unwrap();
+        }
+
+        // Reopening the store restores the schedules
+        let store = ValidatorStore::open(dir.path()).unwrap();
+        let queues = store.load_queues().unwrap();
+        let loaded = ScheduleSnapshot::new(
+            &queues.proposal_validity_buffer,
+            &queues.output_fault_buffer,
+            &queues.trail_fault_buffer,
+            &queues.last_proof_l1_head,
+        );
+        assert_eq!(loaded, snapshot);
+        assert_eq!(
+            queues.proposal_validity_buffer.peek(),
+            Some(&(Reverse(10), 1))
+        );
+
+        // Only the changes are applied on top of the persisted snapshot
+        let mut validity = validity;
+        validity.pop();
+        validity.push((Reverse(30), 5));
+        let mut last_proof_l1_head = last_proof_l1_head;
+        last_proof_l1_head.remove(&1);
+        last_proof_l1_head.insert(3, 301);
+        let updated = ScheduleSnapshot::new(
+            &validity,
+            &output_fault,
+            &schedule(&[]),
+            &last_proof_l1_head,
+        );
+        store.put_schedules(&snapshot, &updated).unwrap();
+        let queues = store.load_queues().unwrap();
+        let loaded = ScheduleSnapshot::new(
+            &queues.proposal_validity_buffer,
+            &queues.output_fault_buffer,
+            &queues.trail_fault_buffer,
+            &queues.last_proof_l1_head,
+        );
+        assert_eq!(loaded, updated);
+        assert_eq!(loaded.proposal_validity, BTreeSet::from([(20, 2), (30, 5)]));
+        assert!(loaded.trail_fault.is_empty());
+        assert_eq!(loaded.last_proof_l1_head, BTreeMap::from([(3, 301)]));
+    }
+
+    #[test]
+    fn test_proofs_round_trip() {
+        let dir = tempdir().unwrap();
+        let store = ValidatorStore::open(dir.path()).unwrap();
+
+        // Proving tasks are retired once their proofs are received
+        store.put_proving_task("a", &proposal(1)).unwrap();
+        store.put_proving_task("b", &proposal(1)).unwrap();
+        store.put_proving_task("c", &proposal(2)).unwrap();
+        assert!(store.put_proving_task("d", &Message::Escalate(3)).is_err());
+        let received = VecDeque::from([Message::Proof(1, None)]);
+        store.put_computed_proofs(&received, &[1]).unwrap();
+        let queues = store.load_queues().unwrap();
+        assert_eq!(queues.proving_tasks.len(), 1);
+        assert!(matches!(
+            queues.proving_tasks[0],
+            Message::Proposal { index: 2, .. }
+        ));
+        assert_eq!(proof_indices(&queues.computed_proof_buffer), vec![1]);
+
+        // Received proofs are appended without rewriting the pending ones
+        let received = VecDeque::from([Message::Proof(3, None), Message::Proof(2, None)]);
+        store.put_computed_proofs(&received, &[2]).unwrap();
+        let queues = store.load_queues().unwrap();
+        assert!(queues.proving_tasks.is_empty());
+        assert_eq!(proof_indices(&queues.computed_proof_buffer), vec![1, 2, 3]);
+
+        // Published proofs are deleted
+        let pending = computed_proof_keys(&queues.computed_proof_buffer);
+        let remaining = computed_proof_keys(&VecDeque::from([Message::Proof(2, None)]));
+        store
+            .delete_computed_proofs(pending.difference(&remaining))
+            .unwrap();
+        let queues = store.load_queues().unwrap();
+        assert_eq!(proof_indices(&queues.computed_proof_buffer), vec![2]);
+
+        // Proving tasks can be dropped by proposal
+        store.put_proving_task("e", &proposal(4)).unwrap();
+        store.delete_proving_tasks(4).unwrap();
+        assert!(store.load_queues().unwrap().proving_tasks.is_empty());
+    }
+
+    #[test]
+    fn test_bind_treasury() {
+        let dir = tempdir().unwrap();
+        let store = ValidatorStore::open(dir.path()).unwrap();
+        let snapshot = ScheduleSnapshot::new(
+            &schedule(&[(10, 1)]),
+            &schedule(&[]),
+            &schedule(&[]),
+            &BTreeMap::new(),
+        );
+        let treasury = Address::repeat_byte(0x01);
+        store.bind_treasury(treasury).unwrap();
+        store
+            .put_schedules(&ScheduleSnapshot::default(), &snapshot)
+            .unwrap();
+        store.put_proving_task("a", &proposal(1)).unwrap();
+
+        // The queues of the same treasury are kept
+        store.bind_treasury(treasury).unwrap();
+        let queues = store.load_queues().unwrap();
+        assert_eq!(queues.proposal_validity_buffer.len(), 1);
+        assert_eq!(queues.proving_tasks.len(), 1);
+
+        // The queues of another treasury are discarded
+        store.bind_treasury(Address::repeat_byte(0x02)).unwrap();
+        let queues = store.load_queues().unwrap();
+        assert!(queues.proposal_validity_buffer.is_empty());
+        assert!(queues.proving_tasks.is_empty());
+    }
+}
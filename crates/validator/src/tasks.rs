@@ -18,17 +18,21 @@ use futures::FutureExt;
 use kailua_kona::client::core::L1_HEAD_INSUFFICIENT;
 use kailua_prover::args::ProveArgs;
 use kailua_prover::channel::AsyncChannel;
-use kailua_prover::proof::read_bincoded_file;
 use kailua_prover::prove::prove;
+use kailua_prover::risczero::boundless::BoundlessArgs;
+use kailua_prover::store::ProofStore;
 use kailua_sync::await_tel_res;
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt as TeleFutureExt, TraceContextExt, Tracer};
+use risc0_zkvm::Receipt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::future::pending;
 use std::panic::AssertUnwindSafe;
 use std::path::PathBuf;
-use std::time::Duration;
 use tokio::process::Command;
 use tokio::sync::mpsc::Sender;
-use tokio::time::sleep;
+use tokio::sync::watch;
 use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Clone)]
@@ -38,123 +42,217 @@ pub struct Task {
     pub proof_file_name: String,
 }
 
-#[allow(deprecated)]
+/// The result of attempting to complete a proving task
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum TaskOutcome {
+    /// The requested proof was computed
+    Proof(Receipt),
+    /// The proof can not be computed using the task's l1 head
+    InsufficientL1Data,
+    /// The task does not produce a proof to be published
+    Skipped,
+    /// The proof could not be computed or read and the task should be retried
+    Failed,
+}
+
+/// The proving configuration of tasks whose proofs are at risk of missing their deadline
+#[derive(Clone, Debug)]
+pub struct TaskEscalation {
+    /// Local indices of the proposals whose proving tasks were escalated
+    pub escalated: watch::Receiver<BTreeSet<u64>>,
+    /// Boundless configuration to prove escalated tasks with, if any
+    pub boundless: Option<BoundlessArgs>,
+}
+
+impl TaskEscalation {
+    /// Returns true if the proposal's proving task should run on the escalation backend
+    pub fn is_escalated(&self, proposal_index: u64) -> bool {
+        self.boundless.is_some() && self.escalated.borrow().contains(&proposal_index)
+    }
+
+    /// Returns a copy of the task configured to run on the escalation backend
+    pub fn escalate(&self, task: &Task) -> Task {
+        let mut task = task.clone();
+        if let Some(boundless) = &self.boundless {
+            task.prove_args.boundless = boundless.clone();
+        }
+        task
+    }
+
+    /// Completes once the proposal's proving task is escalated
+    pub async fn escalation_of(&mut self, proposal_index: u64) {
+        if self.boundless.is_none()
+            || self
+                .escalated
+                .wait_for(|escalated| escalated.contains(&proposal_index))
+                .await
+                .is_err()
+        {
+            pending::<()>().await;
+        }
+    }
+}
+
 pub async fn handle_proving_tasks(
     kailua_cli: Option<PathBuf>,
     task_channel: AsyncChannel<Task>,
     proof_sender: Sender<Message>,
     verbosity: u8,
+    mut escalation: TaskEscalation,
 ) -> anyhow::Result<()> {
-    let tracer = tracer("kailua");
-    let context = opentelemetry::Context::current_with_span(tracer.start("handle_proving_tasks"));
-
     loop {
-        let Ok(Task {
-            proposal_index,
-            prove_args,
-            proof_file_name,
-        }) = task_channel.1.recv().await
-        else {
+        let Ok(mut task) = task_channel.1.recv().await else {
             // The task queueing channel has been closed so no more work to do
             warn!("handle_proving_tasks terminated");
             break Ok(());
         };
-        info!("Handling proof request for local index {proposal_index}.");
-
-        let insufficient_l1_data = if let Some(kailua_cli) = &kailua_cli {
-            info!("Invoking prover binary.");
-            // Prove (note: dev-mode/bonsai env vars are inherited!)
-            let mut kailua_cli_command = Command::new(kailua_cli);
-            // get fake receipts when building under devnet
-            if risc0_zkvm::is_dev_mode() {
-                kailua_cli_command.env("RISC0_DEV_MODE", "1");
-            }
-            // pass arguments to point at target block
-            kailua_cli_command.args(create_proving_args(&prove_args, verbosity));
-            debug!("kailua_cli_command {:?}", &kailua_cli_command);
-            // call the prover to generate a proof
-            match await_tel_res!(
-                context,
-                tracer,
-                "kailua_cli_command",
-                kailua_cli_command
-                    .kill_on_drop(true)
-                    .spawn()
-                    .context("Invoking prover")?
-                    .wait()
-            ) {
-                Ok(proving_task) => {
-                    if !proving_task.success() {
-                        error!("Proving task failure. Exit code: {proving_task}");
-                    } else {
-                        info!("Proving task successful.");
-                    }
-                    proving_task.code().unwrap_or_default() == 111
-                }
-                Err(e) => {
-                    error!("Failed to invoke prover: {e:?}");
-                    false
-                }
-            }
+        let proposal_index = task.proposal_index;
+
+        // Prove escalated tasks using the escalation backend from the start
+        let outcome = if escalation.is_escalated(proposal_index) {
+            task = escalation.escalate(&task);
+            info!("Proving escalated task for local index {proposal_index}.");
+            run_proving_task(&kailua_cli, &task, verbosity).await?
         } else {
-            info!("Proving internally.");
-            // catch any proving errors
-            let result_fut = async {
-                match await_tel_res!(context, tracer, "prove", prove(prove_args.clone())) {
-                    Ok(_) => false,
-                    Err(err) => {
-                        error!("Prover encountered error: {err:?}");
-                        err.root_cause().to_string().contains(L1_HEAD_INSUFFICIENT)
-                    }
-                }
+            let outcome = tokio::select! {
+                outcome = run_proving_task(&kailua_cli, &task, verbosity) => Some(outcome?),
+                _ = escalation.escalation_of(proposal_index) => None,
             };
-            // catch panics
-            AssertUnwindSafe(result_fut)
-                .catch_unwind()
-                .await
-                .unwrap_or_else(|err| {
-                    error!("Prover panicked! {err:?}");
-                    false
-                })
+            match outcome {
+                Some(outcome) => outcome,
+                None => {
+                    // Abandon the ongoing proving attempt
+                    warn!("Restarting proving task for local index {proposal_index} on escalation backend.");
+                    task = escalation.escalate(&task);
+                    run_proving_task(&kailua_cli, &task, verbosity).await?
+                }
+            }
         };
-
-        // we do not get a stitched proof w/o all proofs
-        if !insufficient_l1_data && prove_args.proving.skip_stitching() {
-            info!("Skipping proving task.");
-            continue;
-        }
-
-        // wait for io then read computed proof from disk
-        sleep(Duration::from_secs(1)).await;
-        match read_bincoded_file(&proof_file_name).await {
-            Ok(proof) => {
+        match outcome {
+            TaskOutcome::Proof(proof) => {
                 // Send proof via the channel
                 proof_sender
                     .send(Message::Proof(proposal_index, Some(proof)))
                     .await?;
                 info!("Proof for local index {proposal_index} complete.");
             }
-            Err(e) => {
-                error!("Failed to read proof file: {e:?}");
-                if insufficient_l1_data {
-                    // Complain about unprovability
-                    proof_sender
-                        .send(Message::Proof(proposal_index, None))
-                        .await?;
-                    warn!("Cannot prove local index {proposal_index} due to insufficient l1 head.");
+            TaskOutcome::InsufficientL1Data => {
+                // Complain about unprovability
+                proof_sender
+                    .send(Message::Proof(proposal_index, None))
+                    .await?;
+                warn!("Cannot prove local index {proposal_index} due to insufficient l1 head.");
+            }
+            TaskOutcome::Skipped => {}
+            TaskOutcome::Failed => {
+                // retry proving task
+                info!("Resubmitting proving task for local index {proposal_index}.");
+                task_channel
+                    .0
+                    .send(task)
+                    .await
+                    .context("task channel closed")?;
+            }
+        }
+    }
+}
+
+/// Computes the proof requested by the task, either internally or using the prover binary
+#[allow(deprecated)]
+pub async fn run_proving_task(
+    kailua_cli: &Option<PathBuf>,
+    task: &Task,
+    verbosity: u8,
+) -> anyhow::Result<TaskOutcome> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("run_proving_task"));
+
+    let Task {
+        proposal_index,
+        prove_args,
+        proof_file_name,
+    } = task;
+    info!("Handling proof request for local index {proposal_index}.");
+
+    let insufficient_l1_data = if let Some(kailua_cli) = kailua_cli {
+        info!("Invoking prover binary.");
+        // Prove (note: dev-mode/bonsai env vars are inherited!)
+        let mut kailua_cli_command = Command::new(kailua_cli);
+        // get fake receipts when building under devnet
+        if risc0_zkvm::is_dev_mode() {
+            kailua_cli_command.env("RISC0_DEV_MODE", "1");
+        }
+        // pass arguments to point at target block
+        kailua_cli_command.args(create_proving_args(prove_args, verbosity));
+        // keep proof store credentials out of the process listing
+        kailua_cli_command.envs(prove_args.proving.proof_store.to_env_vec());
+        debug!("kailua_cli_command {:?}", &kailua_cli_command);
+        // call the prover to generate a proof
+        match await_tel_res!(
+            context,
+            tracer,
+            "kailua_cli_command",
+            kailua_cli_command
+                .kill_on_drop(true)
+                .spawn()
+                .context("Invoking prover")?
+                .wait()
+        ) {
+            Ok(proving_task) => {
+                if !proving_task.success() {
+                    error!("Proving task failure. Exit code: {proving_task}");
                 } else {
-                    // retry proving task
-                    info!("Resubmitting proving task for local index {proposal_index}.");
-                    task_channel
-                        .0
-                        .send(Task {
-                            proposal_index,
-                            prove_args,
-                            proof_file_name,
-                        })
-                        .await
-                        .context("task channel closed")?;
+                    info!("Proving task successful.");
                 }
+                proving_task.code().unwrap_or_default() == 111
+            }
+            Err(e) => {
+                error!("Failed to invoke prover: {e:?}");
+                false
+            }
+        }
+    } else {
+        info!("Proving internally.");
+        // catch any proving errors
+        let result_fut = async {
+            match await_tel_res!(context, tracer, "prove", prove(prove_args.clone())) {
+                Ok(_) => false,
+                Err(err) => {
+                    error!("Prover encountered error: {err:?}");
+                    err.root_cause().to_string().contains(L1_HEAD_INSUFFICIENT)
+                }
+            }
+        };
+        // catch panics
+        AssertUnwindSafe(result_fut)
+            .catch_unwind()
+            .await
+            .unwrap_or_else(|err| {
+                error!("Prover panicked! {err:?}");
+                false
+            })
+    };
+
+    // we do not get a stitched proof w/o all proofs
+    if !insufficient_l1_data && prove_args.proving.skip_stitching() {
+        info!("Skipping proving task.");
+        return Ok(TaskOutcome::Skipped);
+    }
+
+    // read computed proof from the store
+    let proof = match ProofStore::new(&prove_args.proving.proof_store).await {
+        Ok(store) => store.load(proof_file_name).await,
+        Err(err) => Err(err.context("ProofStore::new")),
+    };
+    match proof {
+        Ok(proof) => Ok(TaskOutcome::Proof(proof)),
+        Err(e) => {
+            error!("Failed to read proof file: {e:?}");
+            if insufficient_l1_data {
+                Ok(TaskOutcome::InsufficientL1Data)
+            } else {
+                Ok(TaskOutcome::Failed)
             }
         }
     }
@@ -14,11 +14,13 @@
 
 use crate::args::ValidateArgs;
 use crate::channel::DuplexChannel;
+use crate::store::ValidatorStore;
 use crate::{proposals, requests};
 use anyhow::Context;
 use opentelemetry::global::tracer;
 use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::{spawn, try_join};
 use tracing::warn;
 
@@ -40,16 +42,33 @@ pub async fn validate(
         args.proving.skip_derivation_proof = false;
     }
 
+    // Only report faults when running without a wallet
+    if args.watch_only {
+        return proposals::watch::watch_proposals(args, data_dir)
+            .with_context(context.clone())
+            .await
+            .context("watch_proposals");
+    }
+
     // We run two concurrent tasks, one for the chain, and one for the prover.
     // Both tasks communicate using the duplex channel
     let channel_pair = DuplexChannel::new_pair(4096);
+    // Both tasks persist their proving queues to survive restarts
+    let store = Arc::new(
+        ValidatorStore::open(&data_dir.join("validator")).context("ValidatorStore::open")?,
+    );
 
     let handle_proposals = spawn(
-        proposals::handle_proposals(channel_pair.0, args.clone(), data_dir.clone())
-            .with_context(context.clone()),
+        proposals::handle_proposals(
+            channel_pair.0,
+            args.clone(),
+            data_dir.clone(),
+            store.clone(),
+        )
+        .with_context(context.clone()),
     );
     let handle_proof_requests = spawn(
-        requests::handle_proof_requests(channel_pair.1, args, verbosity, data_dir)
+        requests::handle_proof_requests(channel_pair.1, args, verbosity, data_dir, Some(store))
             .with_context(context.clone()),
     );
 
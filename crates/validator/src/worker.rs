@@ -0,0 +1,210 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::coordinator::{ProvingQueueApiClient, TaskLease, MAX_MESSAGE_SIZE};
+use crate::tasks::{run_proving_task, Task, TaskOutcome};
+use anyhow::Context;
+use clap::Parser;
+use futures::future::try_join_all;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use kailua_prover::args::ProveArgs;
+use kailua_prover::risczero::boundless::BoundlessArgs;
+use kailua_prover::store::ProofStoreArgs;
+use kailua_sync::telemetry::TelemetryArgs;
+use opentelemetry::global::tracer;
+use opentelemetry::trace::{FutureExt, TraceContextExt, Tracer};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::spawn;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+/// Lease proving tasks from a validator's coordinator and compute them on this machine
+#[derive(clap::Args, Debug, Clone)]
+pub struct WorkerArgs {
+    /// Address of the validator's proving coordinator endpoint
+    #[clap(long, env)]
+    pub coordinator_url: String,
+    /// Secret token to present to the coordinator
+    #[clap(long, env)]
+    pub coordinator_auth_token: String,
+    /// Name identifying this worker in the coordinator's logs (Default: worker-<pid>)
+    #[clap(long, env)]
+    pub worker_name: Option<String>,
+
+    /// Path to the prover binary to use for proving
+    #[clap(long, env)]
+    pub kailua_cli: Option<PathBuf>,
+    /// How many proofs to compute simultaneously
+    #[clap(long, env, default_value_t = 1)]
+    pub num_concurrent_provers: u64,
+    /// Directory under which to cache proving data instead of the validator's paths
+    #[clap(long, env)]
+    pub data_dir: Option<PathBuf>,
+    /// Seconds to wait before polling an empty proving queue again
+    #[clap(long, env, default_value_t = 5)]
+    pub lease_poll_interval: u64,
+    /// Seconds between heartbeats keeping a leased task assigned to this worker
+    #[clap(long, env, default_value_t = 30)]
+    pub heartbeat_interval: u64,
+
+    #[clap(flatten)]
+    pub boundless: BoundlessArgs,
+    #[clap(flatten)]
+    pub proof_store: ProofStoreArgs,
+
+    #[clap(flatten)]
+    pub telemetry: TelemetryArgs,
+}
+
+pub async fn prove_worker(args: WorkerArgs, verbosity: u8) -> anyhow::Result<()> {
+    let tracer = tracer("kailua");
+    let context = opentelemetry::Context::current_with_span(tracer.start("prove_worker"));
+
+    let client = HttpClientBuilder::default()
+        .max_request_size(MAX_MESSAGE_SIZE)
+        .max_response_size(MAX_MESSAGE_SIZE)
+        .build(&args.coordinator_url)
+        .context("HttpClientBuilder::build")?;
+    let worker_name = args
+        .worker_name
+        .clone()
+        .unwrap_or_else(|| format!("worker-{}", std::process::id()));
+    info!(
+        "Worker {worker_name} leasing proving tasks from {}.",
+        args.coordinator_url
+    );
+
+    let mut proving_handlers = vec![];
+    for i in 0..args.num_concurrent_provers {
+        proving_handlers.push(spawn(
+            handle_leased_tasks(
+                client.clone(),
+                args.clone(),
+                format!("{worker_name}/{i}"),
+                verbosity,
+            )
+            .with_context(context.clone()),
+        ));
+    }
+    for result in try_join_all(proving_handlers).await? {
+        result.context("handle_leased_tasks")?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_leased_tasks(
+    client: HttpClient,
+    args: WorkerArgs,
+    worker_name: String,
+    verbosity: u8,
+) -> anyhow::Result<()> {
+    let poll_interval = Duration::from_secs(args.lease_poll_interval);
+    loop {
+        // lease the next task
+        let lease = match client
+            .lease_task(args.coordinator_auth_token.clone(), worker_name.clone())
+            .await
+        {
+            Ok(Some(lease)) => lease,
+            Ok(None) => {
+                sleep(poll_interval).await;
+                continue;
+            }
+            Err(err) => {
+                error!("Failed to lease proving task: {err:?}");
+                sleep(poll_interval).await;
+                continue;
+            }
+        };
+        let lease_id = lease.lease_id;
+        info!(
+            "Leased proving task for local index {} under lease {lease_id}.",
+            lease.proposal_index
+        );
+
+        // compute the proof while keeping the lease alive
+        let outcome = match leased_task(&args, lease) {
+            Ok(task) => tokio::select! {
+                outcome = run_proving_task(&args.kailua_cli, &task, verbosity) => {
+                    outcome.unwrap_or_else(|err| {
+                        error!("Proving task failed: {err:?}");
+                        TaskOutcome::Failed
+                    })
+                }
+                _ = keep_alive(&client, &args, lease_id) => {
+                    warn!("Abandoning proving task of revoked lease {lease_id}.");
+                    continue;
+                }
+            },
+            Err(err) => {
+                error!("Failed to parse leased proving task: {err:?}");
+                TaskOutcome::Failed
+            }
+        };
+
+        // report the result
+        match client
+            .complete_task(args.coordinator_auth_token.clone(), lease_id, outcome)
+            .await
+        {
+            Ok(true) => info!("Completed lease {lease_id}."),
+            Ok(false) => warn!("Coordinator discarded result of lease {lease_id}."),
+            Err(err) => error!("Failed to complete lease {lease_id}: {err:?}"),
+        }
+    }
+}
+
+/// Reconstructs the proving task from the leased prover arguments and the local Boundless and
+/// proof store configurations
+fn leased_task(args: &WorkerArgs, lease: TaskLease) -> anyhow::Result<Task> {
+    let mut prove_args =
+        ProveArgs::try_parse_from(&lease.prove_args).context("ProveArgs::try_parse_from")?;
+    prove_args.boundless = args.boundless.clone();
+    prove_args.proving.proof_store = args.proof_store.clone();
+    // relocate the task's cache directory under the local data directory
+    if let Some(data_dir) = &args.data_dir {
+        prove_args.kona.data_dir = prove_args
+            .kona
+            .data_dir
+            .as_ref()
+            .and_then(|task_dir| task_dir.file_name())
+            .map(|task_dir| data_dir.join(task_dir));
+        // keep stored proofs under the local data directory as well
+        if prove_args.proving.proof_store.proof_store_dir.is_none() {
+            prove_args.proving.proof_store.proof_store_dir = Some(data_dir.join("proofs"));
+        }
+    }
+    Ok(Task {
+        proposal_index: lease.proposal_index,
+        prove_args,
+        proof_file_name: lease.proof_file_name,
+    })
+}
+
+/// Sends heartbeats for the lease until the coordinator revokes it
+async fn keep_alive(client: &HttpClient, args: &WorkerArgs, lease_id: u64) {
+    loop {
+        sleep(Duration::from_secs(args.heartbeat_interval)).await;
+        match client
+            .heartbeat(args.coordinator_auth_token.clone(), lease_id)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => warn!("Failed to send heartbeat for lease {lease_id}: {err:?}"),
+        }
+    }
+}